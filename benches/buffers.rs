@@ -0,0 +1,84 @@
+/*!
+Compares the default search buffer handling against a preset search
+buffer capacity on a stream of many small chunks. Before timing, prints
+the number of allocations each configuration makes, as counted by a
+wrapper around the system allocator.
+*/
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    io::Cursor,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use regex_chunker::{ByteChunker, GrowthPolicy};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/* About 1 MiB of short comma-separated fields. */
+fn text() -> Vec<u8> {
+    b"field,another field,x,a somewhat longer field than the others,".repeat(16 * 1024)
+}
+
+type Chunker<'a> = ByteChunker<Cursor<&'a [u8]>>;
+
+/* Sets a chunker up for one of the configurations being compared. */
+type Configure = fn(Chunker) -> Chunker;
+
+fn chunk(text: &[u8], configure: Configure) {
+    let chunker = configure(ByteChunker::new(Cursor::new(text), ",").unwrap());
+    for chunk in chunker {
+        black_box(chunk.unwrap());
+    }
+}
+
+fn small_chunks(c: &mut Criterion) {
+    let text = text();
+    let configs: [(&str, Configure); 3] = [
+        ("default", |chunker| chunker),
+        ("capacity", |chunker| chunker.with_search_capacity(4096)),
+        ("capacity_fixed", |chunker| {
+            chunker
+                .with_search_capacity(4096)
+                .with_growth_policy(GrowthPolicy::Fixed(1024))
+        }),
+    ];
+
+    for (name, configure) in configs {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        chunk(&text, configure);
+        let count = ALLOCATIONS.load(Ordering::Relaxed) - before;
+        println!("small_chunks/{}: {} allocations", name, count);
+    }
+
+    let mut group = c.benchmark_group("small_chunks");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    for (name, configure) in configs {
+        group.bench_function(name, |b| b.iter(|| chunk(&text, configure)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, small_chunks);
+criterion_main!(benches);
@@ -0,0 +1,58 @@
+/*!
+Compares chunking on a literal delimiter (which `ByteChunker::new` searches
+for with `memchr`) against chunking on the same delimiter as a `Regex`
+(which `ByteChunker::with_regex` always hands to the regex engine).
+*/
+use std::io::Cursor;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use regex::bytes::Regex;
+use regex_chunker::ByteChunker;
+
+/* About 4 MiB of lines of varying length. */
+fn text() -> Vec<u8> {
+    let mut text = Vec::new();
+    let mut n: usize = 0;
+    while text.len() < 4 * 1024 * 1024 {
+        n = n.wrapping_mul(1103515245).wrapping_add(12345);
+        let len = 20 + (n >> 8) % 100;
+        text.resize(text.len() + len, b'x');
+        text.extend_from_slice(b"\r\n");
+    }
+    text
+}
+
+fn newlines(c: &mut Criterion) {
+    let text = text();
+    let mut group = c.benchmark_group("newlines");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+
+    for pattern in ["\n", "\r\n"] {
+        group.bench_with_input(
+            BenchmarkId::new("literal", pattern.escape_debug()),
+            pattern,
+            |b, p| {
+                b.iter(|| {
+                    ByteChunker::new(Cursor::new(black_box(&text)), p)
+                        .unwrap()
+                        .count()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("regex", pattern.escape_debug()),
+            pattern,
+            |b, p| {
+                b.iter(|| {
+                    ByteChunker::with_regex(Cursor::new(black_box(&text)), Regex::new(p).unwrap())
+                        .with_rescan_window(p.len())
+                        .count()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, newlines);
+criterion_main!(benches);
@@ -0,0 +1,114 @@
+/*!
+Measures the throughput of the sync `ByteChunker` over a few kinds of
+input with each `MatchDisposition`, so that changes to the scanning path
+can show they don't make it slower:
+
+  * `log`: about 4 MiB of log-like lines, delimited by `\n`.
+  * `binary`: about 4 MiB of pseudorandom bytes with a rare four-byte
+    delimiter every 64 KiB or so.
+  * `tiny`: about 1 MiB of one- to four-byte fields delimited by `,`.
+*/
+use std::io::Cursor;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use regex_chunker::{ByteChunker, MatchDisposition};
+
+const MIB: usize = 1024 * 1024;
+
+/* A small linear congruential generator, so the inputs are the same
+every run without pulling in a random number crate. */
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+}
+
+fn log_text() -> Vec<u8> {
+    const LEVELS: [&str; 4] = ["INFO", "DEBUG", "WARN", "ERROR"];
+    let mut rng = Lcg(1);
+    let mut text = Vec::with_capacity(4 * MIB + 256);
+    let mut n = 0u64;
+    while text.len() < 4 * MIB {
+        let level = LEVELS[(rng.next() % 4) as usize];
+        let line = format!(
+            "2023-08-{:02}T12:{:02}:{:02}Z {} worker[{}]: request {} took {} ms\n",
+            1 + n % 28,
+            n % 60,
+            (n / 60) % 60,
+            level,
+            rng.next() % 16,
+            n,
+            rng.next() % 1000,
+        );
+        text.extend_from_slice(line.as_bytes());
+        n += 1;
+    }
+    text
+}
+
+const BINARY_DELIM: &[u8] = b"\xfe\x00\xfe\x01";
+
+fn binary_text() -> Vec<u8> {
+    let mut rng = Lcg(2);
+    let mut text = Vec::with_capacity(4 * MIB + 64 * 1024);
+    while text.len() < 4 * MIB {
+        let len = 32 * 1024 + (rng.next() as usize) % (64 * 1024);
+        /* Values below 0xfe, so the delimiter can't show up by accident. */
+        text.extend((0..len).map(|_| (rng.next() % 0xfe) as u8));
+        text.extend_from_slice(BINARY_DELIM);
+    }
+    text
+}
+
+fn tiny_text() -> Vec<u8> {
+    let mut rng = Lcg(3);
+    let mut text = Vec::with_capacity(MIB + 8);
+    while text.len() < MIB {
+        let len = 1 + (rng.next() as usize) % 4;
+        text.extend((0..len).map(|_| b'a' + (rng.next() % 26) as u8));
+        text.push(b',');
+    }
+    text
+}
+
+fn chunk(text: &[u8], pattern: &str, dispo: MatchDisposition) {
+    let chunker = ByteChunker::new(Cursor::new(text), pattern)
+        .unwrap()
+        .with_match(dispo);
+    for chunk in chunker {
+        black_box(chunk.unwrap());
+    }
+}
+
+fn throughput(c: &mut Criterion) {
+    let inputs = [
+        ("log", log_text(), r"\n"),
+        ("binary", binary_text(), r"(?-u)\xfe\x00\xfe\x01"),
+        ("tiny", tiny_text(), ","),
+    ];
+    let dispos = [
+        ("drop", MatchDisposition::Drop),
+        ("append", MatchDisposition::Append),
+        ("prepend", MatchDisposition::Prepend),
+    ];
+
+    for (name, text, pattern) in inputs.iter() {
+        let mut group = c.benchmark_group(*name);
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        for (dispo_name, dispo) in dispos {
+            group.bench_with_input(BenchmarkId::from_parameter(dispo_name), &dispo, |b, &d| {
+                b.iter(|| chunk(black_box(text), pattern, d))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, throughput);
+criterion_main!(benches);
@@ -1,210 +1,1432 @@
-/*!
-The trait used for types that transform the output of a Chunker.
-*/
-use crate::{
-    ctrl::Utf8FailureMode,
-    RcErr,
-};
-
-/**
-Trait used to implement a [`CustomChunker`](crate::CustomChunker) by
-transforming the output of a [`ByteChunker`](crate::ByteChunker).
-
-This is more powerful than simply calling 
-[`.map()`](https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.map),
-[`.map_while()`](https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.map_while),
-or [`.filter_map()`](https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.filter_map)
-on a `ByteChunker` because the type implementing `Adapter` can be _stateful_.
-
-The example below shows a struct implementing `Adapter` to count the number of
-chunks returned so far.
-
-```rust
-use regex_chunker::{Adapter, ByteChunker, RcErr};
-use std::io::Cursor;
-
-struct ChunkCounter {
-    lines: usize,
-}
-
-impl Adapter for ChunkCounter {
-    type Item = Result<Vec<u8>, RcErr>;
-
-    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
-        match v {
-            Some(Ok(v)) => {
-                self.lines += 1;
-                Some(Ok(v))
-            },
-            x => x,
-        }
-    }
-}
-
-let text =
-br#"What's he that wishes so?
-My cousin Westmoreland? No, my fair cousin:
-If we are mark'd to die, we are enow
-To do our country loss; and if to live,
-The fewer men, the greater share of honour."#;
-
-let c = Cursor::new(text);
-
-let mut chunker = ByteChunker::new(c, r#"\r?\n"#)?
-    .with_adapter(ChunkCounter { lines: 0 });
-
-let _: Vec<String> = (&mut chunker).map(|res| {
-    let v: Vec<u8> = res.unwrap();
-    String::from_utf8(v).unwrap()
-}).collect();
-
-// Prints "5".
-println!("{}", &chunker.get_adapter().lines);
-# Ok::<(), RcErr>(())
-```
-
-*/
-pub trait Adapter {
-    /// The type into which it transforms the values returned by the
-    /// [`ByteChunker`](crate::ByteChunker)'s `Iterator` implementation.
-    type Item;
-
-    /// Convert the `ByteChunker`'s output.
-    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item>;
-}
-
-/**
-Simpler, less flexible, version of the [`Adapter`] trait.
-
-Can be used in situations where it suffices to just pass `None` and `Err()`
-values through and only operate when the inner
-[`ByteChunker`](crate::ByteChunker)'s `.next()` returns `Some(Ok(vec))`.
-
-This is less powerful than just using
-[`.map()`](https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.map),
-_et. al._, but simpler because there's no error handling required by
-the custom type.
-
-The [`StringAdapter`] type tracks error status, but we can implement a
-simpler type that just performs lossy UTF-8 conversion.
-
-```rust
-# use regex_chunker::RcErr;
-use regex_chunker::{ByteChunker, SimpleAdapter};
-use std::io::Cursor;
-
-struct LossyStringAdapter {}
-
-impl SimpleAdapter for LossyStringAdapter {
-    type Item = String;
-
-    fn adapt(&mut self, v: Vec<u8>) -> Self::Item {
-        String::from_utf8_lossy(&v).into()
-    }
-}
-
-let text = b"One, two, three four. Can I have a little more?";
-let c = Cursor::new(text);
-
-let chunks: Vec<_> = ByteChunker::new(c, "[ .,?]+")?
-    .with_simple_adapter(LossyStringAdapter{})
-    .map(|res| res.unwrap())
-    .collect();
-
-assert_eq!(
-    &chunks,
-    &["One", "two", "three", "four", "Can", "I", "have", "a", "little", "more"].clone()
-);
-# Ok::<(), RcErr>(())
-```
-}
-*/
-pub trait SimpleAdapter {
-    /// The type into which it converts the `Vec<u8>`s successfully produced
-    /// by the underlying [`ByteChunker`](crate::ByteChunker)'s  `Iterator`
-    /// implementation.
-    type Item;
-
-    /// Convert the `ByteChunker`'s output when _successful_.
-    fn adapt(&mut self, v: Vec<u8>) -> Self::Item;
-}
-
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
-enum Utf8ErrorStatus {
-    #[default]
-    Ok,
-    Errored,
-    Lossy,
-    Continue,
-}
-impl Eq for Utf8ErrorStatus {}
-
-/**
-An example [`Adapter`] type for producing a chunker that yields `String`s.
-
-```rust
-# use std::error::Error;
-# fn main() -> Result<(), Box<dyn Error>> {
-    use regex_chunker::{ByteChunker, StringAdapter};
-    use std::io::Cursor;
-
-    let text = b"One, two, three four. Can I have a little more?";
-    let c = Cursor::new(text);
-
-    let chunks: Vec<_> = ByteChunker::new(c, "[ .,?]+")?
-        .with_adapter(StringAdapter::default())
-        .map(|res| res.unwrap())
-        .collect();
-
-    assert_eq!(
-        &chunks,
-        &[
-            "One", "two", "three", "four",
-            "Can", "I", "have", "a", "little", "more"
-        ].clone()
-    );
-#   Ok(()) }
-```
-
-*/
-#[derive(Debug, Default)]
-pub struct StringAdapter {
-    status: Utf8ErrorStatus,
-}
-
-impl StringAdapter {
-    pub fn new(mode: Utf8FailureMode) -> Self {
-        let status = match mode {
-            Utf8FailureMode::Fatal => Utf8ErrorStatus::Ok,
-            Utf8FailureMode::Lossy => Utf8ErrorStatus::Lossy,
-            Utf8FailureMode::Continue => Utf8ErrorStatus::Continue,
-        };
-
-        Self { status }
-    }
-}
-
-impl Adapter for StringAdapter {
-    type Item = Result<String, RcErr>;
-
-    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
-        match (self.status, v) {
-            (Utf8ErrorStatus::Errored, _) => None,
-            (_, None) => None,
-            (_, Some(Err(e))) => Some(Err(e)),
-            (Utf8ErrorStatus::Lossy, Some(Ok(v))) =>
-                Some(Ok(String::from_utf8_lossy(&v).into())),
-            (Utf8ErrorStatus::Ok, Some(Ok(v))) => match String::from_utf8(v) {
-                Ok(s) => Some(Ok(s)),
-                Err(e) => {
-                    self.status = Utf8ErrorStatus::Errored;
-                    Some(Err(e.into()))
-                },
-            },
-            (Utf8ErrorStatus::Continue, Some(Ok(v))) => match String::from_utf8(v) {
-                Ok(s) => Some(Ok(s)),
-                Err(e) => Some(Err(e.into())),
-            }
-        }
-    }
-}
\ No newline at end of file
+/*!
+The trait used for types that transform the output of a Chunker.
+*/
+use std::borrow::Cow;
+
+use regex::bytes::Regex;
+
+use crate::{
+    ctrl::Utf8FailureMode,
+    RcErr,
+};
+
+/**
+Trait used to implement a [`CustomChunker`](crate::CustomChunker) by
+transforming the output of a [`ByteChunker`](crate::ByteChunker).
+
+This is more powerful than simply calling 
+[`.map()`](https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.map),
+[`.map_while()`](https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.map_while),
+or [`.filter_map()`](https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.filter_map)
+on a `ByteChunker` because the type implementing `Adapter` can be _stateful_.
+
+The example below shows a struct implementing `Adapter` to count the number of
+chunks returned so far.
+
+```rust
+use regex_chunker::{Adapter, ByteChunker, RcErr};
+use std::io::Cursor;
+
+struct ChunkCounter {
+    lines: usize,
+}
+
+impl Adapter for ChunkCounter {
+    type Item = Result<Vec<u8>, RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        match v {
+            Some(Ok(v)) => {
+                self.lines += 1;
+                Some(Ok(v))
+            },
+            x => x,
+        }
+    }
+}
+
+let text =
+br#"What's he that wishes so?
+My cousin Westmoreland? No, my fair cousin:
+If we are mark'd to die, we are enow
+To do our country loss; and if to live,
+The fewer men, the greater share of honour."#;
+
+let c = Cursor::new(text);
+
+let mut chunker = ByteChunker::new(c, r#"\r?\n"#)?
+    .with_adapter(ChunkCounter { lines: 0 });
+
+let _: Vec<String> = (&mut chunker).map(|res| {
+    let v: Vec<u8> = res.unwrap();
+    String::from_utf8(v).unwrap()
+}).collect();
+
+// Prints "5".
+println!("{}", &chunker.get_adapter().lines);
+# Ok::<(), RcErr>(())
+```
+
+An adapter that sometimes needs to consume a chunk without producing
+anything (like [`FilterAdapter`] or [`GroupAdapter`]) should also
+implement [`Adapter::act`], and return [`AdapterAction::Skip`] from it to
+be handed the next value.
+*/
+pub trait Adapter {
+    /// The type into which it transforms the values returned by the
+    /// [`ByteChunker`](crate::ByteChunker)'s `Iterator` implementation.
+    type Item;
+
+    /// Convert the `ByteChunker`'s output.
+    ///
+    /// Called on its own, this can't ask for another value, so an adapter
+    /// that overrides [`Adapter::act`] to skip some values returns `None`
+    /// for them here.
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item>;
+
+    /// Convert the `ByteChunker`'s output, or ask for more of it. Unlike
+    /// [`Adapter::adapt`], this can consume a value without producing
+    /// anything, by returning [`AdapterAction::Skip`], so it's what an
+    /// adapter that filters or combines chunks should implement.
+    ///
+    /// The default implementation calls [`Adapter::adapt`], turning
+    /// `Some(item)` into [`AdapterAction::Emit`] and `None` into
+    /// [`AdapterAction::Done`].
+    fn act(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> AdapterAction<Self::Item> {
+        self.adapt(v).into()
+    }
+
+    /// Like [`Adapter::act`], but also given the text of the delimiter
+    /// that ended the chunk, for adapters that need to know which
+    /// delimiter it was (like protocol parsers that branch on how a
+    /// message was terminated). This is what a
+    /// [`CustomChunker`](crate::CustomChunker) calls. The `delimiter` is
+    /// `None` for anything but a chunk, for the final chunk at the end of
+    /// the source, and for a chunk cut short by
+    /// [`ByteChunker::with_max_chunk_size`](crate::ByteChunker::with_max_chunk_size),
+    /// as with [`ByteChunker::with_delimiters`](crate::ByteChunker::with_delimiters).
+    /// The async [`stream::CustomChunker`](crate::stream::CustomChunker)
+    /// calls it the same way.
+    ///
+    /// The chunker only keeps track of its delimiters for an adapter
+    /// whose [`Adapter::wants_delimiters`] returns `true`; otherwise the
+    /// `delimiter` is always `None`. The default implementation ignores
+    /// the delimiter and calls [`Adapter::act`].
+    ///
+    /// ```rust
+    /// # use regex_chunker::RcErr;
+    /// use regex_chunker::{Adapter, AdapterAction, ByteChunker};
+    /// use std::io::Cursor;
+    ///
+    /// /* Tells which lines ended with a backslash continuation. */
+    /// struct Continued;
+    ///
+    /// impl Adapter for Continued {
+    ///     type Item = Result<(Vec<u8>, bool), RcErr>;
+    ///
+    ///     /* Without a delimiter to go by, nothing's continued. */
+    ///     fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+    ///         v.map(|res| res.map(|v| (v, false)))
+    ///     }
+    ///
+    ///     fn wants_delimiters(&self) -> bool { true }
+    ///
+    ///     fn adapt_with_delim(
+    ///         &mut self,
+    ///         v: Option<Result<Vec<u8>, RcErr>>,
+    ///         delimiter: Option<&[u8]>,
+    ///     ) -> AdapterAction<Self::Item> {
+    ///         let continued = delimiter == Some(b"\\\n");
+    ///         v.map(|res| res.map(|v| (v, continued))).into()
+    ///     }
+    /// }
+    ///
+    /// let text = b"one \\\ntwo\nthree";
+    /// let lines: Vec<(Vec<u8>, bool)> = ByteChunker::new(Cursor::new(text), r"\\?\n")?
+    ///     .with_adapter(Continued)
+    ///     .collect::<Result<_, _>>()?;
+    ///
+    /// assert_eq!(
+    ///     &lines,
+    ///     &[(b"one ".to_vec(), true), (b"two".to_vec(), false), (b"three".to_vec(), false)]
+    /// );
+    /// # Ok::<(), RcErr>(())
+    /// ```
+    fn adapt_with_delim(
+        &mut self,
+        v: Option<Result<Vec<u8>, RcErr>>,
+        delimiter: Option<&[u8]>,
+    ) -> AdapterAction<Self::Item> {
+        let _ = delimiter;
+        self.act(v)
+    }
+
+    /// Whether [`Adapter::adapt_with_delim`] should be given the text of
+    /// each delimiter. Copying the delimiters out of the chunker's buffer
+    /// costs something, so the default is `false`; an adapter that
+    /// overrides `adapt_with_delim` to look at them should return `true`.
+    fn wants_delimiters(&self) -> bool {
+        false
+    }
+
+    /// Make an [`AndThenAdapter`] that feeds this adapter's output to
+    /// `next`. This adapter has to produce what a chunker does (that is,
+    /// `Result<Vec<u8>, RcErr>`s) for that to work.
+    fn and_then<B: Adapter>(self, next: B) -> AndThenAdapter<Self, B>
+    where
+        Self: Sized,
+    {
+        AndThenAdapter::new(self, next)
+    }
+}
+
+/**
+What an [`Adapter`] does with a value from its chunker (returned by
+[`Adapter::act`]).
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdapterAction<T> {
+    /// Yield this item.
+    Emit(T),
+    /// Yield nothing yet; feed the adapter the chunker's next value.
+    Skip,
+    /// Stop iterating.
+    Done,
+}
+
+/// `Some(item)` becomes [`AdapterAction::Emit`], and `None` becomes
+/// [`AdapterAction::Done`], which is what an [`Adapter::adapt`]
+/// implementation means by them.
+impl<T> From<Option<T>> for AdapterAction<T> {
+    fn from(opt: Option<T>) -> Self {
+        match opt {
+            Some(item) => AdapterAction::Emit(item),
+            None => AdapterAction::Done,
+        }
+    }
+}
+
+/// [`AdapterAction::Emit`] becomes `Some(item)`, and anything else
+/// becomes `None`, for an adapter whose [`Adapter::act`] is called
+/// through [`Adapter::adapt`].
+impl<T> From<AdapterAction<T>> for Option<T> {
+    fn from(action: AdapterAction<T>) -> Self {
+        match action {
+            AdapterAction::Emit(item) => Some(item),
+            AdapterAction::Skip | AdapterAction::Done => None,
+        }
+    }
+}
+
+/**
+Simpler, less flexible, version of the [`Adapter`] trait.
+
+Can be used in situations where it suffices to just pass `None` and `Err()`
+values through and only operate when the inner
+[`ByteChunker`](crate::ByteChunker)'s `.next()` returns `Some(Ok(vec))`.
+
+This is less powerful than just using
+[`.map()`](https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.map),
+_et. al._, but simpler because there's no error handling required by
+the custom type.
+
+The [`StringAdapter`] type tracks error status, but we can implement a
+simpler type that just performs lossy UTF-8 conversion.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{ByteChunker, SimpleAdapter};
+use std::io::Cursor;
+
+struct LossyStringAdapter {}
+
+impl SimpleAdapter for LossyStringAdapter {
+    type Item = String;
+
+    fn adapt(&mut self, v: Vec<u8>) -> Self::Item {
+        String::from_utf8_lossy(&v).into()
+    }
+}
+
+let text = b"One, two, three four. Can I have a little more?";
+let c = Cursor::new(text);
+
+let chunks: Vec<_> = ByteChunker::new(c, "[ .,?]+")?
+    .with_simple_adapter(LossyStringAdapter{})
+    .map(|res| res.unwrap())
+    .collect();
+
+assert_eq!(
+    &chunks,
+    &["One", "two", "three", "four", "Can", "I", "have", "a", "little", "more"].clone()
+);
+# Ok::<(), RcErr>(())
+```
+}
+*/
+pub trait SimpleAdapter {
+    /// The type into which it converts the `Vec<u8>`s successfully produced
+    /// by the underlying [`ByteChunker`](crate::ByteChunker)'s  `Iterator`
+    /// implementation.
+    type Item;
+
+    /// Convert the `ByteChunker`'s output when _successful_.
+    fn adapt(&mut self, v: Vec<u8>) -> Self::Item;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Utf8ErrorStatus {
+    #[default]
+    Ok,
+    Errored,
+    Lossy,
+    Continue,
+}
+impl Eq for Utf8ErrorStatus {}
+
+/**
+An example [`Adapter`] type for producing a chunker that yields `String`s.
+
+```rust
+# use std::error::Error;
+# fn main() -> Result<(), Box<dyn Error>> {
+    use regex_chunker::{ByteChunker, StringAdapter};
+    use std::io::Cursor;
+
+    let text = b"One, two, three four. Can I have a little more?";
+    let c = Cursor::new(text);
+
+    let chunks: Vec<_> = ByteChunker::new(c, "[ .,?]+")?
+        .with_adapter(StringAdapter::default())
+        .map(|res| res.unwrap())
+        .collect();
+
+    assert_eq!(
+        &chunks,
+        &[
+            "One", "two", "three", "four",
+            "Can", "I", "have", "a", "little", "more"
+        ].clone()
+    );
+#   Ok(()) }
+```
+
+A chunk that ends partway through a character is invalid, like any
+other. If the chunker can cut chunks in the middle of a character (with
+[`ByteChunker::with_max_yield_size`](crate::ByteChunker::with_max_yield_size),
+say), use [`StringAdapter::join_split_chars`] to put them back together.
+
+What it does with invalid UTF-8 is set with a [`Utf8FailureMode`]. With
+[`StringAdapter::with_replacement`], invalid sequences are replaced with
+a string of your choosing, while the valid text around them is left alone.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{ByteChunker, StringAdapter};
+use std::io::Cursor;
+
+let text = b"caf\xff au lait";
+let words: Vec<String> = ByteChunker::new(Cursor::new(text), " ")?
+    .with_adapter(StringAdapter::default().with_replacement("?"))
+    .collect::<Result<_, _>>()?;
+
+assert_eq!(&words, &["caf?", "au", "lait"]);
+# Ok::<(), RcErr>(())
+```
+*/
+#[derive(Debug, Default)]
+pub struct StringAdapter {
+    status: Utf8ErrorStatus,
+    replacements: usize,
+    /* Whether to hold back an incomplete character at the end of a chunk
+    that wasn't ended by a delimiter. */
+    join_split: bool,
+    /* An incomplete character from the end of the last chunk, waiting
+    for the rest of it at the start of the next. */
+    pending: Vec<u8>,
+    /* What to put in place of invalid sequences in lossy mode; `None`
+    means U+FFFD REPLACEMENT CHARACTER. */
+    replacement: Option<Cow<'static, str>>,
+}
+
+impl StringAdapter {
+    pub fn new(mode: Utf8FailureMode) -> Self {
+        let status = match mode {
+            Utf8FailureMode::Fatal => Utf8ErrorStatus::Ok,
+            Utf8FailureMode::Lossy => Utf8ErrorStatus::Lossy,
+            Utf8FailureMode::Continue => Utf8ErrorStatus::Continue,
+        };
+
+        Self {
+            status,
+            replacements: 0,
+            join_split: false,
+            pending: Vec::new(),
+            replacement: None,
+        }
+    }
+
+    /**
+    Builder-pattern method for converting lossily, like
+    [`Utf8FailureMode::Lossy`] does, but with `replacement` (which may be
+    empty) in place of each invalid sequence instead of U+FFFD
+    REPLACEMENT CHARACTER. This overrides the adapter's
+    [`Utf8FailureMode`].
+
+    ```rust
+    # use regex_chunker::RcErr;
+    use regex_chunker::{ByteChunker, StringAdapter};
+    use std::io::Cursor;
+
+    let text = b"one\ntw\xffo\n";
+    let lines: Vec<String> = ByteChunker::new(Cursor::new(text), r"\n")?
+        .with_adapter(StringAdapter::default().with_replacement(""))
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&lines, &["one", "two"]);
+    # Ok::<(), RcErr>(())
+    ```
+    */
+    pub fn with_replacement(mut self, replacement: impl Into<Cow<'static, str>>) -> Self {
+        self.status = Utf8ErrorStatus::Lossy;
+        self.replacement = Some(replacement.into());
+        self
+    }
+
+    /**
+    Builder-pattern method for putting back together characters that the
+    chunker has cut in two. If a chunk that wasn't ended by a delimiter
+    (a piece of a chunk split by
+    [`ByteChunker::with_max_yield_size`](crate::ByteChunker::with_max_yield_size),
+    or one cut short by
+    [`ByteChunker::with_max_chunk_size`](crate::ByteChunker::with_max_chunk_size))
+    ends with the start of a character, those bytes are held back and
+    decoded with the start of the next chunk. If that's the whole chunk,
+    it yields nothing. If the
+    source ends partway through a character, the leftover bytes are
+    decoded as a final item, which, being invalid, is handled like any
+    other invalid UTF-8.
+
+    A chunk that was ended by a delimiter is decoded as it is, so a
+    character cut short right before a delimiter is still invalid. The
+    adapter can only tell which chunks those are when a
+    [`CustomChunker`](crate::CustomChunker) (or its async counterpart,
+    [`stream::CustomChunker`](crate::stream::CustomChunker)) is driving
+    it; driven any other way (through [`Adapter::act`]), it holds back
+    the end of every chunk, and [`Adapter::adapt`] returns `None` for a
+    chunk that's all held back.
+
+    ```rust
+    # use regex_chunker::RcErr;
+    use regex_chunker::{ByteChunker, StringAdapter};
+    use std::io::Cursor;
+
+    let text = "naïve café".as_bytes();
+    let pieces: Vec<String> = ByteChunker::new(Cursor::new(text), " ")?
+        .with_max_yield_size(3)
+        .with_adapter(StringAdapter::default().join_split_chars())
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(pieces.concat(), "naïvecafé");
+    # Ok::<(), RcErr>(())
+    ```
+    */
+    pub fn join_split_chars(mut self) -> Self {
+        self.join_split = true;
+        self
+    }
+
+    /**
+    In [`Utf8FailureMode::Lossy`] mode, the number of U+FFFD REPLACEMENT
+    CHARACTERs the adapter has put in place of invalid UTF-8 so far
+    (through [`CustomChunker::get_adapter`](crate::CustomChunker::get_adapter)),
+    or, with [`StringAdapter::with_replacement`], the number of
+    replacements it's made. This is always zero in the other modes. Comparing it to the
+    chunker's [`byte_count`](crate::ByteChunker::byte_count) gives an
+    idea of how much of the source is garbage.
+
+    As with [`String::from_utf8_lossy`], each maximal invalid sequence
+    is replaced by one character, so this counts invalid sequences, not
+    invalid bytes.
+
+    ```rust
+    # use regex_chunker::RcErr;
+    use regex_chunker::{ByteChunker, StringAdapter, Utf8FailureMode};
+    use std::io::Cursor;
+
+    let text = b"fine\nn\xffot\xfe\xfe fine\n";
+    let mut chunker = ByteChunker::new(Cursor::new(text), r"\n")?
+        .with_adapter(StringAdapter::new(Utf8FailureMode::Lossy));
+
+    assert_eq!(chunker.next().unwrap()?, "fine");
+    assert_eq!(chunker.next().unwrap()?, "n\u{FFFD}ot\u{FFFD}\u{FFFD} fine");
+    assert_eq!(chunker.get_adapter().replacement_count(), 3);
+    # Ok::<(), RcErr>(())
+    ```
+    */
+    pub fn replacement_count(&self) -> usize {
+        self.replacements
+    }
+
+    /* Put any pending bytes from the last chunk in front of `v`, and, if
+    `hold_back`, hold back any incomplete character from the end of it. */
+    fn complete(&mut self, v: Vec<u8>, hold_back: bool) -> Vec<u8> {
+        let mut v = if self.pending.is_empty() {
+            v
+        } else {
+            let mut pending = std::mem::take(&mut self.pending);
+            pending.extend_from_slice(&v);
+            pending
+        };
+        if !hold_back {
+            return v;
+        }
+        /* An incomplete character is a lead byte followed by fewer
+        continuation bytes than it needs, so it's at most three bytes. */
+        let start = v.len().saturating_sub(3);
+        if let Some(n) = v[start..].iter().rposition(|&b| b & 0xc0 != 0x80) {
+            let lead = start + n;
+            if let Err(e) = std::str::from_utf8(&v[lead..]) {
+                if e.error_len().is_none() {
+                    self.pending = v.split_off(lead);
+                }
+            }
+        }
+        v
+    }
+
+    /* Convert `v` the way `String::from_utf8_lossy()` does, but with the
+    adapter's replacement standing in for invalid sequences, counting the
+    replacements as they're made. */
+    fn lossy(&mut self, v: Vec<u8>) -> String {
+        let v = match String::from_utf8(v) {
+            Ok(s) => return s,
+            Err(e) => e.into_bytes(),
+        };
+        let mut s = String::with_capacity(v.len());
+        for chunk in v.utf8_chunks() {
+            s.push_str(chunk.valid());
+            if !chunk.invalid().is_empty() {
+                s.push_str(self.replacement.as_deref().unwrap_or("\u{FFFD}"));
+                self.replacements += 1;
+            }
+        }
+        s
+    }
+
+    /* Convert a value from the chunker, holding back an incomplete
+    character at the end of a chunk if `hold_back`. */
+    fn decode(
+        &mut self,
+        v: Option<Result<Vec<u8>, RcErr>>,
+        hold_back: bool,
+    ) -> AdapterAction<Result<String, RcErr>> {
+        let v = match (self.status, v) {
+            (Utf8ErrorStatus::Errored, _) => return AdapterAction::Done,
+            (_, Some(Err(e))) => return AdapterAction::Emit(Err(e)),
+            (_, Some(Ok(v))) => {
+                let was_empty = v.is_empty();
+                let v = self.complete(v, hold_back);
+                /* If all there was of this chunk was the start of a
+                character, there's nothing to yield yet. */
+                if v.is_empty() && !was_empty {
+                    return AdapterAction::Skip;
+                }
+                v
+            }
+            (_, None) => match self.pending.is_empty() {
+                true => return AdapterAction::Done,
+                /* The source ended partway through a character. */
+                false => std::mem::take(&mut self.pending),
+            },
+        };
+
+        match self.status {
+            Utf8ErrorStatus::Lossy => AdapterAction::Emit(Ok(self.lossy(v))),
+            Utf8ErrorStatus::Ok => match String::from_utf8(v) {
+                Ok(s) => AdapterAction::Emit(Ok(s)),
+                Err(e) => {
+                    self.status = Utf8ErrorStatus::Errored;
+                    AdapterAction::Emit(Err(e.into()))
+                }
+            },
+            _ => match String::from_utf8(v) {
+                Ok(s) => AdapterAction::Emit(Ok(s)),
+                Err(e) => AdapterAction::Emit(Err(e.into())),
+            },
+        }
+    }
+}
+
+impl Adapter for StringAdapter {
+    type Item = Result<String, RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        self.decode(v, self.join_split).into()
+    }
+
+    fn act(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> AdapterAction<Self::Item> {
+        self.decode(v, self.join_split)
+    }
+
+    fn adapt_with_delim(
+        &mut self,
+        v: Option<Result<Vec<u8>, RcErr>>,
+        delimiter: Option<&[u8]>,
+    ) -> AdapterAction<Self::Item> {
+        self.decode(v, self.join_split && delimiter.is_none())
+    }
+
+    fn wants_delimiters(&self) -> bool {
+        self.join_split
+    }
+}
+
+/**
+A [`SimpleAdapter`] that pairs each chunk with the (1-based) number of
+the line on which it begins, for chunking line-oriented data like logs.
+
+It keeps track of the line number by counting the newlines (`b'\n'`)
+in each chunk it sees, so the numbers stay accurate even when chunks
+don't correspond to lines. After the chunker has been used for a while,
+[`LineNumberAdapter::current_line`] (through
+[`SimpleCustomChunker::get_adapter`](crate::SimpleCustomChunker::get_adapter))
+tells which line it's gotten to.
+
+In [`MatchDisposition::Drop`](crate::MatchDisposition::Drop) mode
+(the default), the newlines in the delimiters never make it to the
+adapter, so if the delimiters contain newlines, use
+[`LineNumberAdapter::with_delimiter_lines`] to say how many each
+contains, and the adapter will count those as well. In
+[`MatchDisposition::Prepend`](crate::MatchDisposition::Prepend) mode,
+a chunk that begins with a delimiter begins on the line that the
+delimiter ends.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{ByteChunker, LineNumberAdapter};
+use std::io::Cursor;
+
+let log = b"starting\nloading config\nERROR: no config\nexiting\n";
+
+let mut chunker = ByteChunker::new(Cursor::new(log), r"\n")?
+    .with_simple_adapter(LineNumberAdapter::new().with_delimiter_lines(1));
+
+let errors: Vec<usize> = (&mut chunker)
+    .map(|res| res.unwrap())
+    .filter(|(_, line)| line.starts_with(b"ERROR"))
+    .map(|(n, _)| n)
+    .collect();
+
+assert_eq!(&errors, &[3]);
+assert_eq!(chunker.get_adapter().current_line(), 5);
+# Ok::<(), RcErr>(())
+```
+*/
+#[derive(Clone, Debug)]
+pub struct LineNumberAdapter {
+    line: usize,
+    delimiter_lines: usize,
+}
+
+impl LineNumberAdapter {
+    pub fn new() -> Self {
+        Self {
+            line: 1,
+            delimiter_lines: 0,
+        }
+    }
+
+    /// Builder-pattern method for setting the number of newlines in each
+    /// delimiter, for when they are being dropped. Default value is 0.
+    pub fn with_delimiter_lines(mut self, n: usize) -> Self {
+        self.delimiter_lines = n;
+        self
+    }
+
+    /// The number of the line on which the next chunk will begin.
+    pub fn current_line(&self) -> usize {
+        self.line
+    }
+}
+
+impl Default for LineNumberAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimpleAdapter for LineNumberAdapter {
+    type Item = (usize, Vec<u8>);
+
+    fn adapt(&mut self, v: Vec<u8>) -> Self::Item {
+        let line = self.line;
+        let newlines = v.iter().filter(|&&b| b == b'\n').count();
+        self.line += newlines + self.delimiter_lines;
+        (line, v)
+    }
+}
+
+/**
+A [`SimpleAdapter`] that trims leading and trailing bytes from each
+chunk. By default it trims ASCII spaces, tabs, carriage returns, and
+newlines; [`TrimAdapter::new`] takes the set of bytes to trim.
+
+To get trimmed `String`s, follow this adapter with a [`StringAdapter`]
+(see [`AndThenAdapter`]).
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{ByteChunker, TrimAdapter};
+use std::io::Cursor;
+
+let text = b" name , age,\tcity \r\n";
+
+let fields: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ",")?
+    .with_simple_adapter(TrimAdapter::default())
+    .collect::<Result<_, _>>()?;
+assert_eq!(&fields, &[b"name".to_vec(), b"age".to_vec(), b"city".to_vec()]);
+
+let quoted = b"\"one\" \"two\"";
+let words: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(quoted), " ")?
+    .with_simple_adapter(TrimAdapter::new(b"\""))
+    .collect::<Result<_, _>>()?;
+assert_eq!(&words, &[b"one".to_vec(), b"two".to_vec()]);
+# Ok::<(), RcErr>(())
+```
+*/
+#[derive(Clone, Debug)]
+pub struct TrimAdapter {
+    trim: Vec<u8>,
+}
+
+impl TrimAdapter {
+    /// Return a `TrimAdapter` that trims any of the bytes in `trim_set`.
+    pub fn new(trim_set: &[u8]) -> Self {
+        Self {
+            trim: trim_set.to_vec(),
+        }
+    }
+}
+
+impl Default for TrimAdapter {
+    fn default() -> Self {
+        Self::new(b" \t\r\n")
+    }
+}
+
+impl SimpleAdapter for TrimAdapter {
+    type Item = Vec<u8>;
+
+    fn adapt(&mut self, mut v: Vec<u8>) -> Self::Item {
+        let keep = |b: &u8| !self.trim.contains(b);
+        let end = match v.iter().rposition(keep) {
+            Some(n) => n + 1,
+            None => {
+                v.clear();
+                return v;
+            }
+        };
+        // There's at least one byte to keep, so this can't fail.
+        let start = v.iter().position(keep).unwrap_or(0);
+        v.truncate(end);
+        v.drain(..start);
+        v
+    }
+}
+
+/**
+A [`SimpleAdapter`] that yields each chunk as a `Box<[u8]>` (with
+[`Vec::into_boxed_slice`]), which can't have any unused capacity.
+
+A chunk can have room left over in its `Vec` from the chunker's
+buffers, and that room stays allocated for as long as the chunk is
+kept. For a lot of small chunks that are kept around, that adds up, and
+boxing them gives it back. The cost is that trimming the capacity may
+mean copying the chunk into a new allocation, so for chunks that are
+dropped soon after they're yielded, it's not worth it.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{BoxedAdapter, ByteChunker};
+use std::io::Cursor;
+
+let chunks: Vec<Box<[u8]>> = ByteChunker::new(Cursor::new("a,bb,ccc"), ",")?
+    .with_simple_adapter(BoxedAdapter)
+    .collect::<Result<_, _>>()?;
+
+assert_eq!(&*chunks[2], b"ccc");
+# Ok::<(), RcErr>(())
+```
+*/
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BoxedAdapter;
+
+impl SimpleAdapter for BoxedAdapter {
+    type Item = Box<[u8]>;
+
+    fn adapt(&mut self, v: Vec<u8>) -> Self::Item {
+        v.into_boxed_slice()
+    }
+}
+
+/**
+A [`SimpleAdapter`] that removes a byte order mark from the start of the
+very first chunk, and passes every chunk after that through untouched.
+
+The recognized BOMs are
+
+  * UTF-8: `EF BB BF`
+  * UTF-16 big-endian: `FE FF`
+  * UTF-16 little-endian: `FF FE`
+  * UTF-32 big-endian: `00 00 FE FF`
+  * UTF-32 little-endian: `FF FE 00 00`
+
+The UTF-32 marks are checked first, so UTF-16LE text that begins with a
+BOM followed by U+0000 will be mistaken for UTF-32LE. If the first chunk
+doesn't start with one of these, it isn't changed. Note that a BOM must
+be entirely within the first chunk to be recognized.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{BomAdapter, ByteChunker};
+use std::io::Cursor;
+
+let text = b"\xef\xbb\xbfone\n\xef\xbb\xbftwo\n";
+
+let lines: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), r"\n")?
+    .with_simple_adapter(BomAdapter::default())
+    .collect::<Result<_, _>>()?;
+assert_eq!(&lines, &[b"one".to_vec(), b"\xef\xbb\xbftwo".to_vec()]);
+# Ok::<(), RcErr>(())
+```
+*/
+#[derive(Clone, Debug, Default)]
+pub struct BomAdapter {
+    seen_first: bool,
+}
+
+impl BomAdapter {
+    /* Longest first, so UTF-32LE isn't taken for UTF-16LE. */
+    const BOMS: [&'static [u8]; 5] = [
+        &[0x00, 0x00, 0xFE, 0xFF],
+        &[0xFF, 0xFE, 0x00, 0x00],
+        &[0xEF, 0xBB, 0xBF],
+        &[0xFE, 0xFF],
+        &[0xFF, 0xFE],
+    ];
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SimpleAdapter for BomAdapter {
+    type Item = Vec<u8>;
+
+    fn adapt(&mut self, mut v: Vec<u8>) -> Self::Item {
+        if !self.seen_first {
+            self.seen_first = true;
+            if let Some(bom) = Self::BOMS.iter().find(|bom| v.starts_with(bom)) {
+                v.drain(..bom.len());
+            }
+        }
+        v
+    }
+}
+
+/**
+An [`Adapter`] that gathers runs of chunks into groups, for things like
+collecting lines into paragraphs. The supplied function decides whether
+each chunk is a _boundary_: chunks that aren't are accumulated (joined
+by a separator, empty by default), and a boundary chunk causes the
+group accumulated so far to be yielded as one chunk. The boundary chunks
+themselves are discarded, and so are empty groups, so several boundaries
+in a row don't produce empty chunks. At the end of the source, any
+group still being accumulated is yielded.
+
+Errors from the underlying chunker are passed through as they happen,
+without disturbing the group being accumulated.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{ByteChunker, GroupAdapter};
+use std::io::Cursor;
+
+let text = b"Call me\nIshmael.\n\n\nSome years ago\n\nnever mind\nhow long";
+
+let paragraphs: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), r"\n")?
+    .with_adapter(GroupAdapter::new(|line: &[u8]| line.is_empty()).with_separator(b" "))
+    .collect::<Result<_, _>>()?;
+
+assert_eq!(
+    &paragraphs,
+    &[&b"Call me Ishmael."[..], b"Some years ago", b"never mind how long"]
+);
+# Ok::<(), RcErr>(())
+```
+*/
+pub struct GroupAdapter<F> {
+    is_boundary: F,
+    separator: Vec<u8>,
+    group: Option<Vec<u8>>,
+}
+
+impl<F: FnMut(&[u8]) -> bool> GroupAdapter<F> {
+    pub fn new(is_boundary: F) -> Self {
+        Self {
+            is_boundary,
+            separator: Vec::new(),
+            group: None,
+        }
+    }
+
+    /// Builder-pattern method for setting the bytes inserted between
+    /// the chunks in a group.
+    pub fn with_separator(mut self, separator: &[u8]) -> Self {
+        self.separator = separator.to_vec();
+        self
+    }
+}
+
+impl<F> std::fmt::Debug for GroupAdapter<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GroupAdapter")
+            .field("separator", &String::from_utf8_lossy(&self.separator))
+            .field("group", &self.group.as_deref().map(String::from_utf8_lossy))
+            .finish()
+    }
+}
+
+impl<F: FnMut(&[u8]) -> bool> Adapter for GroupAdapter<F> {
+    type Item = Result<Vec<u8>, RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        self.act(v).into()
+    }
+
+    fn act(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> AdapterAction<Self::Item> {
+        let v = match v {
+            Some(Ok(v)) => v,
+            Some(Err(e)) => return AdapterAction::Emit(Err(e)),
+            None => {
+                return match self.group.take() {
+                    Some(group) => AdapterAction::Emit(Ok(group)),
+                    None => AdapterAction::Done,
+                }
+            }
+        };
+
+        if (self.is_boundary)(&v) {
+            return match self.group.take() {
+                Some(group) => AdapterAction::Emit(Ok(group)),
+                None => AdapterAction::Skip,
+            };
+        }
+
+        match self.group.as_mut() {
+            Some(group) => {
+                group.extend_from_slice(&self.separator);
+                group.extend_from_slice(&v);
+            }
+            None => self.group = Some(v),
+        }
+        AdapterAction::Skip
+    }
+}
+
+/**
+An [`Adapter`] that further splits each chunk into fields on a second
+regular expression, yielding a `Vec` of fields per chunk. This works like
+[`Regex::split`](regex::bytes::Regex::split): the matches are discarded,
+and a chunk with no matches yields a single field. Errors from the
+underlying chunker are passed through.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{ByteChunker, FieldAdapter};
+use std::io::Cursor;
+
+let text = b"name, age ,city\nAda,36, London\n";
+
+let rows: Vec<Vec<Vec<u8>>> = ByteChunker::new(Cursor::new(text), r"\n")?
+    .with_adapter(FieldAdapter::new(r"\s*,\s*")?)
+    .collect::<Result<_, _>>()?;
+
+assert_eq!(
+    &rows,
+    &[
+        vec![b"name".to_vec(), b"age".to_vec(), b"city".to_vec()],
+        vec![b"Ada".to_vec(), b"36".to_vec(), b"London".to_vec()],
+    ]
+);
+# Ok::<(), RcErr>(())
+```
+*/
+#[derive(Clone, Debug)]
+pub struct FieldAdapter {
+    separator: Regex,
+}
+
+impl FieldAdapter {
+    /// Return a `FieldAdapter` that splits chunks on the regex `pattern`.
+    pub fn new(pattern: &str) -> Result<Self, RcErr> {
+        Ok(Self::with_regex(Regex::new(pattern)?))
+    }
+
+    /// Return a `FieldAdapter` that splits chunks on an already-compiled
+    /// `Regex`.
+    pub fn with_regex(separator: Regex) -> Self {
+        Self { separator }
+    }
+}
+
+impl Adapter for FieldAdapter {
+    type Item = Result<Vec<Vec<u8>>, RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        v.map(|res| {
+            res.map(|v| {
+                self.separator
+                    .split(&v)
+                    .map(|field| field.to_vec())
+                    .collect()
+            })
+        })
+    }
+}
+
+/**
+An [`Adapter`] that calls a function on each successful chunk, for side
+effects like logging or counting, and passes everything through
+unchanged. Unlike calling
+[`.inspect()`](https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.inspect)
+on a chunker, this keeps the [`CustomChunker`](crate::CustomChunker)
+type, so the function can be reached through
+[`get_adapter()`](crate::CustomChunker::get_adapter).
+
+To see errors from the underlying chunker too, add a second function
+with [`InspectAdapter::with_error_inspector`].
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{ByteChunker, InspectAdapter};
+use std::io::Cursor;
+
+let text = b"one two three";
+let mut longest = 0;
+
+let words: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), " ")?
+    .with_adapter(InspectAdapter::new(|chunk: &[u8]| longest = longest.max(chunk.len())))
+    .collect::<Result<_, _>>()?;
+
+assert_eq!(words.len(), 3);
+assert_eq!(longest, 5);
+# Ok::<(), RcErr>(())
+```
+*/
+pub struct InspectAdapter<F, G = fn(&RcErr)> {
+    inspect: F,
+    inspect_err: G,
+}
+
+impl<F: FnMut(&[u8])> InspectAdapter<F> {
+    pub fn new(inspect: F) -> Self {
+        Self {
+            inspect,
+            inspect_err: |_| {},
+        }
+    }
+}
+
+impl<F, G> InspectAdapter<F, G> {
+    /// Builder-pattern method for supplying a function to be called on
+    /// each error from the underlying chunker.
+    pub fn with_error_inspector<H: FnMut(&RcErr)>(self, inspect_err: H) -> InspectAdapter<F, H> {
+        InspectAdapter {
+            inspect: self.inspect,
+            inspect_err,
+        }
+    }
+}
+
+impl<F, G> std::fmt::Debug for InspectAdapter<F, G> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InspectAdapter").finish_non_exhaustive()
+    }
+}
+
+impl<F: FnMut(&[u8]), G: FnMut(&RcErr)> Adapter for InspectAdapter<F, G> {
+    type Item = Result<Vec<u8>, RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        match &v {
+            Some(Ok(v)) => (self.inspect)(v),
+            Some(Err(e)) => (self.inspect_err)(e),
+            None => {}
+        }
+        v
+    }
+}
+
+/**
+An [`Adapter`] that only passes through the chunks for which a predicate
+returns `true`; the rest are discarded (by returning
+[`AdapterAction::Skip`], so the [`CustomChunker`](crate::CustomChunker)
+moves straight on to the next chunk). Errors from the underlying chunker
+are always passed through.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{ByteChunker, FilterAdapter};
+use std::io::Cursor;
+
+let text = b"# comment\nkey=value\n\n# another\nother=thing\n";
+
+let lines: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), r"\n")?
+    .with_adapter(FilterAdapter::new(|line: &[u8]| {
+        !line.is_empty() && !line.starts_with(b"#")
+    }))
+    .collect::<Result<_, _>>()?;
+
+assert_eq!(&lines, &[b"key=value".to_vec(), b"other=thing".to_vec()]);
+# Ok::<(), RcErr>(())
+```
+*/
+pub struct FilterAdapter<F> {
+    predicate: F,
+}
+
+impl<F: FnMut(&[u8]) -> bool> FilterAdapter<F> {
+    pub fn new(predicate: F) -> Self {
+        Self { predicate }
+    }
+}
+
+impl<F> std::fmt::Debug for FilterAdapter<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilterAdapter").finish_non_exhaustive()
+    }
+}
+
+impl<F: FnMut(&[u8]) -> bool> Adapter for FilterAdapter<F> {
+    type Item = Result<Vec<u8>, RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        self.act(v).into()
+    }
+
+    fn act(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> AdapterAction<Self::Item> {
+        match v {
+            Some(Ok(v)) => match (self.predicate)(&v) {
+                true => AdapterAction::Emit(Ok(v)),
+                false => AdapterAction::Skip,
+            },
+            Some(Err(e)) => AdapterAction::Emit(Err(e)),
+            None => AdapterAction::Done,
+        }
+    }
+}
+
+/**
+An [`Adapter`] that works like `grep`: it only passes through the chunks
+in which a regular expression finds a match, and discards the rest (by
+returning [`AdapterAction::Skip`], like a [`FilterAdapter`]). Errors from
+the underlying chunker are always passed through.
+
+[`GrepAdapter::with_invert`] passes through the chunks that _don't_
+match instead, like `grep -v`. Either way, the adapter keeps a tally of
+the chunks it has passed through, which can be read with
+[`GrepAdapter::count`] through
+[`get_adapter()`](crate::CustomChunker::get_adapter). With
+[`GrepAdapter::with_count_only`], the chunks are all discarded and only
+the tally is kept, like `grep -c`.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{ByteChunker, GrepAdapter};
+use std::io::Cursor;
+
+let text = b"INFO start\nERROR disk full\nINFO retry\nERROR gave up\n";
+
+let errors: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), r"\n")?
+    .with_adapter(GrepAdapter::new("^ERROR")?)
+    .collect::<Result<_, _>>()?;
+assert_eq!(&errors, &[b"ERROR disk full".to_vec(), b"ERROR gave up".to_vec()]);
+
+let mut chunker = ByteChunker::new(Cursor::new(text), r"\n")?
+    .with_adapter(GrepAdapter::new("^ERROR")?.with_invert(true).with_count_only(true));
+assert!(chunker.next().is_none());
+assert_eq!(chunker.get_adapter().count(), 2);
+# Ok::<(), RcErr>(())
+```
+*/
+#[derive(Clone, Debug)]
+pub struct GrepAdapter {
+    pattern: Regex,
+    invert: bool,
+    count_only: bool,
+    count: usize,
+}
+
+impl GrepAdapter {
+    /// Return a `GrepAdapter` that passes through the chunks in which the
+    /// regex `pattern` finds a match.
+    pub fn new(pattern: &str) -> Result<Self, RcErr> {
+        Ok(Self::with_regex(Regex::new(pattern)?))
+    }
+
+    /// Return a `GrepAdapter` that matches chunks with an
+    /// already-compiled `Regex`.
+    pub fn with_regex(pattern: Regex) -> Self {
+        Self {
+            pattern,
+            invert: false,
+            count_only: false,
+            count: 0,
+        }
+    }
+
+    /// Builder-pattern method for passing through the chunks that _don't_
+    /// match, rather than the ones that do.
+    pub fn with_invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Builder-pattern method for discarding every chunk and only keeping
+    /// a tally of the ones that would have been passed through.
+    pub fn with_count_only(mut self, count_only: bool) -> Self {
+        self.count_only = count_only;
+        self
+    }
+
+    /// Return the number of chunks selected so far (passed through, or
+    /// just counted if the adapter is count-only).
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl Adapter for GrepAdapter {
+    type Item = Result<Vec<u8>, RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        self.act(v).into()
+    }
+
+    fn act(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> AdapterAction<Self::Item> {
+        let v = match v {
+            Some(Ok(v)) => v,
+            Some(Err(e)) => return AdapterAction::Emit(Err(e)),
+            None => return AdapterAction::Done,
+        };
+
+        if self.pattern.is_match(&v) == self.invert {
+            return AdapterAction::Skip;
+        }
+        self.count += 1;
+        match self.count_only {
+            true => AdapterAction::Skip,
+            false => AdapterAction::Emit(Ok(v)),
+        }
+    }
+}
+
+/**
+An [`Adapter`] that collapses runs of identical consecutive chunks into
+one, yielding each distinct chunk along with the number of times in a
+row it occurred. A run is yielded when a different chunk arrives, or at
+the end of the source.
+
+However long a run gets, only one copy of its chunk is kept, along with
+a count, so a long run costs no more memory than a single chunk. The
+run isn't yielded until it ends, though, so with a source that repeats
+one chunk indefinitely, this yields nothing indefinitely.
+
+Errors from the underlying chunker are passed through as they happen,
+without interrupting the run being counted.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{ByteChunker, RunLengthAdapter};
+use std::io::Cursor;
+
+let text = b"a,a,a,b,a,a";
+
+let runs: Vec<(usize, Vec<u8>)> = ByteChunker::new(Cursor::new(text), ",")?
+    .with_adapter(RunLengthAdapter::new())
+    .collect::<Result<_, _>>()?;
+
+assert_eq!(&runs, &[(3, b"a".to_vec()), (1, b"b".to_vec()), (2, b"a".to_vec())]);
+# Ok::<(), RcErr>(())
+```
+*/
+#[derive(Clone, Debug, Default)]
+pub struct RunLengthAdapter {
+    run: Option<(usize, Vec<u8>)>,
+}
+
+impl RunLengthAdapter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Adapter for RunLengthAdapter {
+    type Item = Result<(usize, Vec<u8>), RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        self.act(v).into()
+    }
+
+    fn act(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> AdapterAction<Self::Item> {
+        let v = match v {
+            Some(Ok(v)) => v,
+            Some(Err(e)) => return AdapterAction::Emit(Err(e)),
+            None => {
+                return match self.run.take() {
+                    Some(run) => AdapterAction::Emit(Ok(run)),
+                    None => AdapterAction::Done,
+                }
+            }
+        };
+
+        match self.run.as_mut() {
+            Some((n, chunk)) if *chunk == v => {
+                *n += 1;
+                AdapterAction::Skip
+            }
+            _ => match self.run.replace((1, v)) {
+                Some(run) => AdapterAction::Emit(Ok(run)),
+                None => AdapterAction::Skip,
+            },
+        }
+    }
+}
+
+/**
+An [`Adapter`] that runs two others one after the other, feeding what
+the first produces to the second, so one
+[`CustomChunker`](crate::CustomChunker) can do both jobs. Make one with
+[`Adapter::and_then`] or [`AndThenAdapter::new`]; both adapters stay
+reachable through [`AndThenAdapter::first`] and
+[`AndThenAdapter::second`].
+
+The second adapter is given the first one's output in place of a
+chunker's, so the first one has to produce `Result<Vec<u8>, RcErr>`s.
+Each chunk's delimiter is passed to both. When the first adapter
+[skips](AdapterAction::Skip) a chunk, the second one doesn't see it;
+when the first one is done, the second is given `None`, so it can
+yield anything it's holding on to.
+
+A [`SimpleAdapter`] can take part by being wrapped in a [`Simple`].
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{Adapter, ByteChunker, FilterAdapter, Simple, StringAdapter, TrimAdapter};
+use std::io::Cursor;
+
+let text = b"  keep me \n\n   and me\n    \n";
+
+let chunker = ByteChunker::new(Cursor::new(text), r"\n")?.with_adapter(
+    Simple(TrimAdapter::default())
+        .and_then(FilterAdapter::new(|line: &[u8]| !line.is_empty()))
+        .and_then(StringAdapter::default()),
+);
+let lines: Vec<String> = chunker.collect::<Result<_, _>>()?;
+
+assert_eq!(&lines, &["keep me", "and me"]);
+# Ok::<(), RcErr>(())
+```
+*/
+#[derive(Clone, Debug, Default)]
+pub struct AndThenAdapter<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> AndThenAdapter<A, B> {
+    /// Return an [`AndThenAdapter`] that feeds `first`'s output to
+    /// `second`.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Get a reference to the adapter that runs first.
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the adapter that runs first.
+    pub fn first_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Get a reference to the adapter that runs second.
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the adapter that runs second.
+    pub fn second_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume the `AndThenAdapter` and return both adapters.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A, B> Adapter for AndThenAdapter<A, B>
+where
+    A: Adapter<Item = Result<Vec<u8>, RcErr>>,
+    B: Adapter,
+{
+    type Item = B::Item;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        self.act(v).into()
+    }
+
+    fn act(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> AdapterAction<Self::Item> {
+        match self.first.act(v) {
+            AdapterAction::Emit(item) => self.second.act(Some(item)),
+            AdapterAction::Skip => AdapterAction::Skip,
+            AdapterAction::Done => self.second.act(None),
+        }
+    }
+
+    fn adapt_with_delim(
+        &mut self,
+        v: Option<Result<Vec<u8>, RcErr>>,
+        delimiter: Option<&[u8]>,
+    ) -> AdapterAction<Self::Item> {
+        match self.first.adapt_with_delim(v, delimiter) {
+            AdapterAction::Emit(item) => self.second.adapt_with_delim(Some(item), delimiter),
+            AdapterAction::Skip => AdapterAction::Skip,
+            AdapterAction::Done => self.second.adapt_with_delim(None, None),
+        }
+    }
+
+    fn wants_delimiters(&self) -> bool {
+        self.first.wants_delimiters() || self.second.wants_delimiters()
+    }
+}
+
+/**
+Wraps a [`SimpleAdapter`] to make it an [`Adapter`] (passing `None`s and
+errors through untouched), for where only an `Adapter` will do, like in
+an [`AndThenAdapter`].
+*/
+#[derive(Clone, Debug, Default)]
+pub struct Simple<S>(pub S);
+
+impl<S: SimpleAdapter> Adapter for Simple<S> {
+    type Item = Result<S::Item, RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        v.map(|res| res.map(|v| self.0.adapt(v)))
+    }
+}
@@ -0,0 +1,94 @@
+/*!
+An adapter for decoding base64-encoded chunks.
+*/
+use base64::{
+    engine::{general_purpose::STANDARD, GeneralPurpose},
+    Engine,
+};
+
+use crate::{ctrl::DecodeFailureMode, Adapter, RcErr};
+
+/**
+An [`Adapter`] that decodes each chunk as base64, using any
+[`base64::Engine`](https://docs.rs/base64/latest/base64/trait.Engine.html)
+(the default is the standard alphabet with padding). Errors from the
+underlying chunker are passed through, and what happens when a chunk
+can't be decoded is controlled by a [`DecodeFailureMode`].
+
+The chunk has to be entirely base64, so choose a delimiter that
+swallows any surrounding whitespace (like `\r?\n` for lines that may
+end with a carriage return).
+
+This requires the `base64` feature.
+
+```rust
+# use std::error::Error;
+# fn main() -> Result<(), Box<dyn Error>> {
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use regex_chunker::{Base64Adapter, ByteChunker, DecodeFailureMode};
+    use std::io::Cursor;
+
+    let text = b"aGVsbG8=\r\nd29ybGQ=\n";
+    let records: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), r"\r?\n")?
+        .with_adapter(Base64Adapter::default())
+        .collect::<Result<_, _>>()?;
+    assert_eq!(&records, &[b"hello".to_vec(), b"world".to_vec()]);
+
+    let text = b"_-8\n";
+    let records: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), r"\n")?
+        .with_adapter(Base64Adapter::new(URL_SAFE_NO_PAD, DecodeFailureMode::Fatal))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(&records, &[vec![0xff, 0xef]]);
+#   Ok(()) }
+```
+*/
+#[cfg_attr(docsrs, doc(cfg(feature = "base64")))]
+#[derive(Clone, Debug)]
+pub struct Base64Adapter<E = GeneralPurpose> {
+    engine: E,
+    mode: DecodeFailureMode,
+    errored: bool,
+}
+
+impl<E: Engine> Base64Adapter<E> {
+    /// Decode chunks with `engine` (which sets the alphabet and padding),
+    /// handling invalid ones as `mode` says.
+    pub fn new(engine: E, mode: DecodeFailureMode) -> Self {
+        Self {
+            engine,
+            mode,
+            errored: false,
+        }
+    }
+}
+
+impl Default for Base64Adapter<GeneralPurpose> {
+    fn default() -> Self {
+        Self::new(STANDARD, DecodeFailureMode::default())
+    }
+}
+
+impl<E: Engine> Adapter for Base64Adapter<E> {
+    type Item = Result<Vec<u8>, RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        if self.errored {
+            return None;
+        }
+
+        let v = match v? {
+            Ok(v) => v,
+            Err(e) => return Some(Err(e)),
+        };
+
+        match self.engine.decode(&v) {
+            Ok(decoded) => Some(Ok(decoded)),
+            Err(e) => {
+                if let DecodeFailureMode::Fatal = self.mode {
+                    self.errored = true;
+                }
+                Some(Err(e.into()))
+            }
+        }
+    }
+}
@@ -2,18 +2,47 @@
 The base ByteChunker types.
 */
 use std::{
+    borrow::Cow,
     fmt::{Debug, Formatter},
-    hint::spin_loop,
-    io::{ErrorKind, Read},
+    io::{BufRead, ErrorKind, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    sync::{mpsc::Receiver, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
 };
 
-use regex::bytes::Regex;
+#[cfg(feature = "aho-corasick")]
+use aho_corasick::AhoCorasick;
+use memchr::memmem::Finder;
+use regex::bytes::{Match, Regex, RegexBuilder, RegexSet, RegexSetBuilder};
+use regex_syntax::hir::{Hir, HirKind};
 
-use crate::{ctrl::*, CustomChunker, RcErr, SimpleCustomChunker};
+use crate::{
+    ctrl::*, Adapter, CaptureChunker, CustomChunker, DelimitedChunker, KindChunker, OffsetChunker,
+    RcErr, SimpleCustomChunker, SourceChain, TerminalChunker,
+};
 
 // By default the `read_buffer` size is 1 KiB.
 const DEFAULT_BUFFER_SIZE: usize = 1024;
 
+// Takes bytes from a chunker's source without going through its read
+// buffer, returning how many were taken.
+type FillFn<R> = fn(&mut ByteChunker<R>) -> std::io::Result<usize>;
+
+// Decides whether a match found in the search buffer delimits a chunk.
+type MatchFilter = Arc<Mutex<dyn FnMut(&[u8], Range<usize>) -> bool + Send>>;
+
+// Told how many bytes have been read from the source so far.
+type ProgressFn = Arc<Mutex<dyn FnMut(u64) + Send>>;
+
+// The capture groups of a match, not counting the whole match.
+type Groups = Vec<Option<Vec<u8>>>;
+
+// A chunk to be taken from the front of the search buffer: the length of
+// the chunk, and the number of bytes to remove from the buffer with it
+// (more than the length if a match is being dropped).
+type Taken = (usize, usize);
+
 /**
 The `ByteChunker` takes a
 [`bytes::Regex`](https://docs.rs/regex/latest/regex/bytes/struct.Regex.html),
@@ -74,11 +103,46 @@ assert_eq!(
 
 # Ok(())
 # }
+```
+
+Because the chunker can't see bytes it hasn't read yet, a match that runs
+right up to the end of what it's read so far might get longer when more
+bytes come in (think of `\r?\n\r?\n` with only `\n` read so far). Such a
+match is held until the next read (or the end of the source) settles it,
+unless it's already as long as any match of the pattern can be. This
+means a chunk delimited by, say, `;+` won't be returned until at least
+one byte after the `;` has been read.
+
+A `ByteChunker` can be cloned if its source can, which is handy for
+chunking an in-memory buffer more than one way. The clone gets a copy
+of everything the chunker has read but not yet returned, and carries on
+from the same place. Cloning a source that shares some underlying state
+(like a file or socket handle) rarely does anything useful, as the two
+chunkers will read different parts of the data; it's up to the caller
+not to do that.
+
+```
+use regex_chunker::{ByteChunker, MatchDisposition};
+use std::io::Cursor;
+
+# fn main() -> Result<(), regex_chunker::RcErr> {
+let chunker = ByteChunker::new(Cursor::new(b"a,b,c"), ",")?;
+let appended: Vec<Vec<u8>> = chunker
+    .clone()
+    .with_match(MatchDisposition::Append)
+    .collect::<Result<_, _>>()?;
+let dropped: Vec<Vec<u8>> = chunker.collect::<Result<_, _>>()?;
 
+assert_eq!(&appended, &[&b"a,"[..], b"b,", b"c"]);
+assert_eq!(&dropped, &[&b"a"[..], b"b", b"c"]);
+# Ok(())
+# }
+```
 */
+#[derive(Clone)]
 pub struct ByteChunker<R> {
     source: R,
-    fence: Regex,
+    matcher: Matcher,
     read_buff: Vec<u8>,
     search_buff: Vec<u8>,
     error_status: ErrorStatus,
@@ -93,37 +157,707 @@ pub struct ByteChunker<R> {
     to start our next scan of the buffer from _after_ the match, or we'll
     just match the very beginning of the scan buffer again. */
     scan_start_offset: usize,
+    /* If set, the largest number of bytes the chunker will buffer without
+    finding a delimiter before acting according to `oversize_policy`. */
+    max_chunk_size: Option<usize>,
+    oversize_policy: OversizePolicy,
+    /* What to do before retrying a source that isn't ready. */
+    block_policy: BlockPolicy,
+    /* How long to wait at the end of the source for more data, if
+    following it, and how to tell when to stop. */
+    follow: Option<Duration>,
+    follow_handle: FollowHandle,
+    /* How long to wait for the source to produce data, and when the
+    current wait started. */
+    read_timeout: Option<Duration>,
+    waiting_since: Option<Instant>,
+    /* What to do with the data after the last match. */
+    trailing: TrailingPolicy,
+    /* Position in the source stream of the first byte of `search_buff`. */
+    buff_offset: u64,
+    /* Start and end positions in the source stream of the most recently
+    produced chunk. */
+    last_span: (u64, u64),
+    /* Whether to record the capture groups of each match, and the groups
+    belonging to the most recently produced chunk. In Prepend mode, the
+    groups of the match kept at the front of the buffer wait in
+    `front_captures` until their chunk is produced. */
+    track_captures: bool,
+    last_captures: Vec<Option<Vec<u8>>>,
+    front_captures: Vec<Option<Vec<u8>>>,
+    /* Whether to record the text of each match, and the text of the match
+    that followed the most recently produced chunk (`None` if that chunk
+    wasn't ended by a match). */
+    track_delims: bool,
+    last_delim: Option<Vec<u8>>,
+    /* Index of the pattern whose match is associated with the most
+    recently produced chunk, in the same sense as `last_captures`. */
+    last_kind: Option<usize>,
+    front_kind: Option<usize>,
+    skip_empty: bool,
+    /* Whether to return an empty chunk if it's the first one, and
+    whether any chunk has been produced yet. */
+    leading_empty: bool,
+    started: bool,
+    /* If set, chunks smaller than this get accumulated in `merged` until
+    they add up to at least this size. `merged_start` is the source
+    position of the accumulated chunk, and `merged_delim` is the text of
+    the match that followed it. */
+    min_chunk_size: Option<usize>,
+    merged: Option<Vec<u8>>,
+    merged_start: u64,
+    merged_delim: Option<Vec<u8>>,
+    /* If set, chunks larger than this are returned in pieces of at most
+    this size. The remaining `pieces` of a split chunk start at source
+    position `pieces_start`; the chunk's delimiter and capture groups
+    are held until its last piece is returned. */
+    max_yield_size: Option<usize>,
+    pieces: Option<Vec<u8>>,
+    pieces_start: u64,
+    pieces_delim: Option<Vec<u8>>,
+    pieces_captures: Vec<Option<Vec<u8>>>,
+    pieces_kind: Option<usize>,
+    /* If nonzero, each chunk returned starts with (up to) this many
+    bytes from the end of the one before, which are kept in
+    `overlap_tail`. */
+    overlap: usize,
+    overlap_tail: Vec<u8>,
+    /* If the chunker was built with `from_buf_read()`, this searches the
+    source's own buffer directly, and `read_buff` goes unused. */
+    fill: Option<FillFn<R>>,
+    /* A chunk `fill` found whole in the source's buffer, which is
+    returned instead of one taken from the search buffer. */
+    direct: Option<Vec<u8>>,
+    /* Number of bytes at the front of the source's buffer that `fill`
+    should throw away before reading on: a delimiter left there in
+    `Prepend` mode that's been dropped by switching to `Drop`. */
+    source_skip: usize,
+    /* Length of the front part of the search buffer that has already been
+    searched without finding a match. When more bytes arrive, we only need
+    to search again from a little before this point, in case a match
+    straddles it. How far before depends on the longest possible match,
+    if we know it, or the configured `rescan_window`; if we don't have
+    either, the whole buffer is searched again. */
+    scanned_to: usize,
+    max_match_len: Option<usize>,
+    /* Whether the fence has assertions (like `\b` or `$`) that look at
+    the bytes next to a match. Whether one of those holds at the end of
+    the data read so far can depend on what's read next, so a match
+    there isn't trusted just for being as long as a match can be. */
+    look_around: bool,
+    /* Flags the fence (and any other patterns) were compiled with by
+    `case_insensitive()` and friends. */
+    flags: Flags,
+    rescan_window: Option<usize>,
+    /* Whether to trust a match at the end of the buffer without waiting
+    to see whether more data would make it longer. */
+    eager: bool,
+    /* If set, when the search buffer's capacity grows past this many
+    bytes, it's shrunk again once a chunk has been taken out of it. */
+    shrink_threshold: Option<usize>,
+    /* The capacity the search buffer starts with (and is given again
+    after each chunk is taken out of it), and how it grows from there. */
+    search_capacity: usize,
+    growth: GrowthPolicy,
+    /* Number of bytes at the front of the search buffer belonging to a
+    chunk returned by `next_borrowed()` that still need to be removed. */
+    pending_drain: usize,
+    /* The result of the last call to `.peek()`, if it hasn't been
+    returned by `.next()` yet. */
+    peeked: Option<Option<Result<Vec<u8>, RcErr>>>,
+    /* If the caller has told us, the number of bytes left to be read
+    from the source. */
+    source_remaining: Option<u64>,
+    /* Whether the most recent read from the source returned nothing. */
+    source_eof: bool,
+    /* While `finish()` is running, where the unterminated tail goes
+    instead of being handled according to the `TrailingPolicy`. */
+    held_tail: Option<Vec<u8>>,
+    /* Bytes read from the source so far, and how often (in bytes) to
+    report that to the `with_progress()` callback. */
+    bytes_read: u64,
+    progress: Option<(u64, ProgressFn)>,
+    /* Number of chunks (and bytes in them) successfully yielded. */
+    chunks_emitted: u64,
+    bytes_emitted: u64,
+    /* If set, the chunker stops after yielding this many chunks. */
+    chunk_limit: Option<u64>,
+    /* The number of chunks to discard at the start of the source, and
+    the number that still need to be discarded. */
+    skip_chunks: u64,
+    to_skip: u64,
 }
 
 impl<R> ByteChunker<R> {
     /**
     Return a new [`ByteChunker`] wrapping the given writer that will chunk its
     output by delimiting it with the supplied regex pattern.
+
+    A pattern that can match the empty string (like `x*` or `\b`) is
+    allowed, but empty matches are ignored; only matches of at least
+    one byte delimit chunks.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(b"abxxcd"), "x*")?
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"ab"[..], b"cd"]);
+    # Ok(())
+    # }
+    ```
     */
     pub fn new(source: R, delimiter: &str) -> Result<Self, RcErr> {
         let fence = Regex::new(delimiter)?;
-        Ok(Self {
+        let mut chunker = Self::with_regex(source, fence);
+        chunker.max_match_len = max_match_len(delimiter);
+        chunker.look_around = has_look_around(delimiter);
+        chunker.matcher.literal = Flags::default().literal_finder(delimiter);
+        Ok(chunker)
+    }
+
+    /**
+    Return a new [`ByteChunker`] wrapping the given reader that will chunk
+    its output with the supplied, already-compiled
+    [`bytes::Regex`](https://docs.rs/regex/latest/regex/bytes/struct.Regex.html).
+
+    This is useful for sharing one compiled pattern among many chunkers,
+    or for using a `Regex` configured with a
+    [`RegexBuilder`](https://docs.rs/regex/latest/regex/bytes/struct.RegexBuilder.html).
+
+    Because the chunker can't tell how a `Regex` was configured, it
+    can't work out the longest possible match the way it does for
+    [`ByteChunker::new`]; see [`ByteChunker::with_rescan_window`].
+
+    ```
+    use regex::bytes::RegexBuilder;
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let fence = RegexBuilder::new("and").case_insensitive(true).build()?;
+    let text = b"salt AND pepper and vinegar";
+
+    let chunks: Vec<Vec<u8>> = ByteChunker::with_regex(Cursor::new(text), fence)
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"salt "[..], b" pepper ", b" vinegar"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_regex(source: R, fence: Regex) -> Self {
+        Self {
             source,
-            fence,
+            matcher: Matcher {
+                fence,
+                literal: None,
+                #[cfg(feature = "aho-corasick")]
+                literals: None,
+                match_filter: None,
+                multi: None,
+            },
             read_buff: vec![0u8; DEFAULT_BUFFER_SIZE],
             search_buff: Vec::new(),
             error_status: ErrorStatus::Ok,
             match_dispo: MatchDisposition::default(),
             last_scan_matched: false,
             scan_start_offset: 0,
-        })
+            max_chunk_size: None,
+            oversize_policy: OversizePolicy::default(),
+            block_policy: BlockPolicy::default(),
+            follow: None,
+            follow_handle: FollowHandle::default(),
+            read_timeout: None,
+            waiting_since: None,
+            trailing: TrailingPolicy::default(),
+            buff_offset: 0,
+            last_span: (0, 0),
+            track_captures: false,
+            last_captures: Vec::new(),
+            front_captures: Vec::new(),
+            track_delims: false,
+            last_delim: None,
+            last_kind: None,
+            front_kind: None,
+            skip_empty: false,
+            leading_empty: true,
+            started: false,
+            min_chunk_size: None,
+            merged: None,
+            merged_start: 0,
+            merged_delim: None,
+            max_yield_size: None,
+            pieces: None,
+            pieces_start: 0,
+            pieces_delim: None,
+            pieces_captures: Vec::new(),
+            pieces_kind: None,
+            overlap: 0,
+            overlap_tail: Vec::new(),
+            fill: None,
+            direct: None,
+            source_skip: 0,
+            scanned_to: 0,
+            max_match_len: None,
+            look_around: false,
+            flags: Flags::default(),
+            rescan_window: None,
+            eager: false,
+            pending_drain: 0,
+            shrink_threshold: None,
+            search_capacity: 0,
+            growth: GrowthPolicy::default(),
+            peeked: None,
+            source_remaining: None,
+            source_eof: false,
+            held_tail: None,
+            bytes_read: 0,
+            progress: None,
+            chunks_emitted: 0,
+            bytes_emitted: 0,
+            chunk_limit: None,
+            skip_chunks: 0,
+            to_skip: 0,
+        }
+    }
+
+    /**
+    Return a new [`ByteChunker`] like [`ByteChunker::new`] does, but with
+    the delimiter pattern compiled under the given size limit (in bytes)
+    instead of the `regex` crate's default. See
+    [`RegexBuilder::size_limit`](https://docs.rs/regex/latest/regex/bytes/struct.RegexBuilder.html#method.size_limit).
+
+    A big enough pattern (like a long alternation of generated strings)
+    won't compile under the default limit, which a higher one allows. A
+    lower one is a way to reject patterns that would be expensive to
+    compile and run. Either way, a pattern that's too big is reported
+    as an [`RcErr::Regex`]. The limit is kept if the pattern is compiled
+    again (by [`ByteChunker::case_insensitive`] and the like).
+
+    ```
+    use regex_chunker::{ByteChunker, RcErr};
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let pattern = r"\w{20}";
+    let too_small = ByteChunker::with_size_limit(Cursor::new(""), pattern, 100);
+    assert!(matches!(too_small, Err(RcErr::Regex(_))));
+
+    let chunker = ByteChunker::with_size_limit(Cursor::new(""), pattern, 1 << 24)?;
+    assert_eq!(chunker.count(), 0);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_size_limit(source: R, delimiter: &str, size_limit: usize) -> Result<Self, RcErr> {
+        let flags = Flags {
+            size_limit: Some(size_limit),
+            ..Flags::default()
+        };
+        let fence = flags.regex(delimiter)?;
+        let mut chunker = Self::with_regex(source, fence);
+        chunker.flags = flags;
+        chunker.max_match_len = max_match_len(delimiter);
+        chunker.look_around = flags.has_look_around(delimiter);
+        chunker.matcher.literal = flags.literal_finder(delimiter);
+        Ok(chunker)
+    }
+
+    /**
+    Return a new [`ByteChunker`] wrapping the given reader that will chunk
+    its output by delimiting it with matches of any of the supplied regex
+    patterns. Where matches of different patterns overlap, the one that
+    starts first wins; of matches starting at the same place, the one
+    whose pattern comes first in `patterns` wins. (This is the same as
+    delimiting with an alternation of the patterns, but avoids having to
+    build and maintain one huge regex.)
+
+    [`ByteChunker::fence`] will return the alternation of all the
+    patterns.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"SELECT 1;\nSELECT 2; SELECT 3\n";
+    let chunks: Vec<Vec<u8>> = ByteChunker::with_patterns(Cursor::new(text), &[r";\s*", r"\n"])?
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"SELECT 1"[..], b"SELECT 2", b"SELECT 3"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_patterns(source: R, patterns: &[&str]) -> Result<Self, RcErr> {
+        let set = RegexSet::new(patterns)?;
+        let regexes = patterns
+            .iter()
+            .map(|p| Regex::new(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        let alternation = match patterns {
+            // This never matches anything.
+            [] => String::from(r"[^\s\S]"),
+            _ => patterns
+                .iter()
+                .map(|p| format!("(?:{})", p))
+                .collect::<Vec<_>>()
+                .join("|"),
+        };
+        let fence = Regex::new(&alternation)?;
+
+        let mut chunker = Self::with_regex(source, fence);
+        chunker.max_match_len = patterns
+            .iter()
+            .map(|p| max_match_len(p))
+            .collect::<Option<Vec<usize>>>()
+            .and_then(|lens| lens.into_iter().max());
+        chunker.look_around = patterns.iter().any(|p| has_look_around(p));
+        chunker.matcher.multi = Some(MultiFence { set, regexes });
+        Ok(chunker)
+    }
+
+    /**
+    Return a new [`ByteChunker`] wrapping the given reader that will chunk
+    its output by delimiting it with occurrences of any of the supplied
+    byte strings, which are searched for with an
+    [Aho-Corasick](https://docs.rs/aho-corasick) automaton rather than a
+    regex. Of occurrences starting at the same place, the one whose
+    literal comes first in `literals` wins, and the index of the literal
+    that matched is what [`ByteChunker::with_delimiter_kinds`] reports.
+    Empty literals never delimit anything.
+
+    [`ByteChunker::fence`] will return a regex equivalent to the set of
+    literals.
+
+    This requires the `aho-corasick` feature.
+
+    ```
+    use regex_chunker::{ByteChunker, DelimiterKind, MatchDisposition};
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"one<br>two<hr>three";
+    let chunks: Vec<(Vec<u8>, DelimiterKind)> =
+        ByteChunker::with_literals(Cursor::new(text), &[b"<br>", b"<hr>"])?
+            .with_match(MatchDisposition::Append)
+            .with_delimiter_kinds()
+            .collect::<Result<_, _>>()?;
+
+    assert_eq!(
+        &chunks,
+        &[
+            (b"one<br>".to_vec(), DelimiterKind::Pattern(0)),
+            (b"two<hr>".to_vec(), DelimiterKind::Pattern(1)),
+            (b"three".to_vec(), DelimiterKind::None),
+        ]
+    );
+    # Ok(())
+    # }
+    ```
+    */
+    #[cfg(feature = "aho-corasick")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "aho-corasick")))]
+    pub fn with_literals(source: R, literals: &[&[u8]]) -> Result<Self, RcErr> {
+        let literal_set =
+            LiteralSet::new(literals.iter().map(|lit| lit.to_vec()).collect(), false)?;
+        let alternation = match literals {
+            // This never matches anything.
+            [] => String::from(r"[^\s\S]"),
+            _ => literals
+                .iter()
+                .map(|lit| {
+                    let bytes: String = lit.iter().map(|b| format!(r"\x{:02x}", b)).collect();
+                    format!("(?-u:{})", bytes)
+                })
+                .collect::<Vec<_>>()
+                .join("|"),
+        };
+        let fence = Regex::new(&alternation)?;
+
+        let mut chunker = Self::with_regex(source, fence);
+        chunker.max_match_len = literals.iter().map(|lit| lit.len()).max();
+        chunker.matcher.literals = Some(literal_set);
+        Ok(chunker)
     }
 
     /**
     Builder-pattern method for setting the read buffer size.
-    Default size is 1024 bytes.
+    Default size is 1024 bytes. A size of zero is treated as one (reading
+    into an empty buffer always reads nothing, which would look like the
+    end of the source).
+
+    This has no effect on a chunker created with
+    [`ByteChunker::from_buf_read`], which reads from its source's
+    buffer instead.
      */
     pub fn with_buffer_size(mut self, size: usize) -> Self {
-        self.read_buff.resize(size, 0);
+        if self.fill.is_some() {
+            return self;
+        }
+        self.read_buff.resize(size.max(1), 0);
         self.read_buff.shrink_to_fit();
         self
     }
 
+    /**
+    Builder-pattern method for releasing memory after an unusually large
+    chunk. Whenever a chunk is taken out of the chunker's search buffer
+    and the buffer's capacity is more than `threshold` bytes, its
+    capacity is reduced to what it holds, or the read buffer size (see
+    [`ByteChunker::with_buffer_size`]) or search buffer capacity (see
+    [`ByteChunker::with_search_capacity`]), whichever is largest.
+
+    This matters most for chunks returned by
+    [`ByteChunker::next_borrowed`] (and so
+    [`ByteChunker::write_all_to`]), which are taken from the search
+    buffer in place; a chunk returned by `.next()` usually takes the
+    memory that held it along with it. By default, the buffer is never
+    shrunk, which avoids reallocating it.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let mut text = vec![b'x'; 1 << 20];
+    text.extend_from_slice(b"\nsmall\n");
+    let mut chunker = ByteChunker::new(Cursor::new(text), r"\n")?
+        .with_shrink_threshold(64 * 1024);
+
+    assert_eq!(chunker.next_borrowed().unwrap()?.len(), 1 << 20);
+    assert_eq!(&*chunker.next_borrowed().unwrap()?, b"small");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_shrink_threshold(mut self, threshold: usize) -> Self {
+        self.shrink_threshold = Some(threshold);
+        self
+    }
+
+    /**
+    Builder-pattern method for reserving `capacity` bytes for the buffer
+    the chunker searches for delimiters, so it doesn't need reallocating
+    as it fills up.
+
+    By default, a chunk returned by `.next()` takes the memory that held
+    it along with it, and the rest of the buffer is moved to a new
+    allocation that has to grow again as more data is read. With a
+    capacity set, the buffer keeps its memory, and each chunk is copied
+    out of it instead. With a capacity a little larger than a typical
+    chunk plus [`ByteChunker::with_buffer_size`], the buffer should
+    rarely need to grow at all, and each chunk costs one allocation of
+    just its own size. The buffer can still grow past `capacity` to hold
+    a larger chunk, as governed by [`ByteChunker::with_growth_policy`].
+    */
+    pub fn with_search_capacity(mut self, capacity: usize) -> Self {
+        self.search_capacity = capacity;
+        self.search_buff
+            .reserve(capacity.saturating_sub(self.search_buff.len()));
+        self
+    }
+
+    /**
+    Builder-pattern method for controlling how the search buffer grows
+    when more data comes in than it has room for. Default value is
+    [`GrowthPolicy::Double`]; [`GrowthPolicy::Fixed`] bounds how much
+    more memory than necessary the buffer can take up, at the cost of
+    reallocating more often.
+
+    ```
+    use regex_chunker::{ByteChunker, GrowthPolicy};
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"one\ntwo\nthree\n";
+    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), r"\n")?
+        .with_search_capacity(64)
+        .with_growth_policy(GrowthPolicy::Fixed(4096))
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"one"[..], b"two", b"three"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_growth_policy(mut self, policy: GrowthPolicy) -> Self {
+        self.growth = policy;
+        self
+    }
+
+    /* Shrink the search buffer if it's grown past the shrink threshold. */
+    fn maybe_shrink(&mut self) {
+        if let Some(threshold) = self.shrink_threshold {
+            if self.search_buff.capacity() > threshold {
+                let baseline = self
+                    .read_buff
+                    .len()
+                    .max(self.search_capacity)
+                    .max(self.search_buff.len());
+                self.search_buff.shrink_to(baseline);
+            }
+        }
+    }
+
+    /**
+    Builder-pattern method for limiting how much of the search buffer gets
+    searched again when more bytes are read and the delimiter still
+    hasn't been found.
+
+    If the longest possible match of the delimiter pattern is known (it
+    is for a chunker made with [`ByteChunker::new`] from a pattern
+    without unbounded repetition like `+` or `*`), only the last few
+    bytes of what has already been searched are searched again, and this
+    setting is ignored. Otherwise, by default, the whole buffer is
+    searched again, which can get slow when delimiters are far apart.
+    Setting a window of `size` bytes lets the search resume from `size`
+    bytes before the end of what's been searched already, but a match
+    longer than `size` bytes that straddles the boundary between reads
+    may then be missed or cut short.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"a long, long, long line;another one";
+    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ";+")?
+        .with_buffer_size(4)
+        .with_rescan_window(8)
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"a long, long, long line"[..], b"another one"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_rescan_window(mut self, size: usize) -> Self {
+        self.rescan_window = Some(size);
+        self
+    }
+
+    /**
+    Builder-pattern method for returning chunks as soon as their
+    delimiters arrive, for interactive sources like a terminal or a
+    socket, where the rest of the data might not come for a while.
+    Default is `false`.
+
+    The chunker always returns every chunk it already has before reading
+    again, and a read only waits when the source has nothing at all to
+    give. But a match that runs right up to the end of what's been read
+    so far might turn out to be longer once more arrives (`\n+` might
+    match more newlines, say), so normally the chunker reads more before
+    it trusts one. In eager mode it trusts it straight away. The cost is
+    that a delimiter split between two reads is taken as two delimiters,
+    with an empty chunk between them in [`MatchDisposition::Drop`] mode.
+    (A match [filter](ByteChunker::with_match_filter) still gets to see
+    what comes after a match before it's trusted.)
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Read;
+
+    /* Answers the first read, then hangs. */
+    struct Prompt(bool);
+    impl Read for Prompt {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            assert!(!self.0, "the chunker waited for more input");
+            self.0 = true;
+            buf[..4].copy_from_slice(b"yes\n");
+            Ok(4)
+        }
+    }
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let mut chunker = ByteChunker::new(Prompt(false), r"\n+")?.with_eager(true);
+    assert_eq!(chunker.next().unwrap()?, b"yes");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_eager(mut self, yes: bool) -> Self {
+        self.eager = yes;
+        self
+    }
+
+    /**
+    Builder-pattern method for keeping track of how far the chunker has
+    gotten through its source: every time the total number of bytes read
+    from the source passes a multiple of `interval_bytes`, `callback` is
+    called with that total. This happens while the chunker reads, whether
+    or not it finds any chunks, so it's useful for long stretches without
+    a delimiter (and for big sources generally). An interval of zero is
+    treated as one.
+
+    The callback is called from within `.next()` (or whatever else reads
+    from the source), so it shouldn't take long. A read that takes the
+    total past several multiples at once only calls it once. After a
+    [`reset`](ByteChunker::reset), the count starts over; after a
+    [`seek_to`](ByteChunker::seek_to), it starts from the new position.
+    A `ByteChunker` with a progress callback can still be cloned, but the
+    clones share the one callback.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::{io::Cursor, sync::mpsc};
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let (tx, rx) = mpsc::channel();
+    let text = vec![b'x'; 10_000];
+    let chunks = ByteChunker::new(Cursor::new(text), ",")?
+        .with_buffer_size(1000)
+        .with_progress(4096, move |n| tx.send(n).unwrap())
+        .count();
+
+    assert_eq!(chunks, 1);
+    assert_eq!(rx.iter().collect::<Vec<u64>>(), vec![5000, 9000]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_progress<F>(mut self, interval_bytes: u64, callback: F) -> Self
+    where
+        F: FnMut(u64) + Send + 'static,
+    {
+        self.progress = Some((interval_bytes.max(1), Arc::new(Mutex::new(callback))));
+        self
+    }
+
+    /**
+    Builder-pattern method for telling the chunker how many bytes are
+    left to be read from its source, which lets [`Iterator::size_hint`]
+    give an upper bound on the number of chunks to come (at most one more
+    than the number of bytes). This lets, for example, `.collect()`
+    allocate a big enough `Vec` up front. The count goes down as the
+    chunker reads from the source.
+
+    The chunker doesn't check the number, so getting it wrong won't
+    cause any problems besides bad size hints.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"one,two,three";
+    let chunker = ByteChunker::new(Cursor::new(text), ",")?
+        .with_size_hint(text.len() as u64);
+
+    assert_eq!(chunker.size_hint(), (0, Some(14)));
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_size_hint(mut self, total_bytes: u64) -> Self {
+        self.source_remaining = Some(total_bytes);
+        self
+    }
+
     /**
     Builder-pattern method for controlling how the chunker behaves when
     encountering an error in the course of its operation. Default value
@@ -145,139 +879,2100 @@ impl<R> ByteChunker<R> {
     }
 
     /**
-    Builder-pattern method for controlling what the chunker does with the
-    matched text. Default value is [`MatchDisposition::Drop`].
-     */
-    pub fn with_match(mut self, behavior: MatchDisposition) -> Self {
-        self.match_dispo = behavior;
-        if matches!(behavior, MatchDisposition::Drop | MatchDisposition::Append) {
-            // If we swtich to one of these two dispositions, we
-            // need to be sure we reset the scan_start_offset, or
-            // else we'll never scan the beginning of our buffer.
-            self.scan_start_offset = 0;
-        }
+    Builder-pattern method for suppressing empty chunks, like the ones
+    produced between adjacent delimiters, or before a delimiter at the
+    very beginning of the source. This includes an empty final chunk at
+    the end of the source. By default, empty chunks are returned.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b", one, ,two,";
+    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), "[ ,]")?
+        .skip_empty()
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"one"[..], b"two"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn skip_empty(mut self) -> Self {
+        self.skip_empty = true;
         self
     }
 
     /**
-    Consumes the [`ByteChunker`] and returns its wrapped `Read`er.
-    The `ByteChunker` may have read some data from its source that may not
-    yet have been returned or successfully matched; this data may be lost.
-    To retrieve that data, see [`ByteChunker::into_innards`].
+    Builder-pattern method for controlling whether the chunker returns
+    an empty first chunk, which is what comes before a delimiter at the
+    very beginning of the source (in
+    [`MatchDisposition::Drop`] or [`MatchDisposition::Prepend`] mode).
+    Other empty chunks are unaffected. Default is `true`.
+
+    [`ByteChunker::skip_empty`] takes precedence: if it's been called,
+    no empty chunks are returned, leading or otherwise.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"\none\n\ntwo";
+    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), r"\n")?
+        .with_leading_empty(false)
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"one"[..], b"", b"two"]);
+    # Ok(())
+    # }
+    ```
     */
-    pub fn into_inner(self) -> R {
-        self.source
+    pub fn with_leading_empty(mut self, yes: bool) -> Self {
+        self.leading_empty = yes;
+        self
     }
 
-    /**
-    Consumes the [`ByteChunker`] and returns its wrapped `Read`er, as well
-    as any not-yet-processed data that has been read. If this unprocessed
-    data is unimportant, and you just want the reader back, use the more
-    traditional [`ByteChunker::into_inner`].
+    /*
+    Note that a chunk has been produced, and return whether it should be
+    discarded, given whether it's empty.
     */
-    pub fn into_innards(self) -> (R, Vec<u8>) {
-        (self.source, self.search_buff)
+    fn discard(&mut self, empty: bool) -> bool {
+        let leading = !self.started;
+        self.started = true;
+        empty && (self.skip_empty || (leading && !self.leading_empty))
     }
 
     /**
-    Creates a [`CustomChunker`] by combining this `ByteChunker` with an
-    `Adapter` type.
+    Builder-pattern method for batching up small chunks. Successive chunks
+    will be concatenated until their combined length is at least `size`
+    bytes, and then returned as a single chunk. Whatever has accumulated
+    when the source runs out is returned even if it's smaller. By default
+    chunks are not combined.
+
+    In [`MatchDisposition::Drop`] mode, the matched text _between_ the
+    combined chunks is kept (only the match following the last one is
+    dropped); in the other modes the matches are already part of the
+    chunks being combined.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"a\nb\nc\nlong line\nd\ne";
+    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), "\n")?
+        .with_min_chunk_size(4)
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"a\nb\nc"[..], b"long line", b"d\ne"]);
+    # Ok(())
+    # }
+    ```
     */
-    pub fn with_adapter<A>(self, adapter: A) -> CustomChunker<R, A> {
-        (self, adapter).into()
+    pub fn with_min_chunk_size(mut self, size: usize) -> Self {
+        self.min_chunk_size = Some(size);
+        self
     }
 
-    pub fn with_simple_adapter<A>(self, adapter: A) -> SimpleCustomChunker<R, A>
-    {
-        (self, adapter).into()
+    /**
+    Builder-pattern method for limiting the size of the chunks returned.
+    A chunk larger than `size` bytes will be returned in `size`-byte
+    pieces (the last of which may be smaller) across successive calls to
+    `.next()` before the chunker resumes looking for delimiters. A `size`
+    of zero is treated as one. By default chunks are returned whole.
+
+    Unlike [`ByteChunker::with_max_chunk_size`], this doesn't limit how
+    much the chunker buffers, only how much it returns at once.
+
+    Chunks are split on byte boundaries, with no regard for UTF-8, so a
+    multibyte character may be split between two pieces. A
+    [`StringAdapter`](crate::StringAdapter) will put it back together if
+    it's built with
+    [`join_split_chars`](crate::StringAdapter::join_split_chars), but a
+    piece that was only part of a character won't yield a `String` of
+    its own.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"tiny,enormous,wee";
+    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ",")?
+        .with_max_yield_size(4)
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"tiny"[..], b"enor", b"mous", b"wee"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_max_yield_size(mut self, size: usize) -> Self {
+        self.max_yield_size = Some(size.max(1));
+        self
     }
 
-    /*
-    Search the search_buffer for a match; if found, return the next chunk
-    of bytes to be returned from ]`Iterator::next`].
+    /**
+    Builder-pattern method for making chunks overlap, as for sliding
+    windows or n-grams: each chunk returned is preceded by the last
+    `bytes` bytes of the chunk returned before it (or all of that chunk,
+    if it's shorter). The first chunk has nothing before it. An overlap
+    of zero, the default, turns this off.
+
+    The overlap is taken from the chunks as they're returned, after any
+    merging or splitting, so what it contains depends on the
+    [`MatchDisposition`]. With [`MatchDisposition::Drop`] it's only ever
+    chunk content, never delimiters; with [`MatchDisposition::Append`]
+    or [`MatchDisposition::Prepend`] the delimiter is part of the chunk,
+    so it can be part of the overlap too. Chunks that are skipped (with
+    [`ByteChunker::skip_empty`] or [`ByteChunker::with_skip`]) don't
+    contribute to it.
+
+    The positions reported by [`ByteChunker::with_offsets`] are those of
+    the chunk as found in the source, not counting the overlap, which
+    comes from somewhere before it.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"abc,def,g,hij";
+    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ",")?
+        .with_overlap(2)
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"abc"[..], b"bcdef", b"efg", b"fghij"]);
+    # Ok(())
+    # }
+    ```
     */
-    fn scan_buffer(&mut self) -> Option<Vec<u8>> {
-        let (start, end) = match self
-            .fence
-            .find_at(&self.search_buff, self.scan_start_offset)
-        {
-            Some(m) => {
-                self.last_scan_matched = true;
-                (m.start(), m.end())
-            }
-            None => {
-                self.last_scan_matched = false;
-                return None;
-            }
-        };
+    pub fn with_overlap(mut self, bytes: usize) -> Self {
+        self.overlap = bytes;
+        self
+    }
 
-        let mut new_buff;
-        match self.match_dispo {
-            MatchDisposition::Drop => {
-                new_buff = self.search_buff.split_off(end);
-                self.search_buff.resize(start, 0);
-            }
-            MatchDisposition::Append => {
-                new_buff = self.search_buff.split_off(end);
-            }
-            MatchDisposition::Prepend => {
-                new_buff = self.search_buff.split_off(start);
-                self.scan_start_offset = end - start;
-            }
+    /* Prepend the overlap from the last chunk returned to `chunk`, and
+    keep the end of the result for the next one. */
+    fn overlap(&mut self, chunk: Vec<u8>) -> Vec<u8> {
+        if self.overlap == 0 {
+            return chunk;
         }
-
-        std::mem::swap(&mut new_buff, &mut self.search_buff);
-        Some(new_buff)
+        let chunk = if self.overlap_tail.is_empty() {
+            chunk
+        } else {
+            let mut v = std::mem::take(&mut self.overlap_tail);
+            v.extend_from_slice(&chunk);
+            v
+        };
+        let keep = chunk.len().saturating_sub(self.overlap);
+        self.overlap_tail.extend_from_slice(&chunk[keep..]);
+        chunk
     }
 
-    // Function for wrapping types that need this information.
-    #[allow(dead_code)]
-    #[inline(always)]
-    fn buff_size(&self) -> usize {
-        return self.read_buff.len();
+    /**
+    Builder-pattern method for capping the number of bytes the chunker
+    will buffer while looking for a delimiter. Once more than `size` bytes
+    have accumulated ahead of the next match (or with no match at all),
+    the chunker responds according to its [`OversizePolicy`] (see
+    [`ByteChunker::on_oversize`]). By default there is no limit.
+
+    The limit applies to the bytes _preceding_ a delimiter, so in
+    [`MatchDisposition::Append`] mode a chunk may exceed `size` by the
+    length of the matched text; in [`MatchDisposition::Prepend`] mode the
+    text of the previous match counts toward the limit.
+
+    ```
+    use regex_chunker::{ByteChunker, OversizePolicy};
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"short,much much longer,x";
+    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ",")?
+        .with_max_chunk_size(8)
+        .on_oversize(OversizePolicy::Yield)
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(
+        &chunks,
+        &[&b"short"[..], b"much muc", b"h longer", b"x"]
+    );
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_max_chunk_size(mut self, size: usize) -> Self {
+        self.max_chunk_size = Some(size);
+        self
     }
-}
 
-impl<R> Debug for ByteChunker<R> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ByteChunker")
-            .field("source", &std::any::type_name::<R>())
-            .field("fence", &self.fence)
-            .field("read_buff", &String::from_utf8_lossy(&self.read_buff))
-            .field("search_buff", &String::from_utf8_lossy(&self.search_buff))
-            .field("error_status", &self.error_status)
-            .field("match_dispo", &self.match_dispo)
-            .field("last_scan_matched", &self.last_scan_matched)
-            .field("scan_start_offset", &self.scan_start_offset)
-            .finish()
+    /**
+    Builder-pattern method for stopping after `n` chunks. Once it has
+    yielded `n` chunks, the chunker returns `None` without reading any
+    more from its source, so unlike with [`Iterator::take`], the chunker
+    (and its source, with [`ByteChunker::into_innards`]) can still be
+    used afterward. The count starts over if the chunker is
+    [`reset`](ByteChunker::reset).
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"Host: example.com\nAccept: text/plain\n\nbody\nof the message";
+    let mut chunker = ByteChunker::new(Cursor::new(text), r"\n")?.with_chunk_limit(3);
+
+    let headers: Vec<Vec<u8>> = (&mut chunker).collect::<Result<_, _>>()?;
+    assert_eq!(headers.len(), 3);
+    assert!(headers[2].is_empty());
+
+    let (_, unread) = chunker.into_innards();
+    assert_eq!(&unread, b"body\nof the message");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_chunk_limit(mut self, n: usize) -> Self {
+        self.chunk_limit = Some(n as u64);
+        self
     }
-}
 
-/**
-The [`ByteChunker`] specifically doesn't supply an implementation of
-[`Iterator::size_hint`] because, in general, it's impossible to tell
-how much data is left in a reader.
-*/
-impl<R: Read> Iterator for ByteChunker<R> {
-    type Item = Result<Vec<u8>, RcErr>;
+    /**
+    Builder-pattern method for discarding the first `n` chunks, like a
+    header block. Unlike with [`Iterator::skip`], the chunker's methods
+    remain available. Skipped chunks don't count toward
+    [`ByteChunker::chunk_count`] or the
+    [chunk limit](ByteChunker::with_chunk_limit), and if the chunker is
+    [`reset`](ByteChunker::reset), the first `n` chunks of the new source
+    will be skipped, too.
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.error_status == ErrorStatus::Errored {
-            return None;
-        }
+    The source still has to be scanned for delimiters to find where the
+    skipped chunks end, so skipping them costs as much as reading them;
+    this is a convenience, not an optimization.
 
-        loop {
-            if !self.last_scan_matched {
-                match self.source.read(&mut self.read_buff) {
-                    Err(e) => match e.kind() {
-                        ErrorKind::WouldBlock | ErrorKind::Interrupted => {
-                            spin_loop();
-                            continue;
-                        }
-                        _ => match self.error_status {
-                            ErrorStatus::Ok | ErrorStatus::Errored => {
-                                self.error_status = ErrorStatus::Errored;
-                                return Some(Err(e.into()));
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let csv = b"name,age\nAlice,31\nBob,27\n";
+    let rows: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(csv), r"\n")?
+        .with_skip(1)
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&rows, &[&b"Alice,31"[..], b"Bob,27"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_skip(mut self, n: usize) -> Self {
+        self.skip_chunks = n as u64;
+        self.to_skip = n as u64;
+        self
+    }
+
+    /**
+    Builder-pattern method for controlling what the chunker does when it
+    exceeds the size set with [`ByteChunker::with_max_chunk_size`].
+    Default value is [`OversizePolicy::Error`].
+    */
+    pub fn on_oversize(mut self, policy: OversizePolicy) -> Self {
+        self.oversize_policy = policy;
+        self
+    }
+
+    /**
+    Builder-pattern method for controlling what the chunker does with
+    any data left at the end of its source after the last delimiter.
+    Default value is [`TrailingPolicy::Emit`], which returns it as the
+    final chunk. For formats where every record must be terminated,
+    [`TrailingPolicy::Error`] detects truncated input.
+
+    In [`MatchDisposition::Prepend`] mode, chunks _begin_ with
+    delimiters instead of ending with them, so the final chunk (which
+    begins with the last match) always counts as leftover data.
+
+    ```
+    use regex_chunker::{ByteChunker, RcErr, TrailingPolicy};
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"complete\0also complete\0trunc";
+    let mut chunker = ByteChunker::new(Cursor::new(text), r"\x00")?
+        .with_trailing(TrailingPolicy::Error);
+
+    assert_eq!(chunker.next().unwrap()?, b"complete");
+    assert_eq!(chunker.next().unwrap()?, b"also complete");
+    // The truncated record comes with the error.
+    match chunker.next() {
+        Some(Err(RcErr::UnterminatedTail(tail))) => assert_eq!(tail, b"trunc"),
+        other => panic!("expected an unterminated tail, got {:?}", other),
+    }
+    assert!(chunker.next().is_none());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_trailing(mut self, policy: TrailingPolicy) -> Self {
+        self.trailing = policy;
+        self
+    }
+
+    /**
+    Builder-pattern method for controlling what the chunker does when
+    its source (like a non-blocking socket) isn't ready to be read yet.
+    Default value is [`BlockPolicy::Yield`]. This doesn't apply to a read
+    interrupted by a signal
+    ([`ErrorKind::Interrupted`](std::io::ErrorKind::Interrupted)), which
+    is always retried immediately.
+
+    ```
+    use regex_chunker::{BlockPolicy, ByteChunker};
+    use std::{io::Cursor, time::Duration};
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let chunker = ByteChunker::new(Cursor::new("a b"), " ")?
+        .with_block_policy(BlockPolicy::Sleep(Duration::from_millis(10)));
+    assert_eq!(chunker.count(), 2);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_block_policy(mut self, policy: BlockPolicy) -> Self {
+        self.block_policy = policy;
+        self
+    }
+
+    /**
+    Builder-pattern method for giving up on a source that takes too long
+    to produce any data. When a read (including any retries after the
+    source reports it isn't ready) has been waiting for longer than
+    `timeout`, the chunker returns an [`RcErr::Timeout`]; it's treated
+    like any other error, according to [`ByteChunker::on_error`]. If the
+    chunker is told to keep going, the next call starts a fresh wait.
+
+    The chunker can't interrupt a read that's blocked, so this needs the
+    source to cooperate. A non-blocking source (one that returns
+    [`ErrorKind::WouldBlock`] when it has nothing) is retried according
+    to the [`BlockPolicy`] until the time is up. A blocking source has
+    to honor a timeout of its own, like one set with
+    [`TcpStream::set_read_timeout`](std::net::TcpStream::set_read_timeout);
+    when a read fails with [`ErrorKind::WouldBlock`] or
+    [`ErrorKind::TimedOut`] (which is what such sources do) and the
+    time is up, that becomes an `RcErr::Timeout`. A source that blocks
+    indefinitely blocks the chunker indefinitely.
+
+    ```
+    use regex_chunker::{ByteChunker, RcErr};
+    use std::{io::{ErrorKind, Read}, time::Duration};
+
+    // A source that never has anything ready.
+    struct Idle;
+    impl Read for Idle {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(ErrorKind::WouldBlock.into())
+        }
+    }
+
+    # fn main() -> Result<(), RcErr> {
+    let mut chunker = ByteChunker::new(Idle, "\n")?
+        .with_read_timeout(Duration::from_millis(10));
+    assert_eq!(chunker.next(), Some(Err(RcErr::Timeout)));
+    assert_eq!(chunker.next(), None);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /**
+    Builder-pattern method for following the source the way `tail -f`
+    does: when a read returns no data, rather than flushing what it has
+    buffered and finishing, the chunker sleeps for `poll_interval` and
+    tries again, so data appended to the source later turns into more
+    chunks.
+
+    Following only stops with [`ByteChunker::stop_following`] or
+    [`FollowHandle::stop_following`] (see [`ByteChunker::follow_handle`]);
+    after that, the next time the chunker reaches the end of its source
+    it flushes any partial chunk (according to its [`TrailingPolicy`])
+    and finishes as usual.
+
+    For a regular file, reaching the end only means nobody has written
+    any more yet, which is the case this is meant for. For a pipe or a
+    socket, a read returning no data means the other end has closed it
+    and there will never be more, but a [`Read`]er has no way to tell the
+    chunker which kind of source it is, so a following chunker keeps
+    polling a closed pipe until it's told to stop. (Don't follow pipes;
+    reading one already waits for more data.)
+
+    While waiting, a delimiter match that ends at the end of the data so
+    far, and that might get longer (like a match of `\n+`), is held back
+    with its chunk until more data arrives or following stops.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::{fs::{File, OpenOptions}, io::Write, thread, time::Duration};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join("regex_chunker_follow_doctest.txt");
+    std::fs::write(&path, "one\ntwo\n")?;
+
+    let chunker = ByteChunker::new(File::open(&path)?, "\n")?
+        .with_follow(Duration::from_millis(10));
+    let handle = chunker.follow_handle();
+
+    let writer = {
+        let path = path.clone();
+        thread::spawn(move || {
+            let mut f = OpenOptions::new().append(true).open(path).unwrap();
+            f.write_all(b"three\nfour").unwrap();
+            handle.stop_following();
+        })
+    };
+
+    let chunks: Vec<Vec<u8>> = chunker.collect::<Result<_, _>>()?;
+    writer.join().unwrap();
+    std::fs::remove_file(&path)?;
+    assert_eq!(&chunks, &[&b"one"[..], b"two", b"three", b"four"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_follow(mut self, poll_interval: Duration) -> Self {
+        self.follow = Some(poll_interval);
+        self
+    }
+
+    /**
+    Stop following the source (see [`ByteChunker::with_follow`]). The
+    next time the chunker reaches the end of its source, it flushes any
+    partial chunk and finishes.
+    */
+    pub fn stop_following(&mut self) {
+        self.follow = None;
+    }
+
+    /**
+    Return a [`FollowHandle`] that can stop this chunker following its
+    source (see [`ByteChunker::with_follow`]) from another thread. Clones
+    of the chunker share the handle.
+    */
+    pub fn follow_handle(&self) -> FollowHandle {
+        self.follow_handle.clone()
+    }
+
+    /* The time to wait for more data at the end of the source, if the
+    chunker is still following it. */
+    fn follow_interval(&self) -> Option<Duration> {
+        match self.follow {
+            Some(interval) if !self.follow_handle.is_stopped() => Some(interval),
+            _ => None,
+        }
+    }
+
+    /**
+    Builder-pattern method for controlling what the chunker does with the
+    matched text. Default value is [`MatchDisposition::Drop`].
+
+    This can be changed partway through a source, and no bytes are lost
+    or repeated (besides the delimiters that [`MatchDisposition::Drop`]
+    drops). After switching away from [`MatchDisposition::Prepend`],
+    though, the delimiter that ended the last chunk hasn't been returned
+    yet: in [`MatchDisposition::Drop`] mode it's dropped, and in
+    [`MatchDisposition::Append`] mode it begins the next chunk. After
+    switching to `Prepend`, the next chunk begins with whatever follows
+    the last delimiter, since that delimiter has already been dealt with.
+     */
+    pub fn with_match(mut self, behavior: MatchDisposition) -> Self {
+        let was_prepending = matches!(self.match_dispo, MatchDisposition::Prepend);
+        self.match_dispo = behavior;
+        match behavior {
+            // In Prepend mode, the match that ended the last chunk is
+            // left at the front of the buffer, and scan_start_offset
+            // keeps us from finding it again. In Append mode it's
+            // skipped over once, to become part of the next chunk.
+            MatchDisposition::Append => {}
+            MatchDisposition::Drop => {
+                let n = self.scan_start_offset;
+                // A chunker reading from a `BufRead`'s buffer may have
+                // left some or all of the match in there.
+                let buffered = self.search_buff.len() - self.pending_drain;
+                self.pending_drain += n.min(buffered);
+                self.source_skip += n.saturating_sub(buffered);
+                self.buff_offset += n as u64;
+                self.scanned_to = self.scanned_to.saturating_sub(n);
+                self.scan_start_offset = 0;
+            }
+            MatchDisposition::Prepend if !was_prepending => {
+                // Whatever match began the next chunk is gone.
+                self.front_captures.clear();
+                self.front_kind = None;
+            }
+            MatchDisposition::Prepend => {}
+        }
+        self
+    }
+
+    /**
+    Builder-pattern method for vetting each match of the delimiter before
+    it's used, for things the `regex` crate can't express, like
+    lookaround. The `predicate` is passed the chunker's buffer and the
+    range of the match within it, and returns whether the match delimits
+    a chunk. A rejected match is treated as part of the chunk, and the
+    search for a delimiter carries on from the end of it.
+
+    The buffer starts at the beginning of the current chunk (or, in
+    [`MatchDisposition::Prepend`] mode, the match before it), so there's
+    nothing before that to look behind at. A match isn't judged until at
+    least one byte after it has been read (or the source has ended), so
+    there's always something to look ahead at unless the match is at the
+    very end of the source. The predicate may be called more than once
+    for the same match, and should give the same answer each time.
+
+    A `ByteChunker` with a match filter can still be cloned, but the
+    clones share the one predicate.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    // Split on `;`, but not `;;`.
+    let text = b"a;b;;c;d";
+    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ";")?
+        .with_match_filter(|buff: &[u8], m: std::ops::Range<usize>| {
+            buff.get(m.end) != Some(&b';') && (m.start == 0 || buff[m.start - 1] != b';')
+        })
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"a"[..], b"b;;c", b"d"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn with_match_filter<F>(mut self, predicate: F) -> Self
+    where
+        F: FnMut(&[u8], Range<usize>) -> bool + Send + 'static,
+    {
+        self.matcher.match_filter = Some(Arc::new(Mutex::new(predicate)));
+        self.rescan();
+        self
+    }
+
+    /**
+    Change the pattern used to delimit chunks, for sources whose framing
+    changes partway through. Any data that has already been read from
+    the source but not yet returned in a chunk is scanned again with the
+    new pattern, so nothing is lost. (This also replaces any patterns
+    supplied with [`ByteChunker::with_patterns`].)
+
+    A chunk that has already been [`peek`](ByteChunker::peek)ed at, or
+    that is being returned in pieces (see
+    [`ByteChunker::with_max_yield_size`]), was delimited with the old
+    pattern. In [`MatchDisposition::Prepend`] mode, the next chunk still
+    begins with the match of the old pattern that ended the previous one.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"HELLO v2\nREADY\nmsg one|msg two|";
+    let mut chunker = ByteChunker::new(Cursor::new(text), r"\n")?;
+
+    assert_eq!(chunker.next().unwrap()?, b"HELLO v2");
+    assert_eq!(chunker.next().unwrap()?, b"READY");
+
+    chunker.set_delimiter(r"\|")?;
+    assert_eq!(chunker.next().unwrap()?, b"msg one");
+    assert_eq!(chunker.next().unwrap()?, b"msg two");
+    assert!(chunker.next().is_none());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn set_delimiter(&mut self, pattern: &str) -> Result<(), RcErr> {
+        self.matcher.fence = self.flags.regex(pattern)?;
+        self.max_match_len = self.flags.max_match_len(pattern);
+        self.look_around = self.flags.has_look_around(pattern);
+        self.matcher.literal = self.flags.literal_finder(pattern);
+        #[cfg(feature = "aho-corasick")]
+        {
+            self.matcher.literals = None;
+        }
+        self.matcher.multi = None;
+        self.rescan();
+        Ok(())
+    }
+
+    /**
+    Builder-pattern method for making the delimiter pattern match
+    letters regardless of case, as if it began with `(?i)`. Default
+    is `false`.
+
+    This and [`ByteChunker::multi_line`] and
+    [`ByteChunker::dot_matches_newline`] recompile the chunker's
+    pattern (or patterns) with the requested flags, which is why they
+    can fail. The flags stick: they also apply to a pattern supplied
+    later with [`ByteChunker::set_delimiter`]. If the chunker was created
+    with [`ByteChunker::with_regex`], the `Regex` is recompiled from its
+    pattern text, so any options it was built with that these methods
+    don't cover are lost. For a chunker created with
+    `ByteChunker::with_literals`, case-insensitivity only extends to
+    ASCII letters, and the other two flags have no effect.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"salt AND pepper and vinegar";
+    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), " and ")?
+        .case_insensitive(true)?
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"salt"[..], b"pepper", b"vinegar"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn case_insensitive(mut self, yes: bool) -> Result<Self, RcErr> {
+        self.flags.case_insensitive = yes;
+        self.recompile()?;
+        Ok(self)
+    }
+
+    /**
+    Builder-pattern method for making `^` and `$` in the delimiter
+    pattern match at the beginnings and ends of lines, as if it began
+    with `(?m)`. Default is `false`. See [`ByteChunker::case_insensitive`].
+
+    Note that whether a `^` or `$` match is at the edge of the chunker's
+    buffer depends on how the source's data has been read, so patterns
+    anchored without this flag rarely make sense.
+    */
+    pub fn multi_line(mut self, yes: bool) -> Result<Self, RcErr> {
+        self.flags.multi_line = yes;
+        self.recompile()?;
+        Ok(self)
+    }
+
+    /// Builder-pattern method for making `.` in the delimiter pattern
+    /// match `\n`, as if it began with `(?s)`. Default is `false`. See
+    /// [`ByteChunker::case_insensitive`].
+    pub fn dot_matches_newline(mut self, yes: bool) -> Result<Self, RcErr> {
+        self.flags.dot_matches_newline = yes;
+        self.recompile()?;
+        Ok(self)
+    }
+
+    /* Compile the chunker's patterns again with its current flags. */
+    fn recompile(&mut self) -> Result<(), RcErr> {
+        #[cfg(feature = "aho-corasick")]
+        if let Some(literal_set) = self.matcher.literals.take() {
+            let literals = literal_set.literals;
+            self.matcher.literals = Some(LiteralSet::new(literals, self.flags.case_insensitive)?);
+        }
+
+        let pattern = self.matcher.fence.as_str().to_owned();
+        self.matcher.fence = self.flags.regex(&pattern)?;
+        match &mut self.matcher.multi {
+            Some(multi) => {
+                let patterns = multi.set.patterns().to_vec();
+                multi.set = self.flags.regex_set(&patterns)?;
+                multi.regexes = patterns
+                    .iter()
+                    .map(|p| self.flags.regex(p))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.max_match_len = patterns
+                    .iter()
+                    .map(|p| self.flags.max_match_len(p))
+                    .collect::<Option<Vec<usize>>>()
+                    .and_then(|lens| lens.into_iter().max());
+                self.look_around = patterns.iter().any(|p| self.flags.has_look_around(p));
+            }
+            None => {
+                self.max_match_len = self.flags.max_match_len(&pattern);
+                self.look_around = self.flags.has_look_around(&pattern);
+                self.matcher.literal = self.flags.literal_finder(&pattern);
+            }
+        }
+        self.rescan();
+        Ok(())
+    }
+
+    /*
+    Search the whole buffer again before reading anything more, because
+    what the fence matches has changed.
+    */
+    fn rescan(&mut self) {
+        self.drain_pending();
+        self.scanned_to = 0;
+        self.last_scan_matched = true;
+    }
+
+    /// Get a reference to the compiled regular expression used to
+    /// delimit chunks.
+    pub fn fence(&self) -> &Regex {
+        &self.matcher.fence
+    }
+
+    /// Get a reference to the wrapped `Read`er.
+    pub fn get_ref(&self) -> &R {
+        &self.source
+    }
+
+    /**
+    Get a mutable reference to the wrapped `Read`er, for configuring it
+    (setting a socket's timeout, say).
+
+    Reading from (or seeking) the source directly will throw off the
+    chunker, which has probably already read ahead of where its last
+    chunk ended, and won't see the bytes read out from under it.
+    */
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.source
+    }
+
+    /**
+    Consumes the [`ByteChunker`] and returns its wrapped `Read`er.
+    The `ByteChunker` may have read some data from its source that may not
+    yet have been returned or successfully matched; this data may be lost.
+    To retrieve that data, see [`ByteChunker::into_innards`].
+    */
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+
+    /**
+    Consumes the [`ByteChunker`] and returns its wrapped `Read`er, as well
+    as any not-yet-processed data that has been read. If this unprocessed
+    data is unimportant, and you just want the reader back, use the more
+    traditional [`ByteChunker::into_inner`].
+    */
+    pub fn into_innards(mut self) -> (R, Vec<u8>) {
+        self.drain_pending();
+        (self.source, self.search_buff)
+    }
+
+    /**
+    Replace the chunker's source with `new_source`, returning the old
+    one, so the chunker can be reused. All the chunker's settings (and
+    its compiled regex and buffers, but not any
+    [size hint](ByteChunker::with_size_hint)) are kept, but it otherwise starts
+    over as if it were new: any data read from the old source but not
+    yet returned is discarded, and if it had stopped because of an
+    error, it will read from the new source.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let mut chunker = ByteChunker::new(Cursor::new("a,b"), ",")?;
+    assert_eq!(chunker.next().unwrap()?, b"a");
+
+    let old = chunker.reset(Cursor::new("c,d"));
+    assert_eq!(old.position(), 3);
+    let chunks: Vec<Vec<u8>> = chunker.collect::<Result<_, _>>()?;
+    assert_eq!(&chunks, &[b"c", b"d"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn reset(&mut self, new_source: R) -> R {
+        self.clear_state(0);
+        std::mem::replace(&mut self.source, new_source)
+    }
+
+    /*
+    Forget everything about the source read so far, as if starting over
+    with a new source whose first byte is at position `offset`.
+    */
+    fn clear_state(&mut self, offset: u64) {
+        self.search_buff.clear();
+        if self.error_status == ErrorStatus::Errored {
+            self.error_status = ErrorStatus::Ok;
+        }
+        self.last_scan_matched = false;
+        self.scan_start_offset = 0;
+        self.buff_offset = offset;
+        self.last_span = (offset, offset);
+        self.last_captures.clear();
+        self.front_captures.clear();
+        self.last_delim = None;
+        self.merged = None;
+        self.merged_start = 0;
+        self.merged_delim = None;
+        self.pieces = None;
+        self.pieces_start = 0;
+        self.pieces_delim = None;
+        self.pieces_captures.clear();
+        self.last_kind = None;
+        self.front_kind = None;
+        self.pieces_kind = None;
+        self.overlap_tail.clear();
+        self.scanned_to = 0;
+        self.pending_drain = 0;
+        self.source_skip = 0;
+        self.peeked = None;
+        self.source_remaining = None;
+        self.source_eof = false;
+        self.bytes_read = offset;
+        self.chunks_emitted = 0;
+        self.bytes_emitted = 0;
+        self.to_skip = self.skip_chunks;
+        self.started = false;
+        self.waiting_since = None;
+    }
+
+    /**
+    The number of chunks this chunker has successfully yielded (counting
+    one that has been [`peek`](ByteChunker::peek)ed at) since it was
+    created or last [`reset`](ByteChunker::reset).
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let mut chunker = ByteChunker::new(Cursor::new("one, two, three"), ", ")?;
+    chunker.next();
+    chunker.next();
+    assert_eq!(chunker.chunk_count(), 2);
+    // The dropped delimiters don't count.
+    assert_eq!(chunker.byte_count(), 6);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn chunk_count(&self) -> u64 {
+        self.chunks_emitted
+    }
+
+    /**
+    The total number of bytes in the chunks counted by
+    [`ByteChunker::chunk_count`]. In
+    [`MatchDisposition::Drop`] mode, this doesn't include the
+    bytes of the delimiters.
+    */
+    pub fn byte_count(&self) -> u64 {
+        self.bytes_emitted
+    }
+
+    /**
+    The number of bytes the chunker has read from its source but not yet
+    returned in a chunk (or dropped as a delimiter). This doesn't count
+    chunks being combined by [`ByteChunker::with_min_chunk_size`] or
+    split by [`ByteChunker::with_max_yield_size`].
+
+    With a delimiter that never matches, this grows with every read;
+    see [`ByteChunker::with_max_chunk_size`].
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let mut chunker = ByteChunker::new(Cursor::new("one;two;three"), ";")?;
+    assert_eq!(chunker.next().unwrap()?, b"one");
+    assert_eq!(chunker.buffered_len(), 9);
+    assert_eq!(chunker.scan_offset(), 0);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn buffered_len(&self) -> usize {
+        self.search_buff.len() - self.pending_drain
+    }
+
+    /**
+    Where, in the bytes counted by [`ByteChunker::buffered_len`], the
+    chunker will start looking for the next delimiter. This is past a
+    delimiter left at the front in [`MatchDisposition::Prepend`] mode,
+    and past data already searched without finding one (if the chunker
+    knows how long a match can be; see
+    [`ByteChunker::with_rescan_window`]).
+    */
+    pub fn scan_offset(&self) -> usize {
+        self.scan_from()
+    }
+
+    /**
+    Whether the chunker has reached the end of its source and yielded
+    everything it read from it. This tells a chunker that ran out of
+    data apart from one that was stopped early (by
+    [`ByteChunker::with_chunk_limit`], an error, or simply not being
+    iterated to the end), so the caller can decide whether to reopen or
+    reconnect to the source.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let mut chunker = ByteChunker::new(Cursor::new("a,b,c"), ",")?.with_chunk_limit(2);
+    chunker.by_ref().for_each(drop);
+    assert!(!chunker.is_exhausted());
+
+    let mut chunker = ByteChunker::new(Cursor::new("a,b,c"), ",")?;
+    chunker.by_ref().for_each(drop);
+    assert!(chunker.is_exhausted());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn is_exhausted(&self) -> bool {
+        self.source_eof
+            && self.search_buff.len() == self.pending_drain
+            && self.merged.is_none()
+            && self.pieces.is_none()
+            && !matches!(self.peeked, Some(Some(_)))
+    }
+
+    /**
+    Creates an [`OffsetChunker`] that yields each chunk along with its
+    position in the source stream.
+    */
+    pub fn with_offsets(self) -> OffsetChunker<R> {
+        self.into()
+    }
+
+    /**
+    Creates a [`CaptureChunker`] that yields each chunk along with the
+    capture groups of its delimiter.
+    */
+    pub fn with_captures(self) -> CaptureChunker<R> {
+        self.into()
+    }
+
+    /**
+    Creates a [`DelimitedChunker`] that yields each chunk along with the
+    delimiter that followed it. This sets the chunker's
+    [`MatchDisposition`] to [`MatchDisposition::Drop`], as the delimiter
+    is returned separately.
+    */
+    pub fn with_delimiters(self) -> DelimitedChunker<R> {
+        self.into()
+    }
+
+    /**
+    Creates a [`KindChunker`] that yields each chunk along with a
+    [`DelimiterKind`](crate::DelimiterKind) telling which pattern matched the delimiter
+    associated with it; this is most useful with
+    [`ByteChunker::with_patterns`].
+    */
+    pub fn with_delimiter_kinds(self) -> KindChunker<R> {
+        self.into()
+    }
+
+    /**
+    Creates a [`TerminalChunker`] that yields each chunk along with a flag
+    marking the last one. To know which chunk is last, it has to read one
+    chunk ahead; see the [`TerminalChunker`] docs.
+    */
+    pub fn with_terminal_flag(self) -> TerminalChunker<R> {
+        self.into()
+    }
+
+    /**
+    Creates a [`CustomChunker`] by combining this `ByteChunker` with an
+    `Adapter` type.
+    */
+    pub fn with_adapter<A: Adapter>(self, adapter: A) -> CustomChunker<R, A> {
+        (self, adapter).into()
+    }
+
+    /**
+    Creates a [`SimpleCustomChunker`] by combining this `ByteChunker` with
+    a [`SimpleAdapter`](crate::SimpleAdapter) type.
+    */
+    pub fn with_simple_adapter<A>(self, adapter: A) -> SimpleCustomChunker<R, A> {
+        (self, adapter).into()
+    }
+
+    /*
+    Where in the search buffer the next search for a match starts: past
+    the match at the front in `Prepend` mode, and past whatever's already
+    been searched (less enough to catch a match that was cut off at the
+    end last time), if we know how long a match can be.
+    */
+    fn scan_from(&self) -> usize {
+        match self.max_match_len.or(self.rescan_window) {
+            Some(overlap) => self.scanned_to.saturating_sub(overlap),
+            None => 0,
+        }
+        .max(self.scan_start_offset)
+    }
+
+    /*
+    Search the search_buffer for a match; if found, return the next chunk
+    of bytes to be returned from [`Iterator::next`]. `at_eof` is whether
+    the source has run out of bytes.
+    */
+    fn scan_buffer(&mut self, at_eof: bool) -> Option<Result<Taken, RcErr>> {
+        let found = self.matcher.find_accepted(
+            &self.search_buff,
+            self.scan_from(),
+            at_eof,
+            self.track_captures,
+        );
+
+        if let Some(max) = self.max_chunk_size {
+            let unmatched = match found {
+                Some((start, ..)) => start,
+                None => self.search_buff.len(),
+            };
+            if unmatched > max {
+                // There may be more to do with what's left in the
+                // buffer, so it should be scanned again next time.
+                self.last_scan_matched = true;
+                return self.handle_oversize(max);
+            }
+        }
+
+        let (start, end, groups, kind) = match found {
+            // A match that runs right up to the end of the buffer might
+            // turn out to be longer once more bytes arrive, or not to
+            // match at all if the fence has assertions (unless it's
+            // already as long as a match can be and it doesn't, or we're
+            // being eager), so we don't trust it until we've read more or
+            // there's nothing left to read.
+            Some((start, end, ..))
+                if !at_eof && end == self.search_buff.len() && !self.trusts_final(start, end) =>
+            {
+                self.last_scan_matched = false;
+                self.scanned_to = start;
+                return None;
+            }
+            Some(span) => {
+                self.last_scan_matched = true;
+                span
+            }
+            None => {
+                self.last_scan_matched = false;
+                self.scanned_to = self.search_buff.len();
+                return None;
+            }
+        };
+        // Whatever's left in the buffer after the match hasn't been
+        // searched yet.
+        self.scanned_to = 0;
+
+        if self.track_delims || self.min_chunk_size.is_some() {
+            // Reuse the last delimiter's allocation, if there is one.
+            let delim = self.last_delim.get_or_insert_with(Vec::new);
+            delim.clear();
+            delim.extend_from_slice(&self.search_buff[start..end]);
+        }
+
+        Some(Ok(self.accept_fence(start, end, groups, kind)))
+    }
+
+    /*
+    Whether to trust a match from `start` to `end` that runs right up to
+    the end of the data read so far, rather than wait to see whether more
+    data would make it longer (or change the match filter's mind).
+    */
+    fn trusts_final(&self, start: usize, end: usize) -> bool {
+        self.matcher.match_filter.is_none()
+            && (self.eager || (!self.look_around && self.max_match_len == Some(end - start)))
+    }
+
+    /*
+    Note that the match from `start` to `end`, with capture `groups`, of
+    pattern `kind`, delimits the next chunk, returning how much of the
+    data it's in goes with that chunk.
+    */
+    fn accept_fence(&mut self, start: usize, end: usize, groups: Groups, kind: usize) -> Taken {
+        let split = apply_disposition(self.match_dispo, start, end);
+        self.scan_start_offset = split.scan_offset;
+        match self.match_dispo {
+            MatchDisposition::Drop | MatchDisposition::Append => {
+                self.last_captures = groups;
+                self.last_kind = Some(kind);
+            }
+            MatchDisposition::Prepend => {
+                // The chunk we're returning begins with the _previous_
+                // match, so it gets that match's groups.
+                self.last_captures = std::mem::replace(&mut self.front_captures, groups);
+                self.last_kind = self.front_kind.replace(kind);
+            }
+        }
+        let taken = (split.len, split.consumed);
+        self.record_span(taken.0, taken.1);
+        taken
+    }
+
+    /*
+    Remove the chunk described by `taken` from the front of the search
+    buffer and return it (or, if it was found in the source's own
+    buffer, just return it).
+    */
+    fn take_front(&mut self, (len, consumed): Taken) -> Vec<u8> {
+        if let Some(chunk) = self.direct.take() {
+            return chunk;
+        }
+        /* Usually the chunk takes the buffer's memory with it, and what's
+        left goes in a new buffer, which saves moving it to the front.
+        But if the buffer's been given a capacity, it keeps that memory,
+        and the chunk is copied out. */
+        let chunk = if self.search_capacity > 0 {
+            let chunk = self.search_buff[..len].to_vec();
+            self.search_buff.drain(..consumed);
+            chunk
+        } else {
+            let mut chunk = self.search_buff.split_off(consumed);
+            std::mem::swap(&mut chunk, &mut self.search_buff);
+            chunk.truncate(len);
+            chunk
+        };
+        self.maybe_shrink();
+        chunk
+    }
+
+    /*
+    Remove the bytes of the chunk most recently returned by
+    `next_borrowed()`, which had to stay in the search buffer while
+    they were borrowed.
+    */
+    fn drain_pending(&mut self) {
+        if self.pending_drain > 0 {
+            self.search_buff.drain(..self.pending_drain);
+            self.pending_drain = 0;
+            self.maybe_shrink();
+        }
+    }
+
+    /*
+    Note the position of a chunk of `chunk_len` bytes taken from the front
+    of the search buffer, after which `consumed` bytes have been removed
+    from the buffer in total (this will be more than `chunk_len` if a
+    match has been dropped).
+    */
+    fn record_span(&mut self, chunk_len: usize, consumed: usize) {
+        let start = self.buff_offset;
+        self.last_span = (start, start + chunk_len as u64);
+        self.buff_offset += consumed as u64;
+    }
+
+    /*
+    Capture groups of the delimiter associated with the most recently
+    returned chunk. Only tracked if `track_captures` is set.
+    */
+    pub(crate) fn take_captures(&mut self) -> Vec<Option<Vec<u8>>> {
+        std::mem::take(&mut self.last_captures)
+    }
+
+    /* Start or stop copying out the capture groups that
+    `take_captures()` returns. */
+    pub(crate) fn track_captures(&mut self, yes: bool) {
+        self.track_captures = yes;
+        if !yes {
+            self.last_captures.clear();
+            self.front_captures.clear();
+        }
+    }
+
+    /*
+    Text of the delimiter that followed the most recently returned chunk.
+    Only tracked if `track_delims` is set.
+    */
+    pub(crate) fn last_delim(&self) -> Option<&[u8]> {
+        self.last_delim.as_deref()
+    }
+
+    /* Start or stop keeping the delimiters that `last_delim()` returns. */
+    pub(crate) fn track_delimiters(&mut self, yes: bool) {
+        self.track_delims = yes;
+        if !yes {
+            self.last_delim = None;
+        }
+    }
+
+    pub(crate) fn tracks_delimiters(&self) -> bool {
+        self.track_delims
+    }
+
+    /* Whether the chunker has yielded as many chunks as it's allowed. */
+    fn at_chunk_limit(&self) -> bool {
+        self.chunk_limit
+            .is_some_and(|limit| self.chunks_emitted >= limit)
+    }
+
+    /* Count a chunk of `len` bytes as having been yielded. */
+    fn record_emitted(&mut self, len: usize) {
+        self.chunks_emitted += 1;
+        self.bytes_emitted += len as u64;
+    }
+
+    /*
+    Index of the pattern whose match is associated with the most
+    recently returned chunk.
+    */
+    pub(crate) fn take_kind(&mut self) -> Option<usize> {
+        self.last_kind.take()
+    }
+
+    /*
+    Start and end positions in the source of the most recently
+    returned chunk.
+    */
+    pub(crate) fn last_span(&self) -> (u64, u64) {
+        self.last_span
+    }
+
+    /*
+    Respond to there being more than `max` bytes in the search buffer
+    without a delimiter. Returns `None` if the chunker should just
+    keep going.
+    */
+    fn handle_oversize(&mut self, max: usize) -> Option<Result<Taken, RcErr>> {
+        if let OversizePolicy::Yield = self.oversize_policy {
+            return Some(Ok(self.take_prefix(max)));
+        }
+
+        let e = RcErr::ChunkTooLarge {
+            size: self.search_buff.len(),
+        };
+        match self.error_status {
+            ErrorStatus::Ok | ErrorStatus::Errored => {
+                self.error_status = ErrorStatus::Errored;
+                Some(Err(e))
+            }
+            ErrorStatus::Continue => {
+                self.take_prefix(max);
+                self.search_buff.drain(..max);
+                Some(Err(e))
+            }
+            ErrorStatus::Ignore => {
+                self.take_prefix(max);
+                self.search_buff.drain(..max);
+                None
+            }
+        }
+    }
+
+    /*
+    Add `chunk` to the chunks being accumulated to meet the minimum chunk
+    size; if the accumulated chunk is now big enough, return it.
+    */
+    fn merge(&mut self, chunk: Vec<u8>, min: usize) -> Option<Vec<u8>> {
+        let merged = match self.merged.take() {
+            None => {
+                self.merged_start = self.last_span.0;
+                chunk
+            }
+            Some(mut merged) => {
+                // Dropped matches between accumulated chunks get put back.
+                if let MatchDisposition::Drop = self.match_dispo {
+                    if let Some(delim) = self.merged_delim.take() {
+                        merged.extend_from_slice(&delim);
+                    }
+                }
+                merged.extend_from_slice(&chunk);
+                merged
+            }
+        };
+        self.merged_delim = self.last_delim.take();
+
+        if merged.len() < min {
+            self.merged = Some(merged);
+            None
+        } else {
+            self.finish_merged();
+            Some(merged)
+        }
+    }
+
+    /*
+    Return whatever has been accumulated toward the minimum chunk size,
+    if anything, because the source is done.
+    */
+    fn flush_merged(&mut self) -> Option<Vec<u8>> {
+        if self.error_status == ErrorStatus::Errored {
+            self.merged = None;
+        }
+        let merged = self.merged.take()?;
+        self.finish_merged();
+        Some(merged)
+    }
+
+    /*
+    Make the recorded information about the most recent chunk reflect
+    the accumulated chunk about to be returned.
+    */
+    fn finish_merged(&mut self) {
+        self.last_span.0 = self.merged_start;
+        let delim = self.merged_delim.take();
+        if self.track_delims {
+            self.last_delim = delim;
+        }
+    }
+
+    /*
+    If `chunk` is larger than `max`, hold on to all but the first `max`
+    bytes to be returned in subsequent pieces.
+    */
+    fn split(&mut self, mut chunk: Vec<u8>, max: usize) -> Vec<u8> {
+        if chunk.len() <= max {
+            return chunk;
+        }
+
+        let rest = chunk.split_off(max);
+        let start = self.last_span.0;
+        self.last_span = (start, start + max as u64);
+        self.pieces_start = self.last_span.1;
+        self.pieces = Some(rest);
+        // The information about the chunk's delimiter belongs with its
+        // last piece.
+        self.pieces_delim = self.last_delim.take();
+        self.pieces_captures = std::mem::take(&mut self.last_captures);
+        self.pieces_kind = self.last_kind.take();
+        chunk
+    }
+
+    /*
+    Return the next piece of a chunk that was too large to return all at
+    once, if there is one.
+    */
+    fn next_piece(&mut self) -> Option<Vec<u8>> {
+        let mut piece = self.pieces.take()?;
+        let max = self.max_yield_size.unwrap_or(piece.len());
+        let start = self.pieces_start;
+
+        if piece.len() > max {
+            self.pieces = Some(piece.split_off(max));
+            self.pieces_start += max as u64;
+        } else {
+            self.last_delim = self.pieces_delim.take();
+            self.last_captures = std::mem::take(&mut self.pieces_captures);
+            self.last_kind = self.pieces_kind.take();
+        }
+        self.last_span = (start, start + piece.len() as u64);
+        Some(piece)
+    }
+
+    /*
+    Take the first `n` bytes of the search buffer as a chunk that wasn't
+    ended by a match.
+    */
+    fn take_prefix(&mut self, n: usize) -> Taken {
+        self.record_span(n, n);
+        self.scanned_to = self.scanned_to.saturating_sub(n);
+        self.last_captures = std::mem::take(&mut self.front_captures);
+        self.last_kind = self.front_kind.take();
+        self.last_delim = None;
+        // If we're in Prepend mode, the match we've been keeping at the
+        // front of the buffer may have been partly or entirely removed.
+        self.scan_start_offset = self.scan_start_offset.saturating_sub(n);
+        (n, n)
+    }
+
+    /*
+    Deal with whatever is left in the search buffer after the last match
+    when the source runs out.
+    */
+    fn take_tail(&mut self) -> Option<Result<Taken, RcErr>> {
+        if let Some(tail) = self.held_tail.as_mut() {
+            tail.append(&mut self.search_buff);
+            return None;
+        }
+        let taken = self.take_prefix(self.search_buff.len());
+        if let TrailingPolicy::Emit = self.trailing {
+            return Some(Ok(taken));
+        }
+
+        let tail: Vec<u8> = self.search_buff.drain(..).collect();
+        match (self.trailing, self.error_status) {
+            (TrailingPolicy::Error, ErrorStatus::Ok | ErrorStatus::Errored) => {
+                self.error_status = ErrorStatus::Errored;
+                Some(Err(RcErr::UnterminatedTail(tail)))
+            }
+            (TrailingPolicy::Error, ErrorStatus::Continue) => {
+                Some(Err(RcErr::UnterminatedTail(tail)))
+            }
+            _ => None,
+        }
+    }
+
+    // Function for wrapping types that need this information.
+    #[allow(dead_code)]
+    #[inline(always)]
+    fn buff_size(&self) -> usize {
+        self.read_buff.len()
+    }
+}
+
+/*
+What a chunker delimits chunks with, and how to find it. This is kept
+apart from the rest of the chunker so a chunker built with
+`ByteChunker::from_buf_read()` can search its source's buffer.
+*/
+#[derive(Clone)]
+struct Matcher {
+    fence: Regex,
+    /* If the pattern passed to `new()` or `set_delimiter()` only matches
+    one fixed string, a searcher for that string, which is much faster
+    than the regex engine. */
+    literal: Option<Finder<'static>>,
+    /* If the chunker was built with `with_literals()`, the automaton
+    that finds them. `fence` is then an equivalent regex. */
+    #[cfg(feature = "aho-corasick")]
+    literals: Option<LiteralSet>,
+    /* If set by `with_match_filter()`, matches it rejects are passed
+    over as if they weren't there. */
+    match_filter: Option<MatchFilter>,
+    /* If the chunker was built with `with_patterns()`, the patterns it's
+    delimiting with. `fence` is then just an alternation of them. */
+    multi: Option<MultiFence>,
+}
+
+impl Matcher {
+    /*
+    Find the first match in `haystack` at or after `from` that the match
+    filter (if there is one) accepts. A match at the end of the haystack
+    isn't put to the filter until there's something after it to look at,
+    so it's returned as is, to be held by the caller.
+    */
+    fn find_accepted(
+        &self,
+        haystack: &[u8],
+        mut from: usize,
+        at_eof: bool,
+        track_captures: bool,
+    ) -> Option<(usize, usize, Groups, usize)> {
+        let filter = match &self.match_filter {
+            Some(filter) => filter,
+            None => return self.find(haystack, from, track_captures),
+        };
+        loop {
+            let found = self.find(haystack, from, track_captures)?;
+            let (start, end) = (found.0, found.1);
+            if !at_eof && end == haystack.len() {
+                return Some(found);
+            }
+            let mut predicate = filter.lock().unwrap_or_else(|e| e.into_inner());
+            if predicate(haystack, start..end) {
+                return Some(found);
+            }
+            from = end;
+        }
+    }
+
+    /*
+    Find the first nonempty match of the fence in `haystack` at or after
+    `from`, returning its span, (if `track_captures` is set) its capture
+    groups, and the index of the pattern that matched.
+    */
+    fn find(
+        &self,
+        haystack: &[u8],
+        mut from: usize,
+        track_captures: bool,
+    ) -> Option<(usize, usize, Groups, usize)> {
+        #[cfg(feature = "aho-corasick")]
+        if let Some(LiteralSet { automaton, .. }) = &self.literals {
+            while from <= haystack.len() {
+                let input = aho_corasick::Input::new(haystack).span(from..haystack.len());
+                let m = automaton.find(input)?;
+                if !m.is_empty() {
+                    return Some((m.start(), m.end(), Vec::new(), m.pattern().as_usize()));
+                }
+                from = m.start() + 1;
+            }
+            return None;
+        }
+
+        let (fence, kind) = match (&self.multi, &self.literal) {
+            (None, Some(finder)) => {
+                // A literal has no capture groups, so there's nothing to track.
+                let start = from + finder.find(haystack.get(from..)?)?;
+                return Some((start, start + finder.needle().len(), Vec::new(), 0));
+            }
+            (None, None) => (&self.fence, 0),
+            (Some(multi), _) => {
+                let n = multi.earliest(haystack, from)?;
+                (&multi.regexes[n], n)
+            }
+        };
+
+        if !track_captures {
+            return find_nonempty(fence, haystack, from)
+                .map(|m| (m.start(), m.end(), Vec::new(), kind));
+        }
+
+        while from <= haystack.len() {
+            let caps = fence.captures_at(haystack, from)?;
+            // Group 0 always participates in a match.
+            let m = caps.get(0).unwrap();
+            if m.is_empty() {
+                from = m.start() + 1;
+                continue;
+            }
+            let groups: Groups = caps
+                .iter()
+                .skip(1)
+                .map(|g| g.map(|g| g.as_bytes().to_vec()))
+                .collect();
+            return Some((m.start(), m.end(), groups, kind));
+        }
+        None
+    }
+}
+
+/*
+Several patterns, any of which delimits chunks.
+*/
+#[derive(Clone)]
+struct MultiFence {
+    set: RegexSet,
+    regexes: Vec<Regex>,
+}
+
+impl MultiFence {
+    /*
+    Return the index of the pattern with the first nonempty match in
+    `haystack` at or after `from`; ties go to the lower index. The
+    `RegexSet` only tells us which patterns match, so we use it to rule
+    out the rest, then look for where each of those matches.
+    */
+    fn earliest(&self, haystack: &[u8], from: usize) -> Option<usize> {
+        self.set
+            .matches_at(haystack, from)
+            .into_iter()
+            .filter_map(|n| find_nonempty(&self.regexes[n], haystack, from).map(|m| (m.start(), n)))
+            .min()
+            .map(|(_, n)| n)
+    }
+}
+
+/*
+The literals a chunker built with `ByteChunker::with_literals()` is
+delimiting with, and the automaton that finds them.
+*/
+#[cfg(feature = "aho-corasick")]
+#[derive(Clone, Debug)]
+struct LiteralSet {
+    automaton: AhoCorasick,
+    literals: Vec<Vec<u8>>,
+}
+
+#[cfg(feature = "aho-corasick")]
+impl LiteralSet {
+    fn new(literals: Vec<Vec<u8>>, case_insensitive: bool) -> Result<Self, RcErr> {
+        let automaton = AhoCorasick::builder()
+            .match_kind(aho_corasick::MatchKind::LeftmostFirst)
+            .ascii_case_insensitive(case_insensitive)
+            .build(&literals)?;
+        Ok(Self {
+            automaton,
+            literals,
+        })
+    }
+}
+
+/*
+Find the first match of `re` in `haystack` at or after `from` that isn't
+empty. A delimiter has to consume at least one byte, or the chunker would
+never make any progress.
+*/
+pub(crate) fn find_nonempty<'h>(
+    re: &Regex,
+    haystack: &'h [u8],
+    mut from: usize,
+) -> Option<Match<'h>> {
+    while from <= haystack.len() {
+        let m = re.find_at(haystack, from)?;
+        if !m.is_empty() {
+            return Some(m);
+        }
+        from = m.start() + 1;
+    }
+    None
+}
+
+/* Regex flags set with `ByteChunker::case_insensitive()` and friends,
+and the compiled size limit set by `ByteChunker::with_size_limit()`. */
+#[derive(Clone, Copy, Debug, Default)]
+struct Flags {
+    case_insensitive: bool,
+    multi_line: bool,
+    dot_matches_newline: bool,
+    size_limit: Option<usize>,
+}
+
+impl Flags {
+    fn regex(&self, pattern: &str) -> Result<Regex, RcErr> {
+        let mut builder = RegexBuilder::new(pattern);
+        builder
+            .case_insensitive(self.case_insensitive)
+            .multi_line(self.multi_line)
+            .dot_matches_new_line(self.dot_matches_newline);
+        if let Some(limit) = self.size_limit {
+            builder.size_limit(limit);
+        }
+        Ok(builder.build()?)
+    }
+
+    fn regex_set(&self, patterns: &[String]) -> Result<RegexSet, RcErr> {
+        let mut builder = RegexSetBuilder::new(patterns);
+        builder
+            .case_insensitive(self.case_insensitive)
+            .multi_line(self.multi_line)
+            .dot_matches_new_line(self.dot_matches_newline);
+        if let Some(limit) = self.size_limit {
+            builder.size_limit(limit);
+        }
+        Ok(builder.build()?)
+    }
+
+    /*
+    Parse `pattern` with the parser configured the same way `regex()`
+    configures it.
+    */
+    fn parse(&self, pattern: &str) -> Option<Hir> {
+        regex_syntax::ParserBuilder::new()
+            .utf8(false)
+            .case_insensitive(self.case_insensitive)
+            .multi_line(self.multi_line)
+            .dot_matches_new_line(self.dot_matches_newline)
+            .build()
+            .parse(pattern)
+            .ok()
+    }
+
+    /*
+    The length in bytes of the longest string that can match `pattern`,
+    if that's limited.
+    */
+    fn max_match_len(&self, pattern: &str) -> Option<usize> {
+        self.parse(pattern)?.properties().maximum_len()
+    }
+
+    /*
+    Whether `pattern` has any zero-width assertions, like `\b` or `$`,
+    that look at the bytes around a match.
+    */
+    fn has_look_around(&self, pattern: &str) -> bool {
+        self.parse(pattern)
+            .is_some_and(|hir| !hir.properties().look_set().is_empty())
+    }
+
+    /*
+    A searcher for the string `pattern` matches, if it only matches one
+    (nonempty) string, like `\n` or `\r\n`. The leftmost occurrence of
+    that string is then exactly the regex's leftmost match.
+    */
+    fn literal_finder(&self, pattern: &str) -> Option<Finder<'static>> {
+        match self.parse(pattern)?.kind() {
+            HirKind::Literal(lit) if !lit.0.is_empty() => Some(Finder::new(&lit.0).into_owned()),
+            _ => None,
+        }
+    }
+}
+
+/*
+The length in bytes of the longest string that can match `pattern`
+(compiled without any flags), if that's limited.
+*/
+pub(crate) fn max_match_len(pattern: &str) -> Option<usize> {
+    Flags::default().max_match_len(pattern)
+}
+
+/*
+Whether `pattern` (compiled without any flags) has any zero-width
+assertions that look at the bytes around a match.
+*/
+pub(crate) fn has_look_around(pattern: &str) -> bool {
+    Flags::default().has_look_around(pattern)
+}
+
+impl<R> Debug for ByteChunker<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ByteChunker")
+            .field("source", &std::any::type_name::<R>())
+            .field("fence", &self.matcher.fence)
+            .field("literal", &self.matcher.literal.is_some())
+            .field("flags", &self.flags)
+            .field("match_filter", &self.matcher.match_filter.is_some())
+            .field("read_buff", &String::from_utf8_lossy(&self.read_buff))
+            .field("buffered_source", &self.fill.is_some())
+            .field("search_buff", &String::from_utf8_lossy(&self.search_buff))
+            .field("error_status", &self.error_status)
+            .field("match_dispo", &self.match_dispo)
+            .field("last_scan_matched", &self.last_scan_matched)
+            .field("scan_start_offset", &self.scan_start_offset)
+            .field("max_chunk_size", &self.max_chunk_size)
+            .field("oversize_policy", &self.oversize_policy)
+            .field("block_policy", &self.block_policy)
+            .field("follow", &self.follow)
+            .field("follow_stopped", &self.follow_handle.is_stopped())
+            .field("read_timeout", &self.read_timeout)
+            .field("trailing", &self.trailing)
+            .field("buff_offset", &self.buff_offset)
+            .field("last_span", &self.last_span)
+            .field("track_captures", &self.track_captures)
+            .field("track_delims", &self.track_delims)
+            .field("skip_empty", &self.skip_empty)
+            .field("shrink_threshold", &self.shrink_threshold)
+            .field("search_capacity", &self.search_capacity)
+            .field("growth", &self.growth)
+            .field("leading_empty", &self.leading_empty)
+            .field("started", &self.started)
+            .field("min_chunk_size", &self.min_chunk_size)
+            .field("max_yield_size", &self.max_yield_size)
+            .field("overlap", &self.overlap)
+            .field("scanned_to", &self.scanned_to)
+            .field("max_match_len", &self.max_match_len)
+            .field("look_around", &self.look_around)
+            .field("rescan_window", &self.rescan_window)
+            .field("eager", &self.eager)
+            .field("pending_drain", &self.pending_drain)
+            .field("source_skip", &self.source_skip)
+            .field(
+                "multi",
+                &self.matcher.multi.as_ref().map(|multi| &multi.set),
+            )
+            .field("peeked", &self.peeked)
+            .field("source_remaining", &self.source_remaining)
+            .field("bytes_read", &self.bytes_read)
+            .field("progress", &self.progress.as_ref().map(|(n, _)| n))
+            .field("chunks_emitted", &self.chunks_emitted)
+            .field("bytes_emitted", &self.bytes_emitted)
+            .field("chunk_limit", &self.chunk_limit)
+            .field("to_skip", &self.to_skip)
+            .field(
+                "merged",
+                &self.merged.as_deref().map(String::from_utf8_lossy),
+            )
+            .finish()
+    }
+}
+
+impl<R: BufRead> ByteChunker<R> {
+    /**
+    Return a new [`ByteChunker`] wrapping a source that does its own
+    buffering, like a [`BufReader`](std::io::BufReader), that will chunk
+    its output by delimiting it with the supplied regex pattern.
+
+    Rather than reading into an internal buffer of its own and searching
+    that, this chunker searches the source's buffer directly (with
+    [`BufRead::fill_buf`] and [`BufRead::consume`]), and copies each
+    chunk straight out of it. Only when a chunk (or its delimiter) runs
+    past the end of what the source has buffered are the bytes copied
+    into the chunker's own buffer to wait for the rest. The size of the
+    source's buffer determines how much is read at a time.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::{BufReader, Cursor};
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"one\ntwo\nthree\n";
+    let reader = BufReader::with_capacity(4, Cursor::new(text));
+
+    let chunks: Vec<Vec<u8>> = ByteChunker::from_buf_read(reader, "\n")?
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"one"[..], b"two", b"three"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn from_buf_read(source: R, delimiter: &str) -> Result<Self, RcErr> {
+        let mut chunker = Self::new(source, delimiter)?;
+        chunker.read_buff = Vec::new();
+        chunker.fill = Some(Self::fill_from_buf);
+        Ok(chunker)
+    }
+
+    /*
+    Take the next bytes from the source's buffer. If nothing is waiting
+    in the search buffer and they hold a whole chunk, that chunk is
+    copied out into `direct`, and only it (and its delimiter, if that's
+    dropped or appended) is consumed; otherwise they're all appended to
+    the search buffer to be scanned there.
+    */
+    fn fill_from_buf(&mut self) -> std::io::Result<usize> {
+        while self.source_skip > 0 {
+            let n = self.source.fill_buf()?.len().min(self.source_skip);
+            if n == 0 {
+                break;
+            }
+            self.source.consume(n);
+            self.source_skip -= n;
+        }
+
+        let from = self.scan_from();
+        let buff = self.source.fill_buf()?;
+        let n = buff.len();
+
+        if self.search_buff.is_empty() {
+            let found = self
+                .matcher
+                .find_accepted(buff, from, false, self.track_captures)
+                .map(|(start, end, groups, kind)| {
+                    (
+                        start,
+                        end,
+                        groups,
+                        kind,
+                        apply_disposition(self.match_dispo, start, end),
+                    )
+                })
+                // A match at the end of the buffer might be longer once
+                // the source reads more, and one after too many bytes is
+                // handled by `scan_buffer()`, as is a chunk that doesn't
+                // consume anything.
+                .filter(|(start, end, .., split)| {
+                    *end < n
+                        && split.consumed > 0
+                        && !matches!(self.max_chunk_size, Some(max) if *start > max)
+                });
+            if let Some((start, end, groups, kind, split)) = found {
+                if self.track_delims || self.min_chunk_size.is_some() {
+                    let delim = self.last_delim.get_or_insert_with(Vec::new);
+                    delim.clear();
+                    delim.extend_from_slice(&buff[start..end]);
+                }
+                self.direct = Some(buff[..split.len].to_vec());
+                self.source.consume(split.consumed);
+                self.last_scan_matched = false;
+                self.accept_fence(start, end, groups, kind);
+                return Ok(split.consumed);
+            }
+        }
+
+        self.growth.reserve(&mut self.search_buff, n);
+        self.search_buff.extend_from_slice(buff);
+        self.source.consume(n);
+        Ok(n)
+    }
+}
+
+/**
+A [`ByteChunker`] over a reader whose type is only known at runtime, like
+standard input or a file, depending on the command line. Build one with
+[`ByteChunker::boxed`].
+*/
+pub type BoxedByteChunker = ByteChunker<Box<dyn Read>>;
+
+impl ByteChunker<Box<dyn Read>> {
+    /**
+    Return a new [`BoxedByteChunker`] wrapping the given trait-object
+    reader that will chunk its output by delimiting it with the supplied
+    regex pattern. This is [`ByteChunker::new`], but saves spelling out
+    the type of the boxed source.
+
+    ```
+    use regex_chunker::{BoxedByteChunker, ByteChunker};
+    use std::io::{stdin, Cursor, Read};
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let from_stdin = false;
+    let source: Box<dyn Read> = if from_stdin {
+        Box::new(stdin())
+    } else {
+        Box::new(Cursor::new("one two three"))
+    };
+
+    let chunker: BoxedByteChunker = ByteChunker::boxed(source, " ")?;
+    assert_eq!(chunker.count(), 3);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn boxed(source: Box<dyn Read>, delimiter: &str) -> Result<BoxedByteChunker, RcErr> {
+        Self::new(source, delimiter)
+    }
+}
+
+impl<R: Read> ByteChunker<SourceChain<R>> {
+    /**
+    Return a new [`ByteChunker`] that reads from each of `sources` in turn
+    and chunks them as one continuous stream, delimiting it with the
+    supplied regex pattern. This is for data that's been split across
+    several files, say, at arbitrary points.
+
+    The boundaries between the sources are invisible to the chunker: a
+    chunk that begins in one source and ends in the next is returned
+    whole, a delimiter split across two sources is still found, and
+    nothing is added between them. (If each source should end a chunk,
+    give each its own chunker, or [`reset`](ByteChunker::reset) one
+    chunker with each new source.) Only the last source running out
+    counts as the end of the data.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let parts = vec![
+        Cursor::new("first record\r"),
+        Cursor::new("\nsecond rec"),
+        Cursor::new("ord\r\n"),
+    ];
+
+    let chunks: Vec<Vec<u8>> = ByteChunker::from_sources(parts, "\r\n")?
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"first record"[..], b"second record"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn from_sources(sources: Vec<R>, delimiter: &str) -> Result<Self, RcErr> {
+        Self::new(SourceChain::new(sources), delimiter)
+    }
+}
+
+impl<R: Seek> ByteChunker<R> {
+    /**
+    Seek the source back to its beginning and start chunking over, for
+    making more than one pass through a file. This is
+    [`ByteChunker::seek_to`] with an `offset` of 0.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut chunker = ByteChunker::new(Cursor::new("a,b,c"), ",")?;
+    let count = chunker.by_ref().count();
+
+    chunker.rewind()?;
+    let chunks: Vec<Vec<u8>> = chunker.collect::<Result<_, _>>()?;
+    assert_eq!(chunks.len(), count);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn rewind(&mut self) -> std::io::Result<()> {
+        self.seek_to(0)
+    }
+
+    /**
+    Seek the source to `offset` bytes from its beginning and start
+    chunking from there. Like [`ByteChunker::reset`], this keeps the
+    chunker's settings but discards any data read and not yet returned
+    (along with any [size hint](ByteChunker::with_size_hint)), and
+    resets its counts; offsets it reports (see
+    [`ByteChunker::with_offsets`]) carry on being positions in the
+    source. The first chunk starts at `offset`, even if that's in the
+    middle of what would have been a chunk.
+
+    If seeking fails, the error is returned and the chunker is left
+    as it was.
+    */
+    pub fn seek_to(&mut self, offset: u64) -> std::io::Result<()> {
+        self.source.seek(SeekFrom::Start(offset))?;
+        self.clear_state(offset);
+        Ok(())
+    }
+}
+
+impl<R: Read> ByteChunker<R> {
+    /*
+    Append the next bytes from the source to the search buffer, returning
+    the number of bytes added.
+    */
+    fn fill_search_buff(&mut self) -> std::io::Result<usize> {
+        let n = match self.fill {
+            Some(fill) => fill(self)?,
+            None => {
+                let n = self.source.read(&mut self.read_buff)?;
+                self.growth.reserve(&mut self.search_buff, n);
+                self.search_buff.extend_from_slice(&self.read_buff[..n]);
+                n
+            }
+        };
+        if let Some(remaining) = self.source_remaining.as_mut() {
+            *remaining = remaining.saturating_sub(n as u64);
+        }
+        let before = self.bytes_read;
+        self.bytes_read += n as u64;
+        if let Some((interval, callback)) = &self.progress {
+            if self.bytes_read / interval > before / interval {
+                let mut callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+                callback(self.bytes_read);
+            }
+        }
+        Ok(n)
+    }
+
+    /* Whether the current wait for data has outlasted the read timeout. */
+    fn waited_too_long(&self) -> bool {
+        match (self.read_timeout, self.waiting_since) {
+            (Some(timeout), Some(since)) => since.elapsed() >= timeout,
+            _ => false,
+        }
+    }
+
+    /*
+    Pull bytes from the source until there's a chunk to return (or an
+    error or the end of the source).
+    */
+    fn read_chunk(&mut self) -> Option<Result<Taken, RcErr>> {
+        self.drain_pending();
+        if self.error_status == ErrorStatus::Errored {
+            return None;
+        }
+
+        loop {
+            if !self.last_scan_matched {
+                if self.read_timeout.is_some() && self.waiting_since.is_none() {
+                    self.waiting_since = Some(Instant::now());
+                }
+                let filled = self.fill_search_buff();
+                let waiting = matches!(
+                    &filled,
+                    Err(e) if matches!(
+                        e.kind(),
+                        ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted
+                    )
+                );
+                if !waiting {
+                    self.waiting_since = None;
+                }
+                match filled {
+                    Err(e)
+                        if self.read_timeout.is_some()
+                            && matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                    {
+                        if !self.waited_too_long() {
+                            self.block_policy.wait();
+                            continue;
+                        }
+                        self.waiting_since = None;
+                        match self.error_status {
+                            ErrorStatus::Ok | ErrorStatus::Errored => {
+                                self.error_status = ErrorStatus::Errored;
+                                return Some(Err(RcErr::Timeout));
+                            }
+                            ErrorStatus::Continue => return Some(Err(RcErr::Timeout)),
+                            ErrorStatus::Ignore => continue,
+                        }
+                    }
+                    Err(e) => match e.kind() {
+                        // A read interrupted by a signal can be tried again
+                        // right away; a source that isn't ready probably
+                        // won't be for a while.
+                        ErrorKind::Interrupted => continue,
+                        ErrorKind::WouldBlock => {
+                            self.block_policy.wait();
+                            continue;
+                        }
+                        _ => match self.error_status {
+                            ErrorStatus::Ok | ErrorStatus::Errored => {
+                                self.error_status = ErrorStatus::Errored;
+                                return Some(Err(e.into()));
                             }
                             ErrorStatus::Continue => {
                                 return Some(Err(e.into()));
@@ -288,34 +2983,399 @@ impl<R: Read> Iterator for ByteChunker<R> {
                         },
                     },
                     Ok(0) => {
+                        if let Some(interval) = self.follow_interval() {
+                            thread::sleep(interval);
+                            continue;
+                        }
+                        self.source_eof = true;
                         if self.search_buff.is_empty() {
                             return None;
-                        } else {
-                            let mut new_buff: Vec<u8> = Vec::new();
-                            std::mem::swap(&mut self.search_buff, &mut new_buff);
-                            return Some(Ok(new_buff));
                         }
+                        // There may be a match we put off trusting at the
+                        // end of the buffer.
+                        match self.scan_buffer(true) {
+                            Some(x) => return Some(x),
+                            None if self.last_scan_matched => continue,
+                            None => {}
+                        }
+                        return self.take_tail();
                     }
-                    Ok(n) => {
-                        self.search_buff.extend_from_slice(&self.read_buff[..n]);
-                        match self.scan_buffer() {
-                            Some(v) => return Some(Ok(v)),
-                            None => {
-                                spin_loop();
-                                continue;
-                            }
+                    Ok(_) => {
+                        self.source_eof = false;
+                        if let Some(chunk) = &self.direct {
+                            return Some(Ok((chunk.len(), 0)));
+                        }
+                        match self.scan_buffer(false) {
+                            Some(x) => return Some(x),
+                            None => continue,
                         }
                     }
                 }
             } else {
-                match self.scan_buffer() {
-                    Some(v) => return Some(Ok(v)),
-                    None => {
-                        spin_loop();
-                        continue;
+                match self.scan_buffer(false) {
+                    Some(x) => return Some(x),
+                    None => continue,
+                }
+            }
+        }
+    }
+
+    /*
+    Produce the next chunk, merging, splitting, and skipping empty chunks
+    as configured.
+    */
+    fn produce(&mut self) -> Option<Result<Vec<u8>, RcErr>> {
+        loop {
+            if let Some(v) = self.next_piece() {
+                return Some(Ok(v));
+            }
+
+            let chunk = match self.read_chunk() {
+                Some(Err(e)) => return Some(Err(e)),
+                Some(Ok(taken)) => {
+                    let chunk = self.take_front(taken);
+                    match self.min_chunk_size {
+                        Some(min) => match self.merge(chunk, min) {
+                            Some(v) => v,
+                            None => continue,
+                        },
+                        None => chunk,
                     }
                 }
+                None => self.flush_merged()?,
+            };
+
+            let chunk = match self.max_yield_size {
+                Some(max) => self.split(chunk, max),
+                None => chunk,
+            };
+
+            if self.discard(chunk.is_empty()) {
+                continue;
+            }
+            return Some(Ok(chunk));
+        }
+    }
+}
+
+impl<R: Read> ByteChunker<R> {
+    /**
+    Return a reference to the next chunk without consuming it; the
+    following call to `.next()` will return it. Unlike with
+    [`Iterator::peekable`], the `ByteChunker` itself stays accessible.
+
+    A peeked-at chunk counts as having been read: metadata wrappers like
+    the [`OffsetChunker`] report on the most recently read chunk, and
+    [`ByteChunker::into_innards`] won't include it in the unprocessed
+    data it returns.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let mut chunker = ByteChunker::new(Cursor::new("header;body;body"), ";")?;
+
+    if let Some(Ok(chunk)) = chunker.peek() {
+        assert_eq!(chunk, b"header");
+    }
+    assert_eq!(chunker.next().unwrap()?, b"header");
+    assert_eq!(chunker.next().unwrap()?, b"body");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn peek(&mut self) -> Option<&Result<Vec<u8>, RcErr>> {
+        if self.peeked.is_none() {
+            let next = self.next();
+            self.peeked = Some(next);
+        }
+        self.peeked.as_ref().and_then(|peeked| peeked.as_ref())
+    }
+
+    /**
+    Return the next chunk like [`Iterator::next`] does, but borrowed from
+    the chunker's internal buffer instead of copied into a new `Vec`
+    where possible.
+
+    The borrow is only valid until the chunker is used again (the borrow
+    checker will enforce this); the chunk's bytes are removed from the
+    buffer at the start of the next call to `.next()` or
+    `.next_borrowed()`. If chunks need to be combined, split, or
+    overlapped (with [`ByteChunker::with_min_chunk_size`],
+    [`ByteChunker::with_max_yield_size`], or
+    [`ByteChunker::with_overlap`]), the chunk has already been
+    [`peek`](ByteChunker::peek)ed at, or chunks still need to be
+    [skipped](ByteChunker::with_skip), it is returned owned. So is a
+    chunk that a chunker built with [`ByteChunker::from_buf_read`] found
+    whole in its source's buffer.
+
+    Mixing calls to this and to `.next()` is fine.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let text = b"one two  three";
+    let mut chunker = ByteChunker::new(Cursor::new(text), " +")?;
+
+    let mut total = 0;
+    while let Some(chunk) = chunker.next_borrowed() {
+        total += chunk?.len();
+    }
+    assert_eq!(total, 11);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn next_borrowed(&mut self) -> Option<Result<Cow<'_, [u8]>, RcErr>> {
+        if self.peeked.is_some()
+            || self.min_chunk_size.is_some()
+            || self.max_yield_size.is_some()
+            || self.overlap > 0
+            || self.to_skip > 0
+        {
+            return self.next().map(|res| res.map(Cow::Owned));
+        }
+        if self.at_chunk_limit() {
+            self.drain_pending();
+            return None;
+        }
+
+        loop {
+            let taken = match self.read_chunk()? {
+                Ok(taken) => taken,
+                Err(e) => return Some(Err(e)),
+            };
+            self.pending_drain = taken.1;
+            if self.discard(taken.0 == 0) {
+                self.direct = None;
+                continue;
+            }
+            self.record_emitted(taken.0);
+            if let Some(chunk) = self.direct.take() {
+                return Some(Ok(Cow::Owned(chunk)));
+            }
+            return Some(Ok(Cow::Borrowed(&self.search_buff[..taken.0])));
+        }
+    }
+
+    /**
+    Consume the chunker, writing every chunk to `sink`, with `sep` (if
+    supplied) between each pair of consecutive chunks, but not after the
+    last one. Returns the total number of bytes written, separators
+    included.
+
+    Reading stops at the first error, whether from the source or (as
+    [`RcErr::Write`]) from the sink; whatever was written before then
+    stays written.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let mut out: Vec<u8> = Vec::new();
+    let n = ByteChunker::new(Cursor::new("one  two three\n"), r"\s+")?
+        .write_all_to(&mut out, Some(b","))?;
+
+    assert_eq!(&out, b"one,two,three");
+    assert_eq!(n, out.len());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_all_to<W: Write>(
+        mut self,
+        sink: &mut W,
+        sep: Option<&[u8]>,
+    ) -> Result<usize, RcErr> {
+        let mut written = 0;
+        let mut first = true;
+        while let Some(chunk) = self.next_borrowed() {
+            let chunk = chunk?;
+            if let (Some(sep), false) = (sep, first) {
+                sink.write_all(sep).map_err(|e| RcErr::Write(e.into()))?;
+                written += sep.len();
+            }
+            sink.write_all(&chunk).map_err(|e| RcErr::Write(e.into()))?;
+            written += chunk.len();
+            first = false;
+        }
+        Ok(written)
+    }
+
+    /**
+    Consume the chunker, returning every complete chunk separately from
+    whatever bytes were left at the end of the source with no delimiter
+    after them (which will be empty if the source ended with a
+    delimiter). This is for framed data, where a final partial frame
+    means the data was truncated rather than being one more frame. The
+    chunker's [`TrailingPolicy`] doesn't apply.
+
+    In [`MatchDisposition::Prepend`] mode, a chunk is only complete once
+    the delimiter beginning the _next_ chunk has been found, so the last
+    chunk is always the leftover.
+
+    Reading stops at the first error, which is returned instead.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let (frames, partial) = ByteChunker::new(Cursor::new("one;two;thr"), ";")?.finish()?;
+
+    assert_eq!(&frames, &[b"one", b"two"]);
+    assert_eq!(&partial, b"thr");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn finish(mut self) -> Result<(Vec<Vec<u8>>, Vec<u8>), RcErr> {
+        self.held_tail = Some(Vec::new());
+        let chunks = self.by_ref().collect::<Result<_, _>>()?;
+        let tail = self.held_tail.take().unwrap_or_default();
+        Ok((chunks, tail))
+    }
+}
+
+impl<R: Read + Send + 'static> ByteChunker<R> {
+    /**
+    Consume the chunker, moving it to a new thread that sends everything
+    it yields down a channel, and return the receiving end, for splitting
+    chunking and processing between producer and consumer threads. The
+    channel is closed after the chunker's last item.
+
+    The channel is unbounded, so if the receiver falls behind, chunks
+    pile up in memory; to make the chunking thread wait for the receiver
+    instead, use [`ByteChunker::into_bounded_channel`]. Dropping the
+    `Receiver` stops the thread the next time it tries to send a chunk
+    (so not while it's waiting on a read).
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), regex_chunker::RcErr> {
+    let rx = ByteChunker::new(Cursor::new("one two three"), " ")?.into_channel();
+    let chunks: Vec<Vec<u8>> = rx.iter().collect::<Result<_, _>>()?;
+
+    assert_eq!(&chunks, &[&b"one"[..], b"two", b"three"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn into_channel(self) -> Receiver<Result<Vec<u8>, RcErr>> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.spawn_sender(move |res| tx.send(res).is_ok());
+        rx
+    }
+
+    /**
+    Like [`ByteChunker::into_channel`], but the channel holds at most
+    `bound` items; when it's full, the chunking thread blocks until the
+    receiver takes one, so at most `bound` chunks (plus the one being
+    sent) are held in memory. With a `bound` of 0, each chunk is handed
+    over directly, as in [`std::sync::mpsc::sync_channel`].
+    */
+    pub fn into_bounded_channel(self, bound: usize) -> Receiver<Result<Vec<u8>, RcErr>> {
+        let (tx, rx) = std::sync::mpsc::sync_channel(bound);
+        self.spawn_sender(move |res| tx.send(res).is_ok());
+        rx
+    }
+
+    /*
+    Run the chunker on a new thread, passing everything it yields to
+    `send`, until it runs out or `send` returns `false`.
+    */
+    fn spawn_sender<F>(self, mut send: F)
+    where
+        F: FnMut(Result<Vec<u8>, RcErr>) -> bool + Send + 'static,
+    {
+        thread::spawn(move || {
+            for res in self {
+                if !send(res) {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/**
+In general, it's impossible to tell how much data is left in a reader,
+so unless the number of bytes in the source has been supplied with
+[`ByteChunker::with_size_hint`], [`Iterator::size_hint`] gives no upper
+bound on the number of chunks remaining.
+*/
+impl<R: Read> Iterator for ByteChunker<R> {
+    type Item = Result<Vec<u8>, RcErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(peeked) = self.peeked.take() {
+            return peeked;
+        }
+        if self.at_chunk_limit() {
+            return None;
+        }
+
+        while self.to_skip > 0 {
+            match self.produce()? {
+                Ok(_) => self.to_skip -= 1,
+                Err(e) => return Some(Err(e)),
             }
         }
+
+        let chunk = self.produce()?.map(|v| self.overlap(v));
+        if let Ok(v) = &chunk {
+            self.record_emitted(v.len());
+        }
+        Some(chunk)
+    }
+
+    /* Counting doesn't need the chunks themselves, so it borrows them
+    from the search buffer rather than allocating each one. Errors are
+    counted, and whatever follows them is governed by the error
+    response, just as when iterating. */
+    fn count(mut self) -> usize {
+        let mut n = 0;
+        while self.next_borrowed().is_some() {
+            n += 1;
+        }
+        n
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let peeked = match &self.peeked {
+            Some(None) => return (0, Some(0)),
+            Some(Some(_)) => 1,
+            None => 0,
+        };
+
+        let upper = self.source_remaining.and_then(|remaining| {
+            // Every chunk but the last has at least one byte in it or
+            // after it.
+            let buffered = self.search_buff.len() - self.pending_drain
+                + self.pieces.as_ref().map_or(0, Vec::len)
+                + self.merged.as_ref().map_or(0, Vec::len);
+            usize::try_from(remaining)
+                .ok()?
+                .checked_add(buffered)?
+                .checked_add(1 + peeked)
+        });
+        let upper = upper
+            .map(|upper| upper.saturating_sub(usize::try_from(self.to_skip).unwrap_or(usize::MAX)));
+        let upper = match self.chunk_limit {
+            Some(limit) => {
+                let allowed = usize::try_from(limit.saturating_sub(self.chunks_emitted))
+                    .unwrap_or(usize::MAX)
+                    .saturating_add(peeked);
+                Some(upper.map_or(allowed, |upper| upper.min(allowed)))
+            }
+            None => upper,
+        };
+        (peeked, upper)
     }
-}
\ No newline at end of file
+}
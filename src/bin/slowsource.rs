@@ -73,7 +73,7 @@ fn getopts() -> Result<(Box<dyn Read>, RanDur), Box<dyn Error>> {
 fn main() -> Result<(), Box<dyn Error>> {
     let (src, durs) = getopts()?;
 
-    let chunker = ByteChunker::new(src, RE)?.with_match(MatchDisposition::Append);
+    let chunker = ByteChunker::boxed(src, RE)?.with_match(MatchDisposition::Append);
 
     for chunk in chunker {
         let chunk = chunk?;
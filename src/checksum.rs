@@ -0,0 +1,70 @@
+/*!
+An adapter for checksumming chunks.
+*/
+use crc32fast::Hasher;
+
+use crate::SimpleAdapter;
+
+/**
+A [`SimpleAdapter`] that pairs each chunk with its CRC-32 checksum, for
+checking the integrity of a stream of records.
+
+It also keeps a running checksum of all the chunks it has seen, as if
+they had been concatenated; after the chunker has been used for a while,
+[`ChecksumAdapter::checksum`] (through
+[`SimpleCustomChunker::get_adapter`](crate::SimpleCustomChunker::get_adapter))
+returns it. Note that this only covers the bytes in the chunks, so
+unless the chunker's [`MatchDisposition`](crate::MatchDisposition)
+keeps the delimiters, it won't be the checksum of the whole source.
+
+This requires the `crc32fast` feature.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{ByteChunker, ChecksumAdapter};
+use std::io::Cursor;
+
+let text = b"hello\nworld\n";
+
+let mut chunker = ByteChunker::new(Cursor::new(text), r"\n")?
+    .with_simple_adapter(ChecksumAdapter::new());
+let sums: Vec<(u32, Vec<u8>)> = chunker.by_ref().collect::<Result<_, _>>()?;
+
+assert_eq!(sums[0], (crc32fast::hash(b"hello"), b"hello".to_vec()));
+assert_eq!(sums[1], (crc32fast::hash(b"world"), b"world".to_vec()));
+assert_eq!(chunker.get_adapter().checksum(), crc32fast::hash(b"helloworld"));
+# Ok::<(), RcErr>(())
+```
+*/
+#[cfg_attr(docsrs, doc(cfg(feature = "crc32fast")))]
+#[derive(Clone, Debug, Default)]
+pub struct ChecksumAdapter {
+    total: Hasher,
+    chunks: u64,
+}
+
+impl ChecksumAdapter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The CRC-32 checksum of all the chunks seen so far.
+    pub fn checksum(&self) -> u32 {
+        self.total.clone().finalize()
+    }
+
+    /// The number of chunks seen so far.
+    pub fn chunks(&self) -> u64 {
+        self.chunks
+    }
+}
+
+impl SimpleAdapter for ChecksumAdapter {
+    type Item = (u32, Vec<u8>);
+
+    fn adapt(&mut self, v: Vec<u8>) -> Self::Item {
+        self.total.update(&v);
+        self.chunks += 1;
+        (crc32fast::hash(&v), v)
+    }
+}
@@ -0,0 +1,142 @@
+/*!
+A chunker for splitting CSV data into records.
+*/
+use std::{io::Read, ops::Range};
+
+use crate::{ByteChunker, RcErr};
+
+/**
+A [`ByteChunker`] preconfigured to yield the records of
+[RFC 4180](https://www.rfc-editor.org/rfc/rfc4180) CSV data, split on
+`\n` or `\r\n`, without their line endings. Unlike a plain line chunker,
+it doesn't split records on the line endings inside quoted fields (which
+may also contain `""`, an escaped quote). Each record is returned raw;
+splitting it into fields and unquoting them is left to the caller.
+
+A line ending only ends a record if it comes after an even number of
+quotes in that record. This holds across reads from the source: the
+chunker keeps count of the quotes it has passed in the current record,
+so each byte is only looked at once, however many line endings a quoted
+field contains. It also means that a stray quote in an unquoted field
+(which RFC 4180 doesn't allow) will run that record together with the
+ones after it, up to the next stray quote.
+
+As with a [`LineChunker`](crate::LineChunker), a final record with no
+line ending is returned like any other, and a source that ends with a
+line ending doesn't produce an empty final record. Blank lines produce
+empty records.
+
+```
+use regex_chunker::CsvRecordChunker;
+use std::io::Cursor;
+
+# fn main() -> Result<(), regex_chunker::RcErr> {
+let text = b"name,quote\r\nAda,\"Said \"\"hi\"\"\r\nthen left\"\r\nBob,none\r\n";
+
+let records: Vec<Vec<u8>> = CsvRecordChunker::new(Cursor::new(&text[..]))
+    .collect::<Result<_, _>>()?;
+assert_eq!(
+    &records,
+    &[
+        &b"name,quote"[..],
+        b"Ada,\"Said \"\"hi\"\"\r\nthen left\"",
+        b"Bob,none",
+    ]
+);
+# Ok(())
+# }
+```
+*/
+#[derive(Debug)]
+pub struct CsvRecordChunker<R> {
+    chunker: ByteChunker<R>,
+}
+
+impl<R> CsvRecordChunker<R> {
+    /// Return a new [`CsvRecordChunker`] that yields the records of
+    /// `source`.
+    pub fn new(source: R) -> Self {
+        // This pattern is known to be valid.
+        let mut parity = QuoteParity::default();
+        let chunker = ByteChunker::new(source, r"\r?\n")
+            .unwrap()
+            .with_match_filter(move |buff: &[u8], m: Range<usize>| parity.outside_quotes(buff, m));
+        Self { chunker }
+    }
+
+    /// Builder-pattern method for setting the read buffer size, as with
+    /// [`ByteChunker::with_buffer_size`].
+    pub fn with_buffer_size(mut self, size: usize) -> Self {
+        self.chunker = self.chunker.with_buffer_size(size);
+        self
+    }
+
+    /// Consume the `CsvRecordChunker` and return the underlying
+    /// [`ByteChunker`].
+    ///
+    /// The chunker returned vets each line ending by counting all the
+    /// quotes in front of it, rather than keeping a running count: that
+    /// depends on the chunker taking each record out of its buffer whole,
+    /// which one that's been reconfigured (or cloned) might not. This
+    /// takes longer for records with many line endings in quoted fields.
+    pub fn into_inner(self) -> ByteChunker<R> {
+        self.chunker
+            .with_match_filter(|buff: &[u8], m: Range<usize>| !odd_quotes(&buff[..m.start]))
+    }
+}
+
+/* Whether `bytes` has an odd number of quotes in it. */
+fn odd_quotes(bytes: &[u8]) -> bool {
+    memchr::memchr_iter(b'"', bytes).count() % 2 == 1
+}
+
+/*
+Whether the quotes counted so far in the current record leave it inside
+a quoted field, and how far into the chunker's buffer they've been
+counted.
+*/
+#[derive(Clone, Copy, Debug, Default)]
+struct QuoteParity {
+    counted_to: usize,
+    odd: bool,
+}
+
+impl QuoteParity {
+    /*
+    Whether the line ending at `m` is outside any quoted field. The
+    chunker's buffer begins at the start of the current record, so that's
+    the case if there are an even number of quotes before it. (An escaped
+    quote is two quotes, so it doesn't change anything.) Only the quotes
+    since the last line ending looked at are counted.
+
+    Once a line ending is accepted, the chunker takes the record in front
+    of it out of its buffer, so counting starts again from the front.
+    Counting also starts again if the chunker asks about a line ending
+    before the ones it's already asked about.
+    */
+    fn outside_quotes(&mut self, buff: &[u8], m: Range<usize>) -> bool {
+        if m.start < self.counted_to {
+            *self = Self::default();
+        }
+        self.odd ^= odd_quotes(&buff[self.counted_to..m.start]);
+        self.counted_to = m.start;
+
+        let outside = !self.odd;
+        if outside {
+            *self = Self::default();
+        }
+        outside
+    }
+}
+
+impl<R: Read> Iterator for CsvRecordChunker<R> {
+    type Item = Result<Vec<u8>, RcErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunker.next()
+    }
+
+    fn count(self) -> usize {
+        self.chunker.count()
+    }
+}
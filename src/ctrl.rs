@@ -1,6 +1,14 @@
 /*!
 A bunch of enums that control the behavior of chunkers.
 */
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub(crate) enum ErrorStatus {
     Ok,
@@ -38,6 +46,45 @@ pub enum MatchDisposition {
     Prepend,
 }
 
+/*
+How a chunk is split off the front of a buffer at a match, according to
+a `MatchDisposition`: the chunk is the first `len` bytes of the buffer,
+`consumed` bytes are removed from the buffer along with it (more than
+`len` if the match is dropped), and the search for the next match starts
+`scan_offset` bytes into what's left (past the match, if it was left at
+the front to begin the next chunk).
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Split {
+    pub(crate) len: usize,
+    pub(crate) consumed: usize,
+    pub(crate) scan_offset: usize,
+}
+
+/*
+Decide how to split a buffer at the match `start..end`. This is shared
+by the sync and async chunkers so they can't disagree about it.
+*/
+pub(crate) fn apply_disposition(dispo: MatchDisposition, start: usize, end: usize) -> Split {
+    match dispo {
+        MatchDisposition::Drop => Split {
+            len: start,
+            consumed: end,
+            scan_offset: 0,
+        },
+        MatchDisposition::Append => Split {
+            len: end,
+            consumed: end,
+            scan_offset: 0,
+        },
+        MatchDisposition::Prepend => Split {
+            len: start,
+            consumed: start,
+            scan_offset: end - start,
+        },
+    }
+}
+
 /// Type for specifying a [`StringAdapter`](crate::StringAdapter)'s
 /// behavior upon encountering non-UTF-8 data.
 #[derive(Clone, Copy, Debug, Default)]
@@ -53,4 +100,126 @@ pub enum Utf8FailureMode {
     /// `Some(Err(RcErr))` until the it starts reading UTF-8 from the
     /// `source` again.
     Continue,
-}
\ No newline at end of file
+}
+
+/// Type for specifying what a chunker should do when it has buffered more
+/// than its maximum chunk size without finding a delimiter. (See
+/// [`ByteChunker::with_max_chunk_size`](crate::ByteChunker::with_max_chunk_size).)
+#[derive(Clone, Copy, Debug, Default)]
+pub enum OversizePolicy {
+    /// Yield the first `max_chunk_size` bytes as a chunk and keep going.
+    Yield,
+    /// Report an [`RcErr::ChunkTooLarge`](crate::RcErr::ChunkTooLarge).
+    /// What happens after that is governed by the chunker's
+    /// [`ErrorResponse`]; if the chunker continues, the oversized bytes
+    /// are discarded. This is the default behavior.
+    #[default]
+    Error,
+}
+
+/// Type for specifying what a chunker does with the data left over at
+/// the end of its source after the last delimiter. (See
+/// [`ByteChunker::with_trailing`](crate::ByteChunker::with_trailing).)
+#[derive(Clone, Copy, Debug, Default)]
+pub enum TrailingPolicy {
+    /// Yield it as the final chunk. This is the default behavior.
+    #[default]
+    Emit,
+    /// Throw it away.
+    Discard,
+    /// Report an
+    /// [`RcErr::UnterminatedTail`](crate::RcErr::UnterminatedTail)
+    /// holding it.
+    Error,
+}
+
+/// Type for specifying what a chunker does while it waits on a source
+/// that isn't ready yet (a non-blocking reader returning
+/// [`ErrorKind::WouldBlock`](std::io::ErrorKind::WouldBlock)) before
+/// trying it again. (See
+/// [`ByteChunker::with_block_policy`](crate::ByteChunker::with_block_policy).)
+#[derive(Clone, Copy, Debug, Default)]
+pub enum BlockPolicy {
+    /// Retry immediately. This has the lowest latency, but keeps a CPU
+    /// core busy for as long as the source isn't ready.
+    Spin,
+    /// Give up the rest of the thread's time slice (with
+    /// [`std::thread::yield_now`]) before retrying. This is the default
+    /// behavior.
+    #[default]
+    Yield,
+    /// Sleep for the given amount of time before retrying.
+    Sleep(Duration),
+}
+
+impl BlockPolicy {
+    /* Wait, according to the policy, before retrying the source. */
+    pub(crate) fn wait(&self) {
+        match self {
+            BlockPolicy::Spin => std::hint::spin_loop(),
+            BlockPolicy::Yield => std::thread::yield_now(),
+            BlockPolicy::Sleep(d) => std::thread::sleep(*d),
+        }
+    }
+}
+
+/// A handle for telling a following chunker (see
+/// [`ByteChunker::with_follow`](crate::ByteChunker::with_follow)) to stop
+/// waiting for more data. It can be sent to another thread, which is
+/// handy because the chunker itself is usually busy waiting. Once it's
+/// been told to stop, the chunker finishes the next time it catches up
+/// with the end of its source, flushing anything it has buffered.
+#[derive(Clone, Debug, Default)]
+pub struct FollowHandle(Arc<AtomicBool>);
+
+impl FollowHandle {
+    /// Tell the chunker to stop following its source.
+    pub fn stop_following(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Whether [`FollowHandle::stop_following`] has been called.
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// Type for specifying how a chunker's search buffer grows when more
+/// data comes in than it has room for. (See
+/// [`ByteChunker::with_growth_policy`](crate::ByteChunker::with_growth_policy).)
+#[derive(Clone, Copy, Debug, Default)]
+pub enum GrowthPolicy {
+    /// Let the buffer grow the way a `Vec` does, roughly doubling its
+    /// capacity each time. This is the default behavior.
+    #[default]
+    Double,
+    /// Grow the buffer by the smallest multiple of this many bytes that
+    /// makes room. This reallocates more often than doubling, but never
+    /// reserves much more memory than is needed.
+    Fixed(usize),
+}
+
+impl GrowthPolicy {
+    /* Make sure `buff` has room for `additional` more bytes. */
+    pub(crate) fn reserve(&self, buff: &mut Vec<u8>, additional: usize) {
+        if let GrowthPolicy::Fixed(step) = *self {
+            let spare = buff.capacity() - buff.len();
+            if additional > spare {
+                let step = step.max(1);
+                buff.reserve_exact(spare + (additional - spare).div_ceil(step) * step);
+            }
+        }
+    }
+}
+
+/// Type for specifying how an adapter that decodes chunks (like the
+/// `JsonAdapter`) behaves upon encountering a chunk it can't decode.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum DecodeFailureMode {
+    /// Report an error and stop (return `Some(Err(RcErr))` once and then
+    /// `None` thereafter). This is the default behavior.
+    #[default]
+    Fatal,
+    /// Report an error but keep going, decoding the chunks that follow.
+    Continue,
+}
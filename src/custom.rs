@@ -3,7 +3,7 @@ The custom chunker type.
 */
 use std::io::Read;
 
-use crate::{Adapter, ByteChunker, RcErr, SimpleAdapter};
+use crate::{Adapter, AdapterAction, ByteChunker, RcErr, SimpleAdapter};
 
 /**
 A chunker that has additionally been supplied with an [`Adapter`], so it
@@ -38,7 +38,6 @@ to yield [`String`]s instead of byte vectors.
 #   Ok(()) }
 ```
 */
-
 pub struct CustomChunker<R, A> {
     chunker: ByteChunker<R>,
     adapter: A,
@@ -47,7 +46,8 @@ pub struct CustomChunker<R, A> {
 impl<R, A> CustomChunker<R, A> {
     /// Consume this `CustomChunker` and return the underlying
     /// [`ByteChunker`] and [`Adapter`].
-    pub fn into_innards(self) -> (ByteChunker<R>, A) {
+    pub fn into_innards(mut self) -> (ByteChunker<R>, A) {
+        self.chunker.track_delimiters(false);
         (self.chunker, self.adapter)
     }
 
@@ -57,6 +57,10 @@ impl<R, A> CustomChunker<R, A> {
     /// Get a mutable reference to the underlying [`Adapter`].
     pub fn get_adapter_mut(&mut self) -> &mut A { &mut self.adapter }
 
+    #[cfg(test)]
+    pub(crate) fn byte_chunker(&self) -> &ByteChunker<R> {
+        &self.chunker
+    }
 }
 
 impl<R, A> From<(ByteChunker<R>, A)> for CustomChunker<R, A> {
@@ -73,8 +77,23 @@ where
     type Item = A::Item;
 
     fn next(&mut self) -> Option<A::Item> {
-        let opt = self.chunker.next();
-        self.adapter.adapt(opt)
+        // Copying out each delimiter costs something, so it's only done
+        // for adapters that will look at them.
+        if self.adapter.wants_delimiters() && !self.chunker.tracks_delimiters() {
+            self.chunker.track_delimiters(true);
+        }
+        loop {
+            let opt = self.chunker.next();
+            let delim = match opt {
+                Some(Ok(_)) => self.chunker.last_delim(),
+                _ => None,
+            };
+            match self.adapter.adapt_with_delim(opt, delim) {
+                AdapterAction::Emit(item) => return Some(item),
+                AdapterAction::Skip => continue,
+                AdapterAction::Done => return None,
+            }
+        }
     }
 }
 
@@ -0,0 +1,108 @@
+/*!
+An adapter for decoding chunks of text in encodings other than UTF-8.
+*/
+use encoding_rs::{CoderResult, Decoder, Encoding};
+
+use crate::{Adapter, RcErr};
+
+/**
+An [`Adapter`] that decodes each chunk from a caller-supplied
+[`Encoding`](encoding_rs::Encoding) (like `SHIFT_JIS` or `WINDOWS_1252`)
+into a `String`. Errors from the underlying chunker are passed through.
+
+Malformed sequences are replaced with U+FFFD REPLACEMENT CHARACTER, as is
+the `encoding_rs` way. The decoder is stateful: if a chunk ends partway
+through a multi-byte sequence (which can happen when a chunker has a
+maximum yield size, for instance), the incomplete sequence is held back
+and completed by the start of the next chunk. If the source ends with an
+incomplete sequence still held back, one final `String` (containing the
+replacement character) is produced.
+
+No special treatment is given to byte order marks.
+
+This requires the `encoding_rs` feature.
+
+```rust
+# use std::error::Error;
+# fn main() -> Result<(), Box<dyn Error>> {
+    use regex_chunker::{ByteChunker, EncodingAdapter};
+    use std::io::Cursor;
+
+    // "café\nnaïve\n" in Windows-1252
+    let text = b"caf\xe9\nna\xefve\n";
+    let words: Vec<String> = ByteChunker::new(Cursor::new(text), r"\n")?
+        .with_adapter(EncodingAdapter::new(encoding_rs::WINDOWS_1252))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(&words, &["café", "naïve"]);
+#   Ok(()) }
+```
+*/
+#[cfg_attr(docsrs, doc(cfg(feature = "encoding_rs")))]
+pub struct EncodingAdapter {
+    decoder: Decoder,
+    finished: bool,
+}
+
+impl EncodingAdapter {
+    pub fn new(encoding: &'static Encoding) -> Self {
+        Self {
+            decoder: encoding.new_decoder_without_bom_handling(),
+            finished: false,
+        }
+    }
+
+    /// Return the encoding being decoded from.
+    pub fn encoding(&self) -> &'static Encoding {
+        self.decoder.encoding()
+    }
+
+    fn decode(&mut self, mut src: &[u8], last: bool) -> String {
+        let mut s = String::new();
+        loop {
+            /* max_utf8_buffer_length() only fails on overflow, in which
+            case just reserve something reasonable and go around again. */
+            let needed = self
+                .decoder
+                .max_utf8_buffer_length(src.len())
+                .unwrap_or(src.len());
+            s.reserve(needed);
+            let (result, read, _) = self.decoder.decode_to_string(src, &mut s, last);
+            src = &src[read..];
+            if let CoderResult::InputEmpty = result {
+                return s;
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for EncodingAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncodingAdapter")
+            .field("encoding", &self.encoding())
+            .field("finished", &self.finished)
+            .finish()
+    }
+}
+
+impl Adapter for EncodingAdapter {
+    type Item = Result<String, RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        match v {
+            Some(Ok(v)) => Some(Ok(self.decode(&v, false))),
+            Some(Err(e)) => Some(Err(e)),
+            None => {
+                if self.finished {
+                    return None;
+                }
+                self.finished = true;
+                let tail = self.decode(&[], true);
+                if tail.is_empty() {
+                    None
+                } else {
+                    Some(Ok(tail))
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 /*!
 Error types returned by the various chunkers.
 */
-use std::{error::Error, fmt::Display, string::FromUtf8Error};
+use std::{error::Error, fmt::Display, string::FromUtf8Error, sync::Arc};
 
 /**
 Wraps various types of errors that can happen in the internals of a
@@ -9,17 +9,146 @@ Chunker. The way Chunkers respond to and report these errors can be
 controlled through builder-pattern methods that take the
 [`ErrorResponse`](crate::ErrorResponse) and
 [`Utf8FailureMode`](crate::Utf8FailureMode) types.
+
+So that `RcErr`s can be cloned and compared, errors from other crates
+that can't be are wrapped in an [`Arc`]. Two such errors are equal if
+they're the same kind of error with the same message. To just find out
+what sort of error an `RcErr` is, use [`RcErr::kind`].
+
+Which variants exist depends on which of the crate's features are
+enabled, and more may be added in the future, so matches on an `RcErr`
+need a wildcard arm.
 */
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum RcErr {
     /// Error returned during creation of a regex.
     Regex(regex::Error),
     /// Error returned during reading from a `*Chunker`'s source.
-    Read(std::io::Error),
+    Read(Arc<std::io::Error>),
+    /// Error returned while writing chunks to a sink with
+    /// [`ByteChunker::write_all_to`](crate::ByteChunker::write_all_to).
+    Write(Arc<std::io::Error>),
     /// Error returned by a
     // [`CustomChunker<StringAdapter>`](crate::StringChunker)
     /// upon encountering non-UTF-8 data.
     Utf8(FromUtf8Error),
+    /// Returned when a chunker has buffered more than its maximum chunk
+    /// size without finding a delimiter. `size` is the number of bytes
+    /// buffered at the time.
+    ChunkTooLarge { size: usize },
+    /// Returned when a chunker's source ends partway through a chunk
+    /// (with data after the last delimiter), and it's been told (with
+    /// [`TrailingPolicy::Error`](crate::TrailingPolicy::Error)) that
+    /// this is an error. It holds the leftover bytes.
+    UnterminatedTail(Vec<u8>),
+    /// Returned when a chunker with a read timeout (see
+    /// [`ByteChunker::with_read_timeout`](crate::ByteChunker::with_read_timeout))
+    /// has waited longer than that for its source to produce any data.
+    Timeout,
+    /// Error returned by a
+    /// [`CustomChunker<JsonAdapter>`](crate::JsonAdapter) upon
+    /// encountering a chunk that can't be deserialized.
+    #[cfg(feature = "serde")]
+    Deserialize(Arc<serde_json::Error>),
+    /// Error returned by a
+    /// [`CustomChunker<Base64Adapter>`](crate::Base64Adapter) upon
+    /// encountering a chunk that isn't valid base64.
+    #[cfg(feature = "base64")]
+    Base64(base64::DecodeError),
+    /// Error returned by a
+    /// [`CustomChunker<HexAdapter>`](crate::HexAdapter) upon
+    /// encountering a chunk that isn't valid hexadecimal (including one
+    /// with an odd number of digits).
+    #[cfg(feature = "hex")]
+    Hex(hex::FromHexError),
+    /// Error returned when the automaton for
+    /// [`ByteChunker::with_literals`](crate::ByteChunker::with_literals)
+    /// can't be built.
+    #[cfg(feature = "aho-corasick")]
+    Literals(aho_corasick::BuildError),
+}
+
+/**
+The variety of an [`RcErr`], without any of the details; returned by
+[`RcErr::kind`].
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RcErrKind {
+    Regex,
+    Read,
+    Write,
+    Utf8,
+    ChunkTooLarge,
+    UnterminatedTail,
+    Timeout,
+    #[cfg(feature = "serde")]
+    Deserialize,
+    #[cfg(feature = "base64")]
+    Base64,
+    #[cfg(feature = "hex")]
+    Hex,
+    #[cfg(feature = "aho-corasick")]
+    Literals,
+}
+
+impl RcErr {
+    /**
+    Return what kind of error this is.
+
+    ```
+    use regex_chunker::{ByteChunker, RcErrKind};
+
+    let err = ByteChunker::new(std::io::empty(), "(").unwrap_err();
+    assert_eq!(err.kind(), RcErrKind::Regex);
+    ```
+    */
+    pub fn kind(&self) -> RcErrKind {
+        match self {
+            RcErr::Regex(_) => RcErrKind::Regex,
+            RcErr::Read(_) => RcErrKind::Read,
+            RcErr::Write(_) => RcErrKind::Write,
+            RcErr::Utf8(_) => RcErrKind::Utf8,
+            RcErr::ChunkTooLarge { .. } => RcErrKind::ChunkTooLarge,
+            RcErr::UnterminatedTail(_) => RcErrKind::UnterminatedTail,
+            RcErr::Timeout => RcErrKind::Timeout,
+            #[cfg(feature = "serde")]
+            RcErr::Deserialize(_) => RcErrKind::Deserialize,
+            #[cfg(feature = "base64")]
+            RcErr::Base64(_) => RcErrKind::Base64,
+            #[cfg(feature = "hex")]
+            RcErr::Hex(_) => RcErrKind::Hex,
+            #[cfg(feature = "aho-corasick")]
+            RcErr::Literals(_) => RcErrKind::Literals,
+        }
+    }
+}
+
+impl PartialEq for RcErr {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RcErr::Regex(a), RcErr::Regex(b)) => a == b,
+            (RcErr::Read(a), RcErr::Read(b)) | (RcErr::Write(a), RcErr::Write(b)) => {
+                a.kind() == b.kind() && a.to_string() == b.to_string()
+            }
+            (RcErr::Utf8(a), RcErr::Utf8(b)) => a == b,
+            (RcErr::ChunkTooLarge { size: a }, RcErr::ChunkTooLarge { size: b }) => a == b,
+            (RcErr::UnterminatedTail(a), RcErr::UnterminatedTail(b)) => a == b,
+            (RcErr::Timeout, RcErr::Timeout) => true,
+            #[cfg(feature = "serde")]
+            (RcErr::Deserialize(a), RcErr::Deserialize(b)) => {
+                a.classify() == b.classify() && a.to_string() == b.to_string()
+            }
+            #[cfg(feature = "base64")]
+            (RcErr::Base64(a), RcErr::Base64(b)) => a == b,
+            #[cfg(feature = "hex")]
+            (RcErr::Hex(a), RcErr::Hex(b)) => a == b,
+            #[cfg(feature = "aho-corasick")]
+            (RcErr::Literals(a), RcErr::Literals(b)) => a.to_string() == b.to_string(),
+            _ => false,
+        }
+    }
 }
 
 impl Display for RcErr {
@@ -27,7 +156,27 @@ impl Display for RcErr {
         match self {
             RcErr::Regex(e) => write!(f, "regex error: {}", &e),
             RcErr::Read(e) => write!(f, "read error: {}", &e),
+            RcErr::Write(e) => write!(f, "write error: {}", &e),
             RcErr::Utf8(e) => write!(f, "UTF-8 decoding error: {}", &e),
+            RcErr::ChunkTooLarge { size } => write!(
+                f,
+                "chunk too large: {} bytes buffered without a delimiter",
+                size
+            ),
+            RcErr::UnterminatedTail(tail) => write!(
+                f,
+                "source ended mid-chunk, with {} bytes after the last delimiter",
+                tail.len()
+            ),
+            RcErr::Timeout => write!(f, "timed out waiting for the source"),
+            #[cfg(feature = "serde")]
+            RcErr::Deserialize(e) => write!(f, "deserialization error: {}", &e),
+            #[cfg(feature = "base64")]
+            RcErr::Base64(e) => write!(f, "base64 decoding error: {}", &e),
+            #[cfg(feature = "hex")]
+            RcErr::Hex(e) => write!(f, "hex decoding error: {}", &e),
+            #[cfg(feature = "aho-corasick")]
+            RcErr::Literals(e) => write!(f, "literal searcher error: {}", &e),
         }
     }
 }
@@ -40,7 +189,7 @@ impl From<regex::Error> for RcErr {
 
 impl From<std::io::Error> for RcErr {
     fn from(e: std::io::Error) -> Self {
-        RcErr::Read(e)
+        RcErr::Read(Arc::new(e))
     }
 }
 
@@ -50,12 +199,94 @@ impl From<FromUtf8Error> for RcErr {
     }
 }
 
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for RcErr {
+    fn from(e: serde_json::Error) -> Self {
+        RcErr::Deserialize(Arc::new(e))
+    }
+}
+
+#[cfg(feature = "base64")]
+impl From<base64::DecodeError> for RcErr {
+    fn from(e: base64::DecodeError) -> Self {
+        RcErr::Base64(e)
+    }
+}
+
+#[cfg(feature = "hex")]
+impl From<hex::FromHexError> for RcErr {
+    fn from(e: hex::FromHexError) -> Self {
+        RcErr::Hex(e)
+    }
+}
+
+#[cfg(feature = "aho-corasick")]
+impl From<aho_corasick::BuildError> for RcErr {
+    fn from(e: aho_corasick::BuildError) -> Self {
+        RcErr::Literals(e)
+    }
+}
+
+/**
+Read and write errors are converted back into the `io::Error`s they
+came from (or, if the `RcErr` has been cloned, an `io::Error` of the
+same kind wrapping it), an [`RcErr::Timeout`] becomes an `io::Error`
+of kind [`ErrorKind::TimedOut`](std::io::ErrorKind::TimedOut), and an
+[`RcErr::UnterminatedTail`] becomes one of kind
+[`ErrorKind::UnexpectedEof`](std::io::ErrorKind::UnexpectedEof); any other
+`RcErr` becomes an `io::Error` of kind
+[`ErrorKind::InvalidData`](std::io::ErrorKind::InvalidData) wrapping it.
+
+This lets chunkers be used in functions that return `io::Result`s.
+
+```
+use regex_chunker::ByteChunker;
+use std::io::Cursor;
+
+fn count_fields(data: &[u8]) -> std::io::Result<usize> {
+    let mut n = 0;
+    for field in ByteChunker::new(Cursor::new(data), ",")? {
+        field?;
+        n += 1;
+    }
+    Ok(n)
+}
+
+assert_eq!(count_fields(b"a,b,c").unwrap(), 3);
+```
+*/
+impl From<RcErr> for std::io::Error {
+    fn from(e: RcErr) -> Self {
+        match e {
+            RcErr::Read(e) | RcErr::Write(e) => match Arc::try_unwrap(e) {
+                Ok(e) => e,
+                Err(e) => std::io::Error::new(e.kind(), e),
+            },
+            RcErr::Timeout => std::io::Error::new(std::io::ErrorKind::TimedOut, e),
+            RcErr::UnterminatedTail(_) => std::io::Error::new(std::io::ErrorKind::UnexpectedEof, e),
+            e => std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        }
+    }
+}
+
 impl Error for RcErr {
-    fn source<'a>(&'a self) -> Option<&(dyn Error + 'static)> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             RcErr::Regex(e) => Some(e),
-            RcErr::Read(e) => Some(e),
+            RcErr::Read(e) => Some(e.as_ref()),
+            RcErr::Write(e) => Some(e.as_ref()),
             RcErr::Utf8(e) => Some(e),
+            RcErr::ChunkTooLarge { .. } => None,
+            RcErr::UnterminatedTail(_) => None,
+            RcErr::Timeout => None,
+            #[cfg(feature = "serde")]
+            RcErr::Deserialize(e) => Some(e.as_ref()),
+            #[cfg(feature = "base64")]
+            RcErr::Base64(e) => Some(e),
+            #[cfg(feature = "hex")]
+            RcErr::Hex(e) => Some(e),
+            #[cfg(feature = "aho-corasick")]
+            RcErr::Literals(e) => Some(e),
         }
     }
 }
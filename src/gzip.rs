@@ -0,0 +1,44 @@
+/*!
+Chunking gzipped data.
+*/
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+
+use crate::{ByteChunker, RcErr};
+
+impl<R: Read> ByteChunker<GzDecoder<R>> {
+    /**
+    Return a new [`ByteChunker`] that decompresses the gzipped data read
+    from `source` and chunks the result by delimiting it with the
+    supplied regex pattern.
+
+    This is just shorthand for wrapping `source` in a
+    [`flate2::read::GzDecoder`](https://docs.rs/flate2/latest/flate2/read/struct.GzDecoder.html)
+    and passing that to [`ByteChunker::new`]. Decompression errors
+    (like corrupt or truncated data) are returned as [`RcErr::Read`].
+
+    ```
+    # use flate2::{write::GzEncoder, Compression};
+    # use std::io::Write;
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    # encoder.write_all(b"GET /\nGET /favicon.ico\nPOST /login\n")?;
+    # let gzipped = encoder.finish()?;
+    // gzipped: Vec<u8> holds a compressed log.
+    let requests = ByteChunker::new_gzip(Cursor::new(gzipped), r"\n")?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(requests.len(), 3);
+    assert_eq!(&requests[2], b"POST /login");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn new_gzip(source: R, delimiter: &str) -> Result<Self, RcErr> {
+        ByteChunker::new(GzDecoder::new(source), delimiter)
+    }
+}
@@ -0,0 +1,94 @@
+/*!
+An adapter for decoding hex-encoded chunks.
+*/
+use crate::{ctrl::DecodeFailureMode, Adapter, RcErr};
+
+/**
+An [`Adapter`] that decodes each chunk as a string of hexadecimal digits
+(in either case). Errors from the underlying chunker are passed through,
+and what happens when a chunk can't be decoded is controlled by a
+[`DecodeFailureMode`].
+
+A chunk with an odd number of digits can't be decoded, and produces an
+[`RcErr::Hex`] (wrapping a `hex::FromHexError::OddLength`), as does a
+chunk containing anything besides hex digits. By default this includes
+whitespace; to allow digits to be broken up with whitespace (like
+`de ad be ef`), use [`HexAdapter::skip_whitespace`].
+
+This requires the `hex` feature.
+
+```rust
+# use std::error::Error;
+# fn main() -> Result<(), Box<dyn Error>> {
+    use regex_chunker::{ByteChunker, DecodeFailureMode, HexAdapter, RcErr};
+    use std::io::Cursor;
+
+    let text = b"cafe\nf00d\nBEEF\n";
+    let words: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), r"\n")?
+        .with_adapter(HexAdapter::default())
+        .collect::<Result<_, _>>()?;
+    assert_eq!(&words, &[vec![0xca, 0xfe], vec![0xf0, 0x0d], vec![0xbe, 0xef]]);
+
+    let text = b"00 01 02\nabc\n0a0b\n";
+    let mut chunker = ByteChunker::new(Cursor::new(text), r"\n")?
+        .with_adapter(HexAdapter::new(DecodeFailureMode::Continue).skip_whitespace());
+    assert_eq!(chunker.next().unwrap()?, vec![0, 1, 2]);
+    assert!(matches!(chunker.next(), Some(Err(RcErr::Hex(hex::FromHexError::OddLength)))));
+    assert_eq!(chunker.next().unwrap()?, vec![0x0a, 0x0b]);
+#   Ok(()) }
+```
+*/
+#[cfg_attr(docsrs, doc(cfg(feature = "hex")))]
+#[derive(Clone, Debug, Default)]
+pub struct HexAdapter {
+    mode: DecodeFailureMode,
+    skip_whitespace: bool,
+    errored: bool,
+}
+
+impl HexAdapter {
+    /// Decode chunks, handling invalid ones as `mode` says.
+    pub fn new(mode: DecodeFailureMode) -> Self {
+        Self {
+            mode,
+            skip_whitespace: false,
+            errored: false,
+        }
+    }
+
+    /// Builder-pattern method for ignoring ASCII whitespace in chunks,
+    /// rather than treating it as an error.
+    pub fn skip_whitespace(mut self) -> Self {
+        self.skip_whitespace = true;
+        self
+    }
+}
+
+impl Adapter for HexAdapter {
+    type Item = Result<Vec<u8>, RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        if self.errored {
+            return None;
+        }
+
+        let mut v = match v? {
+            Ok(v) => v,
+            Err(e) => return Some(Err(e)),
+        };
+
+        if self.skip_whitespace {
+            v.retain(|b| !b.is_ascii_whitespace());
+        }
+
+        match hex::decode(&v) {
+            Ok(decoded) => Some(Ok(decoded)),
+            Err(e) => {
+                if let DecodeFailureMode::Fatal = self.mode {
+                    self.errored = true;
+                }
+                Some(Err(e.into()))
+            }
+        }
+    }
+}
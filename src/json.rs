@@ -0,0 +1,102 @@
+/*!
+An adapter for deserializing JSON chunks.
+*/
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use crate::{ctrl::DecodeFailureMode, Adapter, RcErr};
+
+/**
+An [`Adapter`] that deserializes each chunk as a JSON value of type `T`,
+for reading newline-delimited JSON (or any other stream of JSON values
+with a recognizable delimiter). Errors from the underlying chunker are
+passed through, and what happens when a chunk can't be deserialized is
+controlled by a [`DecodeFailureMode`].
+
+Blank lines will produce empty chunks, which aren't valid JSON; to
+tolerate them, use [`ByteChunker::skip_empty`](crate::ByteChunker::skip_empty).
+
+This requires the `serde` feature.
+
+```rust
+# use std::error::Error;
+# fn main() -> Result<(), Box<dyn Error>> {
+    use regex_chunker::{ByteChunker, JsonAdapter};
+    use serde::Deserialize;
+    use std::io::Cursor;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    let text = b"{\"x\": 1, \"y\": 2}\n{\"x\": -3, \"y\": 4}\n";
+    let c = Cursor::new(text);
+
+    let points: Vec<Point> = ByteChunker::new(c, r"\r?\n")?
+        .with_adapter(JsonAdapter::default())
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(&points, &[Point { x: 1, y: 2 }, Point { x: -3, y: 4 }]);
+#   Ok(()) }
+```
+*/
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub struct JsonAdapter<T> {
+    mode: DecodeFailureMode,
+    errored: bool,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T> JsonAdapter<T> {
+    pub fn new(mode: DecodeFailureMode) -> Self {
+        Self {
+            mode,
+            errored: false,
+            _item: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for JsonAdapter<T> {
+    fn default() -> Self {
+        Self::new(DecodeFailureMode::default())
+    }
+}
+
+impl<T> std::fmt::Debug for JsonAdapter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonAdapter")
+            .field("item", &std::any::type_name::<T>())
+            .field("mode", &self.mode)
+            .field("errored", &self.errored)
+            .finish()
+    }
+}
+
+impl<T: DeserializeOwned> Adapter for JsonAdapter<T> {
+    type Item = Result<T, RcErr>;
+
+    fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+        if self.errored {
+            return None;
+        }
+
+        let v = match v? {
+            Ok(v) => v,
+            Err(e) => return Some(Err(e)),
+        };
+
+        match serde_json::from_slice(&v) {
+            Ok(t) => Some(Ok(t)),
+            Err(e) => {
+                if let DecodeFailureMode::Fatal = self.mode {
+                    self.errored = true;
+                }
+                Some(Err(e.into()))
+            }
+        }
+    }
+}
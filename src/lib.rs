@@ -12,7 +12,7 @@ tally on text coming in on the standard input.
 ```rust
 use std::{collections::BTreeMap, error::Error};
 use regex_chunker::ByteChunker;
-  
+
 fn main() -> Result<(), Box<dyn Error>> {
     let mut counts: BTreeMap<String, usize> = BTreeMap::new();
     let stdin = std::io::stdin();
@@ -39,21 +39,79 @@ and implementing
 (This also pulls in several crates of
 [`tokio`](https://docs.rs/tokio/latest/tokio/index.html) machinery, which is why
 it's behind a feature flag.)
+
+Enabling the `serde` feature exposes the `JsonAdapter`, for deserializing
+streams of JSON values (like newline-delimited JSON).
+
+Enabling the `rayon` feature exposes [`par_chunks`],
+which chunks a large in-memory slice using multiple threads, and enabling
+the `mmap` feature exposes the `MmapChunker`, for chunking memory-mapped
+files. Enabling the `flate2` feature adds `ByteChunker::new_gzip`, which
+decompresses gzipped data before chunking it, and enabling the `base64`
+feature exposes the `Base64Adapter`, for decoding base64-encoded chunks
+(the `hex` feature similarly exposes the `HexAdapter`). Enabling the
+`encoding_rs` feature exposes the `EncodingAdapter`, for decoding text in
+encodings other than UTF-8. Enabling the `aho-corasick` feature adds
+`ByteChunker::with_literals`, which delimits chunks with any of a set of
+literal byte strings, and enabling the `crc32fast` feature exposes the
+`ChecksumAdapter`, which pairs each chunk with its CRC-32 checksum.
 */
 
 pub(crate) mod adapter;
 pub use adapter::*;
+#[cfg(any(feature = "base64", docsrs))]
+mod b64;
+#[cfg(any(feature = "base64", docsrs))]
+pub use b64::Base64Adapter;
 mod base;
 pub use base::*;
+#[cfg(any(feature = "crc32fast", docsrs))]
+mod checksum;
+#[cfg(any(feature = "crc32fast", docsrs))]
+pub use checksum::ChecksumAdapter;
 pub(crate) mod ctrl;
 pub use ctrl::*;
+mod csv;
+pub use csv::*;
 mod custom;
 pub use custom::*;
+#[cfg(any(feature = "encoding_rs", docsrs))]
+mod encoding;
+#[cfg(any(feature = "encoding_rs", docsrs))]
+pub use encoding::EncodingAdapter;
 mod err;
-pub use err::RcErr;
+pub use err::{RcErr, RcErrKind};
+#[cfg(any(feature = "flate2", docsrs))]
+mod gzip;
+#[cfg(any(feature = "hex", docsrs))]
+mod hexdec;
+#[cfg(any(feature = "hex", docsrs))]
+pub use hexdec::HexAdapter;
+#[cfg(any(feature = "serde", docsrs))]
+mod json;
+#[cfg(any(feature = "serde", docsrs))]
+pub use json::JsonAdapter;
+mod line;
+pub use line::*;
+mod meta;
+pub use meta::*;
+#[cfg(any(feature = "mmap", docsrs))]
+mod mmap;
+#[cfg(any(feature = "mmap", docsrs))]
+pub use mmap::MmapChunker;
+#[cfg(any(feature = "rayon", docsrs))]
+mod par;
+#[cfg(any(feature = "rayon", docsrs))]
+pub use par::par_chunks;
+mod reader;
+pub use reader::*;
+mod slice;
+pub use slice::*;
 #[cfg(any(feature = "async", docsrs))]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub mod stream;
+mod util;
+pub use util::*;
 
 #[cfg(test)]
 pub(crate) mod tests {
@@ -240,4 +298,2904 @@ pub(crate) mod tests {
             .with_adapter(StringAdapter::default());
         assert!(matches!(chunker.next(), Some(Ok(_))));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn string_lossy() {
+        let text: &[u8] = b"ok\n\xe2\x82!\n\xc3\xa9t\xc3t\n\xf0\x9f\x98\x80\xff\xfe";
+        let mut chunker = ByteChunker::new(Cursor::new(text), r"\n")
+            .unwrap()
+            .with_adapter(StringAdapter::new(Utf8FailureMode::Lossy));
+        let strings: Vec<String> = (&mut chunker).collect::<Result<_, _>>().unwrap();
+
+        let expected: Vec<String> = text
+            .split(|&b| b == b'\n')
+            .map(|v| String::from_utf8_lossy(v).into())
+            .collect();
+        assert_eq!(strings, expected);
+        let replaced = expected
+            .iter()
+            .flat_map(|s| s.chars())
+            .filter(|&c| c == '\u{FFFD}');
+        assert_eq!(chunker.get_adapter().replacement_count(), replaced.count());
+
+        for replacement in ["", "?", "<bad>"] {
+            let mut chunker = ByteChunker::new(Cursor::new(text), r"\n")
+                .unwrap()
+                .with_adapter(StringAdapter::default().with_replacement(replacement.to_string()));
+            let strings: Vec<String> = (&mut chunker).collect::<Result<_, _>>().unwrap();
+            let expected: Vec<String> = expected
+                .iter()
+                .map(|s| s.replace('\u{FFFD}', replacement))
+                .collect();
+            assert_eq!(strings, expected);
+            assert_eq!(chunker.get_adapter().replacement_count(), 4);
+        }
+    }
+
+    #[test]
+    fn string_split_chars() {
+        /* "naïve café 😀", split every which way. */
+        let text = "naïve café 😀".as_bytes();
+        for size in 1..=5 {
+            for mode in [Utf8FailureMode::Fatal, Utf8FailureMode::Lossy] {
+                let s: String = ByteChunker::new(Cursor::new(text), "$^")
+                    .unwrap()
+                    .with_max_yield_size(size)
+                    .with_adapter(StringAdapter::new(mode).join_split_chars())
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+                assert_eq!(s, "naïve café 😀", "{size} {mode:?}");
+            }
+        }
+
+        /* Leftovers at the end are still invalid. */
+        let results: Vec<Result<String, RcErr>> =
+            ByteChunker::new(Cursor::new(b"ok\xe2\x82"), "$^")
+                .unwrap()
+                .with_max_yield_size(1)
+                .with_adapter(StringAdapter::default().join_split_chars())
+                .collect();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[2], Err(RcErr::Utf8(_))));
+
+        let s: Vec<String> = ByteChunker::new(Cursor::new(b"ok\xe2\x82"), ",")
+            .unwrap()
+            .with_adapter(
+                StringAdapter::default()
+                    .with_replacement("?")
+                    .join_split_chars(),
+            )
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(s, vec!["ok", "?"]);
+
+        /* Without joining, the last chunk is decoded as it is. */
+        let s: Vec<String> = ByteChunker::new(Cursor::new(b"ok\xe2\x82"), ",")
+            .unwrap()
+            .with_adapter(StringAdapter::default().with_replacement("?"))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(s, vec!["ok?"]);
+    }
+
+    /* A character cut short right before a delimiter isn't joined to the
+    start of the next chunk. */
+    #[test]
+    fn string_partial_char_before_delimiter() {
+        let text = b"abc\xe2\ndef\n";
+        for join in [false, true] {
+            let adapter = |mode| match join {
+                true => StringAdapter::new(mode).join_split_chars(),
+                false => StringAdapter::new(mode),
+            };
+
+            let s: Vec<String> = ByteChunker::new(Cursor::new(text), "\n")
+                .unwrap()
+                .with_adapter(adapter(Utf8FailureMode::Lossy))
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(s, vec!["abc\u{FFFD}", "def"]);
+
+            let results: Vec<Result<String, RcErr>> = ByteChunker::new(Cursor::new(text), "\n")
+                .unwrap()
+                .with_adapter(adapter(Utf8FailureMode::Continue))
+                .collect();
+            assert!(matches!(&results[0], Err(RcErr::Utf8(e)) if e.as_bytes() == b"abc\xe2"));
+            assert_eq!(results[1].as_deref().ok(), Some("def"));
+        }
+
+        let lines: Vec<String> = LineChunker::new(Cursor::new(text))
+            .strings_with(Utf8FailureMode::Lossy)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(lines, vec!["abc\u{FFFD}", "def"]);
+    }
+
+    /* Driven through act(), a chunk that's only the start of a
+    character is skipped, rather than ending the stream. */
+    #[test]
+    fn string_adapt_split_chars() {
+        let mut adapter = StringAdapter::default().join_split_chars();
+        assert_eq!(
+            adapter.act(Some(Ok(b"caf\xc3".to_vec()))),
+            AdapterAction::Emit(Ok("caf".into()))
+        );
+        assert_eq!(
+            adapter.act(Some(Ok(b"\xa9 \xf0\x9f".to_vec()))),
+            AdapterAction::Emit(Ok("é ".into()))
+        );
+        assert_eq!(
+            adapter.act(Some(Ok(b"\x98".to_vec()))),
+            AdapterAction::Skip
+        );
+        assert_eq!(
+            adapter.act(Some(Ok(b"\x80!".to_vec()))),
+            AdapterAction::Emit(Ok("😀!".into()))
+        );
+        assert_eq!(adapter.act(None), AdapterAction::Done);
+
+        /* Through adapt(), which can't skip, it's `None`. */
+        let mut adapter = StringAdapter::default().join_split_chars();
+        assert_eq!(adapter.adapt(Some(Ok(b"\xc3".to_vec()))), None);
+        assert_eq!(adapter.adapt(Some(Ok(b"\xa9".to_vec()))), Some(Ok("é".into())));
+    }
+
+    #[test]
+    fn max_chunk_size() {
+        let text = b"abcdefghij,k,lmnopqrstuv";
+
+        let vec_vec: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ",")
+            .unwrap()
+            .with_max_chunk_size(4)
+            .on_oversize(OversizePolicy::Yield)
+            .map(|res| res.unwrap())
+            .collect();
+        ref_slice_cmp(
+            &vec_vec,
+            &["abcd", "efgh", "ij", "k", "lmno", "pqrs", "tuv"],
+        );
+        assert_eq!(vec_vec.len(), 7);
+
+        let vec_vec: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ",")
+            .unwrap()
+            .with_max_chunk_size(4)
+            .on_oversize(OversizePolicy::Yield)
+            .with_match(MatchDisposition::Append)
+            .map(|res| res.unwrap())
+            .collect();
+        ref_slice_cmp(
+            &vec_vec,
+            &["abcd", "efgh", "ij,", "k,", "lmno", "pqrs", "tuv"],
+        );
+        assert_eq!(vec_vec.len(), 7);
+
+        let vec_vec: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ",")
+            .unwrap()
+            .with_max_chunk_size(4)
+            .on_oversize(OversizePolicy::Yield)
+            .with_match(MatchDisposition::Prepend)
+            .map(|res| res.unwrap())
+            .collect();
+        ref_slice_cmp(
+            &vec_vec,
+            &["abcd", "efgh", "ij", ",k", ",lmn", "opqr", "stuv"],
+        );
+        assert_eq!(vec_vec.len(), 7);
+
+        let mut chunker = ByteChunker::new(Cursor::new(text), ",")
+            .unwrap()
+            .with_max_chunk_size(4);
+        assert!(matches!(
+            chunker.next(),
+            Some(Err(RcErr::ChunkTooLarge { .. }))
+        ));
+        assert!(chunker.next().is_none());
+
+        let vec_vec: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ",")
+            .unwrap()
+            .with_max_chunk_size(4)
+            .on_error(ErrorResponse::Ignore)
+            .map(|res| res.unwrap())
+            .collect();
+        ref_slice_cmp(&vec_vec, &["ij", "k", "tuv"]);
+        assert_eq!(vec_vec.len(), 3);
+    }
+
+    #[test]
+    fn offsets() {
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+
+        for dispo in [
+            MatchDisposition::Drop,
+            MatchDisposition::Append,
+            MatchDisposition::Prepend,
+        ] {
+            let chunker = ByteChunker::new(File::open(PASSWD_PATH).unwrap(), PASSWD_PATT)
+                .unwrap()
+                .with_buffer_size(16)
+                .with_match(dispo)
+                .with_offsets();
+            let mut prev_end = 0;
+            for meta in chunker.map(|res| res.unwrap()) {
+                assert!(meta.start >= prev_end);
+                let (start, end) = (meta.start as usize, meta.end as usize);
+                assert_eq!(&byte_vec[start..end], meta.bytes.as_slice());
+                prev_end = meta.end;
+            }
+            if !matches!(dispo, MatchDisposition::Drop) {
+                assert_eq!(prev_end as usize, byte_vec.len());
+            }
+        }
+    }
+
+    #[test]
+    fn captures() {
+        let text = b"1:alpha2:beta:gamma3:";
+        let patt = r"(\d)?:";
+
+        let chunks: Vec<_> = ByteChunker::new(Cursor::new(text), patt)
+            .unwrap()
+            .with_captures()
+            .map(|res| res.unwrap())
+            .collect();
+        let groups: Vec<_> = chunks.iter().map(|(_, g)| g.clone()).collect();
+        assert_eq!(
+            groups,
+            vec![
+                vec![Some(b"1".to_vec())],
+                vec![Some(b"2".to_vec())],
+                vec![None],
+                vec![Some(b"3".to_vec())],
+            ]
+        );
+
+        let chunks: Vec<_> = ByteChunker::new(Cursor::new(text), patt)
+            .unwrap()
+            .with_match(MatchDisposition::Prepend)
+            .with_captures()
+            .map(|res| res.unwrap())
+            .collect();
+        let bytes: Vec<_> = chunks.iter().map(|(b, _)| b.clone()).collect();
+        ref_slice_cmp(&bytes, &["", "1:alpha", "2:beta", ":gamma", "3:"]);
+        let groups: Vec<_> = chunks.iter().map(|(_, g)| g.clone()).collect();
+        assert_eq!(
+            groups,
+            vec![
+                vec![],
+                vec![Some(b"1".to_vec())],
+                vec![Some(b"2".to_vec())],
+                vec![None],
+                vec![Some(b"3".to_vec())],
+            ]
+        );
+
+        /* Converting a ByteChunker sets it up the same way, and taking
+        it back out stops it keeping captures. */
+        let chunker: CaptureChunker<_> = ByteChunker::new(Cursor::new(b"a[1]b[2]c"), r"\[(\d)\]")
+            .unwrap()
+            .into();
+        let chunker = chunker.into_inner();
+        assert!(format!("{:?}", chunker).contains("track_captures: false"));
+        let chunker: CaptureChunker<_> = chunker.into();
+        let groups: Vec<_> = chunker.map(|res| res.unwrap().1).collect();
+        assert_eq!(
+            groups,
+            vec![vec![Some(b"1".to_vec())], vec![Some(b"2".to_vec())], vec![]]
+        );
+    }
+
+    #[test]
+    fn delimiters() {
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+
+        let mut rebuilt: Vec<u8> = Vec::new();
+        let chunker = ByteChunker::new(File::open(PASSWD_PATH).unwrap(), PASSWD_PATT)
+            .unwrap()
+            .with_buffer_size(16)
+            .with_delimiters();
+        for (bytes, delim) in chunker.map(|res| res.unwrap()) {
+            rebuilt.extend_from_slice(&bytes);
+            if let Some(delim) = delim {
+                rebuilt.extend_from_slice(&delim);
+            }
+        }
+
+        assert_eq!(&rebuilt, &byte_vec);
+
+        /* Converting a ByteChunker sets it up the same way. */
+        let chunker: DelimitedChunker<_> = ByteChunker::new(Cursor::new(b"a;b;c"), ";")
+            .unwrap()
+            .with_match(MatchDisposition::Append)
+            .into();
+        let chunks: Vec<_> = chunker.collect::<Result<_, _>>().unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                (b"a".to_vec(), Some(b";".to_vec())),
+                (b"b".to_vec(), Some(b";".to_vec())),
+                (b"c".to_vec(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn skip_empty() {
+        let text = b",,one,,two,,";
+        let re = Regex::new(",").unwrap();
+
+        for dispo in [
+            MatchDisposition::Drop,
+            MatchDisposition::Append,
+            MatchDisposition::Prepend,
+        ] {
+            let expected: Vec<&[u8]> = chunk_vec(&re, text, dispo)
+                .into_iter()
+                .filter(|v| !v.is_empty())
+                .collect();
+            let vec_vec: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ",")
+                .unwrap()
+                .with_match(dispo)
+                .skip_empty()
+                .map(|res| res.unwrap())
+                .collect();
+
+            assert_eq!(vec_vec.len(), expected.len());
+            ref_slice_cmp(&vec_vec, &expected);
+        }
+    }
+
+    #[test]
+    fn min_chunk_size() {
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+        let re = Regex::new(PASSWD_PATT).unwrap();
+
+        for dispo in [MatchDisposition::Append, MatchDisposition::Prepend] {
+            let slice_vec = chunk_vec(&re, &byte_vec, dispo);
+            let vec_vec: Vec<Vec<u8>> =
+                ByteChunker::new(File::open(PASSWD_PATH).unwrap(), PASSWD_PATT)
+                    .unwrap()
+                    .with_match(dispo)
+                    .with_min_chunk_size(64)
+                    .map(|res| res.unwrap())
+                    .collect();
+
+            let (last, rest) = vec_vec.split_last().unwrap();
+            assert!(rest.iter().all(|v| v.len() >= 64));
+            assert!(!last.is_empty());
+            assert_eq!(vec_vec.concat(), slice_vec.concat());
+        }
+
+        let text = b"one,two,,three,four";
+        let vec_vec: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ",+")
+            .unwrap()
+            .with_min_chunk_size(6)
+            .map(|res| res.unwrap())
+            .collect();
+        ref_slice_cmp(&vec_vec, &["one,two", "three,four"]);
+        assert_eq!(vec_vec.len(), 2);
+    }
+
+    #[test]
+    fn max_yield_size() {
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+
+        let chunker = ByteChunker::new(File::open(PASSWD_PATH).unwrap(), r"\n")
+            .unwrap()
+            .with_match(MatchDisposition::Append)
+            .with_max_yield_size(7)
+            .with_offsets();
+        let mut rebuilt: Vec<u8> = Vec::new();
+        for meta in chunker.map(|res| res.unwrap()) {
+            assert!(meta.bytes.len() <= 7);
+            assert_eq!(meta.start as usize, rebuilt.len());
+            rebuilt.extend_from_slice(&meta.bytes);
+            assert_eq!(meta.end as usize, rebuilt.len());
+        }
+        assert_eq!(&rebuilt, &byte_vec);
+
+        /* Characters split between pieces are put back together. */
+        let pieces: Vec<String> = ByteChunker::new(Cursor::new("über straße".as_bytes()), " ")
+            .unwrap()
+            .with_max_yield_size(2)
+            .with_adapter(StringAdapter::default().join_split_chars())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(pieces.iter().all(|piece| piece.len() <= 3));
+        assert_eq!(pieces.concat(), "überstraße");
+    }
+
+    #[test]
+    fn from_buf_read() {
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+        let re = Regex::new(PASSWD_PATT).unwrap();
+        let slice_vec = chunk_vec(&re, &byte_vec, MatchDisposition::Append);
+
+        for size in [1, 7, 1024] {
+            let reader = std::io::BufReader::with_capacity(size, File::open(PASSWD_PATH).unwrap());
+            let chunk_vec: Vec<Vec<u8>> = ByteChunker::from_buf_read(reader, PASSWD_PATT)
+                .unwrap()
+                .with_match(MatchDisposition::Append)
+                .map(|res| res.unwrap())
+                .collect();
+            ref_slice_cmp(&slice_vec, &chunk_vec);
+        }
+
+        /* Chunks found whole in the reader's buffer come straight out of
+        it, and nothing else is buffered by the chunker. */
+        let reader = std::io::BufReader::with_capacity(64, Cursor::new("one,two,three"));
+        let mut chunker = ByteChunker::from_buf_read(reader, ",").unwrap();
+        assert_eq!(chunker.next().unwrap().unwrap(), b"one");
+        assert_eq!(chunker.buffered_len(), 0);
+        assert_eq!(chunker.next().unwrap().unwrap(), b"two");
+        assert_eq!(chunker.next().unwrap().unwrap(), b"three");
+        assert!(chunker.next().is_none());
+
+        /* Whether chunks are found in the reader's buffer or in the
+        chunker's doesn't change what comes out, or what's said about it. */
+        let text = b"ab,,cde,f,,,ghij,k,";
+        let reader = |size| std::io::BufReader::with_capacity(size, Cursor::new(text));
+        let delimited: Vec<(Vec<u8>, Option<Vec<u8>>)> = ByteChunker::new(Cursor::new(text), ",+")
+            .unwrap()
+            .with_delimiters()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        for dispo in [
+            MatchDisposition::Drop,
+            MatchDisposition::Append,
+            MatchDisposition::Prepend,
+        ] {
+            let metas: Vec<ChunkMeta> = ByteChunker::new(Cursor::new(text), ",+")
+                .unwrap()
+                .with_match(dispo)
+                .with_offsets()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            for size in [1, 3, 5, 64] {
+                let buf_metas: Vec<ChunkMeta> = ByteChunker::from_buf_read(reader(size), ",+")
+                    .unwrap()
+                    .with_match(dispo)
+                    .with_offsets()
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+                assert_eq!(buf_metas, metas);
+            }
+        }
+        for size in [1, 3, 5, 64] {
+            let buf_delimited: Vec<(Vec<u8>, Option<Vec<u8>>)> =
+                ByteChunker::from_buf_read(reader(size), ",+")
+                    .unwrap()
+                    .with_delimiters()
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+            assert_eq!(buf_delimited, delimited);
+        }
+
+        /* Switching away from Prepend after a chunk found in the reader's
+        buffer deals with the delimiter it left there. */
+        let text = b"a,b,c,d";
+        for dispo in [
+            MatchDisposition::Drop,
+            MatchDisposition::Append,
+            MatchDisposition::Prepend,
+        ] {
+            let mut chunker = ByteChunker::new(Cursor::new(text), ",")
+                .unwrap()
+                .with_match(MatchDisposition::Prepend);
+            let mut expected = vec![chunker.next().unwrap().unwrap()];
+            let rest: Vec<Vec<u8>> = chunker.with_match(dispo).collect::<Result<_, _>>().unwrap();
+            expected.extend(rest);
+            for size in [1, 3, 64] {
+                let reader = std::io::BufReader::with_capacity(size, Cursor::new(text));
+                let mut chunker = ByteChunker::from_buf_read(reader, ",")
+                    .unwrap()
+                    .with_match(MatchDisposition::Prepend);
+                let mut chunks = vec![chunker.next().unwrap().unwrap()];
+                let rest: Vec<Vec<u8>> =
+                    chunker.with_match(dispo).collect::<Result<_, _>>().unwrap();
+                chunks.extend(rest);
+                assert_eq!(chunks, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn long_gaps() {
+        let mut text: Vec<u8> = Vec::new();
+        for n in 0..4 {
+            text.resize(text.len() + 200_000, b'a' + n);
+            text.extend_from_slice(b"<>");
+        }
+
+        for patt in ["<>", "<+>+"] {
+            let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(&text), patt)
+                .unwrap()
+                .with_buffer_size(64)
+                .with_rescan_window(16)
+                .map(|res| res.unwrap())
+                .collect();
+            assert_eq!(chunks.len(), 4);
+            for (n, chunk) in chunks.iter().enumerate() {
+                assert_eq!(chunk.len(), 200_000);
+                assert!(chunk.iter().all(|&b| b == b'a' + n as u8));
+            }
+        }
+    }
+
+    #[test]
+    fn zero_width_matches() {
+        let text = b"xxaxbcxxx";
+        for (patt, dispo, expected) in [
+            ("x*", MatchDisposition::Drop, vec![&b""[..], b"a", b"bc"]),
+            (
+                "x*",
+                MatchDisposition::Append,
+                vec![&b"xx"[..], b"ax", b"bcxxx"],
+            ),
+            (
+                "x*",
+                MatchDisposition::Prepend,
+                vec![&b""[..], b"xxa", b"xbc", b"xxx"],
+            ),
+            (r"\b", MatchDisposition::Drop, vec![&text[..]]),
+        ] {
+            let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), patt)
+                .unwrap()
+                .with_match(dispo)
+                .with_captures()
+                .map(|res| res.unwrap().0)
+                .collect();
+            assert_eq!(chunks, expected);
+        }
+    }
+
+    /// Reads only one byte at a time from the wrapped reader.
+    struct Trickle<R>(R);
+
+    impl<R: Read> Read for Trickle<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(1);
+            self.0.read(&mut buf[..n])
+        }
+    }
+
+    #[test]
+    fn boundary_matches() {
+        let text = b"one\r\n\r\ntwo\n\nthree\r\n\n";
+        let chunks: Vec<Vec<u8>> = ByteChunker::new(Trickle(Cursor::new(text)), r"\r?\n\r?\n")
+            .unwrap()
+            .map(|res| res.unwrap())
+            .collect();
+        assert_eq!(chunks, vec![&b"one"[..], b"two", b"three"]);
+
+        /* Whether an assertion holds at the end of what's been read can
+        depend on what comes next. */
+        let cases: [(&str, &[u8], &[&str]); 3] = [
+            (r"a\b", b"xa-ab-c", &["x", "-ab-c"]),
+            (r"\n$", b"one\ntwo", &["one\ntwo"]),
+            (r",\B", b"12,34", &["12,34"]),
+        ];
+        for (patt, text, expected) in cases {
+            let chunks: Vec<Vec<u8>> = ByteChunker::new(Trickle(Cursor::new(text)), patt)
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            ref_slice_cmp(&chunks, expected);
+            assert_eq!(chunks.len(), expected.len());
+
+            let reader = std::io::BufReader::with_capacity(1, Cursor::new(text));
+            let chunks: Vec<Vec<u8>> = ByteChunker::from_buf_read(reader, patt)
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            ref_slice_cmp(&chunks, expected);
+            assert_eq!(chunks.len(), expected.len());
+        }
+
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+        let re = Regex::new(PASSWD_PATT).unwrap();
+        for dispo in [
+            MatchDisposition::Drop,
+            MatchDisposition::Append,
+            MatchDisposition::Prepend,
+        ] {
+            let slice_vec = chunk_vec(&re, &byte_vec, dispo);
+            let chunks: Vec<Vec<u8>> =
+                ByteChunker::new(Trickle(File::open(PASSWD_PATH).unwrap()), PASSWD_PATT)
+                    .unwrap()
+                    .with_match(dispo)
+                    .map(|res| res.unwrap())
+                    .collect();
+            ref_slice_cmp(&chunks, &slice_vec);
+        }
+    }
+
+    #[test]
+    fn slices() {
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+        let re = Regex::new(PASSWD_PATT).unwrap();
+        for dispo in [
+            MatchDisposition::Drop,
+            MatchDisposition::Append,
+            MatchDisposition::Prepend,
+        ] {
+            let slice_vec = chunk_vec(&re, &byte_vec, dispo);
+            let chunks: Vec<&[u8]> = SliceChunker::new(&byte_vec, PASSWD_PATT)
+                .unwrap()
+                .with_match(dispo)
+                .collect();
+            ref_slice_cmp(&chunks, &slice_vec);
+
+            let byte_chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(&byte_vec), PASSWD_PATT)
+                .unwrap()
+                .with_match(dispo)
+                .map(|res| res.unwrap())
+                .collect();
+            assert_eq!(chunks, byte_chunks);
+        }
+    }
+
+    #[test]
+    fn borrowed() {
+        for dispo in [
+            MatchDisposition::Drop,
+            MatchDisposition::Append,
+            MatchDisposition::Prepend,
+        ] {
+            let expected: Vec<Vec<u8>> =
+                ByteChunker::new(File::open(PASSWD_PATH).unwrap(), PASSWD_PATT)
+                    .unwrap()
+                    .with_match(dispo)
+                    .map(|res| res.unwrap())
+                    .collect();
+            let mut chunker = ByteChunker::new(File::open(PASSWD_PATH).unwrap(), PASSWD_PATT)
+                .unwrap()
+                .with_buffer_size(16)
+                .with_match(dispo);
+            let mut chunks: Vec<Vec<u8>> = Vec::new();
+            // Alternate borrowed and owned chunks.
+            while let Some(res) = chunker.next_borrowed() {
+                chunks.push(res.unwrap().into_owned());
+                match chunker.next() {
+                    Some(res) => chunks.push(res.unwrap()),
+                    None => break,
+                }
+            }
+            assert_eq!(chunks, expected);
+        }
+    }
+
+    #[test]
+    fn reset() {
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+        let re = Regex::new(PASSWD_PATT).unwrap();
+        let slice_vec = chunk_vec(&re, &byte_vec, MatchDisposition::Prepend);
+
+        let source: Box<dyn Read> = Box::new(ErrorReader);
+        let mut chunker = ByteChunker::new(source, PASSWD_PATT)
+            .unwrap()
+            .with_match(MatchDisposition::Prepend)
+            .with_offsets();
+        assert!(chunker.next().unwrap().is_err());
+        assert!(chunker.next().is_none());
+
+        let mut chunker = chunker.into_inner();
+        chunker.reset(Box::new(File::open(PASSWD_PATH).unwrap()));
+        let mut chunker = chunker.with_offsets();
+        let first = chunker.next().unwrap().unwrap();
+        assert_eq!(first.start, 0);
+
+        let mut chunker = chunker.into_inner();
+        chunker.reset(Box::new(File::open(PASSWD_PATH).unwrap()));
+        let chunks: Vec<Vec<u8>> = chunker.map(|res| res.unwrap()).collect();
+        ref_slice_cmp(&chunks, &slice_vec);
+    }
+
+    /// Fails every read.
+    struct ErrorReader;
+
+    impl Read for ErrorReader {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("nope"))
+        }
+    }
+
+    #[test]
+    fn peek() {
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+        let re = Regex::new(PASSWD_PATT).unwrap();
+        let slice_vec = chunk_vec(&re, &byte_vec, MatchDisposition::Drop);
+
+        let mut chunker = ByteChunker::new(File::open(PASSWD_PATH).unwrap(), PASSWD_PATT).unwrap();
+        let mut chunks: Vec<Vec<u8>> = Vec::new();
+        while let Some(peeked) = chunker.peek() {
+            let peeked = peeked.as_ref().unwrap().clone();
+            assert_eq!(chunker.peek().unwrap().as_ref().unwrap(), &peeked);
+            chunks.push(chunker.next().unwrap().unwrap());
+            assert_eq!(chunks.last(), Some(&peeked));
+        }
+        assert!(chunker.next().is_none());
+        ref_slice_cmp(&chunks, &slice_vec);
+    }
+
+    #[test]
+    fn count() {
+        let new = || {
+            ByteChunker::new(File::open(PASSWD_PATH).unwrap(), PASSWD_PATT)
+                .unwrap()
+                .with_buffer_size(64)
+        };
+        let expected = new().collect::<Vec<_>>().len();
+
+        assert_eq!(new().count(), expected);
+        let prepend = || new().with_match(MatchDisposition::Prepend);
+        assert_eq!(prepend().count(), prepend().collect::<Vec<_>>().len());
+        assert_eq!(new().with_skip(3).count(), expected - 3);
+        assert_eq!(new().with_chunk_limit(5).count(), 5);
+        let mut chunker = new();
+        chunker.next().unwrap().unwrap();
+        chunker.peek();
+        assert_eq!(chunker.count(), expected - 1);
+
+        /* A final chunk with no delimiter after it is still counted. */
+        assert_eq!(LineChunker::new(Cursor::new("a\nb\r\nc")).count(), 3);
+
+        /* Errors count as items, and the error response still decides
+        whether there's anything after them. */
+        assert_eq!(ByteChunker::new(ErrorReader, ",").unwrap().count(), 1);
+        let limited = ByteChunker::new(ErrorReader, ",")
+            .unwrap()
+            .on_error(ErrorResponse::Continue)
+            .take(4)
+            .count();
+        assert_eq!(limited, 4);
+    }
+
+    #[test]
+    fn exhausted() {
+        for size in [1, 3, 1024] {
+            for dispo in [MatchDisposition::Drop, MatchDisposition::Prepend] {
+                let mut chunker = ByteChunker::new(Cursor::new("a,b,c,"), ",")
+                    .unwrap()
+                    .with_buffer_size(size)
+                    .with_match(dispo);
+                while chunker.next().is_some() {}
+                assert!(chunker.is_exhausted());
+
+                let mut chunker = ByteChunker::new(Cursor::new("a,b,c"), ",")
+                    .unwrap()
+                    .with_buffer_size(size)
+                    .with_match(dispo);
+                chunker.next().unwrap().unwrap();
+                assert!(!chunker.is_exhausted());
+                chunker.next().unwrap().unwrap();
+                chunker.peek();
+                assert!(!chunker.is_exhausted());
+                chunker.next().unwrap().unwrap();
+                assert!(chunker.is_exhausted());
+            }
+        }
+
+        let mut chunker = ByteChunker::new(Cursor::new("a,b,c"), ",")
+            .unwrap()
+            .with_min_chunk_size(2);
+        assert_eq!(chunker.next().unwrap().unwrap(), b"a,b");
+        assert!(!chunker.is_exhausted());
+        assert_eq!(chunker.next().unwrap().unwrap(), b"c");
+        assert!(chunker.is_exhausted());
+
+        let mut chunker = ByteChunker::new(ErrorReader, ",").unwrap();
+        while chunker.next().is_some() {}
+        assert!(!chunker.is_exhausted());
+    }
+
+    #[test]
+    fn finish() {
+        for size in [1, 4, 1024] {
+            let new = |text: &'static str| {
+                ByteChunker::new(Cursor::new(text), ";")
+                    .unwrap()
+                    .with_buffer_size(size)
+            };
+
+            let (chunks, tail) = new("one;two;thr").finish().unwrap();
+            ref_slice_cmp(&chunks, &["one", "two"]);
+            assert_eq!(tail, b"thr");
+
+            let (chunks, tail) = new("one;two;").finish().unwrap();
+            ref_slice_cmp(&chunks, &["one", "two"]);
+            assert!(tail.is_empty());
+
+            let (chunks, tail) = new("partial").finish().unwrap();
+            assert!(chunks.is_empty());
+            assert_eq!(tail, b"partial");
+
+            let (chunks, tail) = new(";a;b;c")
+                .with_match(MatchDisposition::Prepend)
+                .finish()
+                .unwrap();
+            ref_slice_cmp(&chunks, &["", ";a", ";b"]);
+            assert_eq!(tail, b";c");
+
+            /* The trailing policy is ignored. */
+            let (_, tail) = new("one;thr")
+                .with_trailing(TrailingPolicy::Error)
+                .finish()
+                .unwrap();
+            assert_eq!(tail, b"thr");
+        }
+
+        assert!(ByteChunker::new(ErrorReader, ";")
+            .unwrap()
+            .finish()
+            .is_err());
+    }
+
+    #[test]
+    fn from_sources() {
+        let text = std::fs::read(PASSWD_PATH).unwrap();
+        let re = Regex::new(PASSWD_PATT).unwrap();
+        let expected = chunk_vec(&re, &text, MatchDisposition::Drop);
+
+        /* Cut the text at every few bytes, so chunks and delimiters are
+        split across sources. */
+        let sources: Vec<Cursor<&[u8]>> = text.chunks(7).map(Cursor::new).collect();
+        let chunks: Vec<Vec<u8>> = ByteChunker::from_sources(sources, PASSWD_PATT)
+            .unwrap()
+            .map(|res| res.unwrap())
+            .collect();
+        ref_slice_cmp(&chunks, &expected);
+
+        /* A two-byte delimiter split between two sources is still one
+        delimiter, and empty sources are passed over. */
+        let sources = vec![
+            Cursor::new(&b"abc\r"[..]),
+            Cursor::new(&b""[..]),
+            Cursor::new(&b"\ndef"[..]),
+        ];
+        let chunks: Vec<Vec<u8>> = ByteChunker::from_sources(sources, "\r\n|\r|\n")
+            .unwrap()
+            .map(|res| res.unwrap())
+            .collect();
+        ref_slice_cmp(&chunks, &["abc", "def"]);
+
+        let no_sources: Vec<Cursor<&[u8]>> = Vec::new();
+        assert_eq!(
+            ByteChunker::from_sources(no_sources, ",").unwrap().count(),
+            0
+        );
+    }
+
+    #[test]
+    fn interrupted() {
+        /* Gets interrupted before every read that succeeds. */
+        struct Interrupted {
+            text: Cursor<&'static [u8]>,
+            interrupt: bool,
+        }
+        impl Read for Interrupted {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.interrupt = !self.interrupt;
+                if self.interrupt {
+                    return Err(std::io::ErrorKind::Interrupted.into());
+                }
+                self.text.read(buf)
+            }
+        }
+
+        let source = Interrupted {
+            text: Cursor::new(b"a,b,c"),
+            interrupt: false,
+        };
+        /* Backing off would take seconds. */
+        let start = std::time::Instant::now();
+        let chunks: Vec<Vec<u8>> = ByteChunker::new(source, ",")
+            .unwrap()
+            .with_buffer_size(1)
+            .with_block_policy(BlockPolicy::Sleep(std::time::Duration::from_secs(1)))
+            .map(|res| res.unwrap())
+            .collect();
+        ref_slice_cmp(&chunks, &["a", "b", "c"]);
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn eager() {
+        /* Hands over its pieces one read at a time. */
+        struct Pieces(Vec<&'static [u8]>);
+        impl Read for Pieces {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0.is_empty() {
+                    return Ok(0);
+                }
+                let piece = self.0.remove(0);
+                buf[..piece.len()].copy_from_slice(piece);
+                Ok(piece.len())
+            }
+        }
+        let pieces = || Pieces(vec![b"one\n", b"\ntwo\n\n", b"three"]);
+
+        let chunks: Vec<Vec<u8>> = ByteChunker::new(pieces(), r"\n+")
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        ref_slice_cmp(&chunks, &["one", "two", "three"]);
+
+        /* Eager mode doesn't wait to see that the first newline is
+        followed by another. */
+        let mut chunker = ByteChunker::new(pieces(), r"\n+").unwrap().with_eager(true);
+        assert_eq!(chunker.next().unwrap().unwrap(), b"one");
+        assert_eq!(chunker.get_ref().0.len(), 2);
+        let rest: Vec<Vec<u8>> = chunker.map(Result::unwrap).collect();
+        ref_slice_cmp(&rest, &["", "two", "three"]);
+
+        /* A match filter still gets to look past the match. */
+        let mut chunker = ByteChunker::new(pieces(), r"\n+")
+            .unwrap()
+            .with_eager(true)
+            .with_match_filter(|_: &[u8], _| true);
+        assert_eq!(chunker.next().unwrap().unwrap(), b"one");
+        assert_eq!(chunker.get_ref().0.len(), 1);
+    }
+
+    #[test]
+    fn buffered_len() {
+        let mut chunker = ByteChunker::new(Cursor::new("one;;two;;three"), ";;")
+            .unwrap()
+            .with_match(MatchDisposition::Prepend);
+        assert_eq!(chunker.next_borrowed().unwrap().unwrap(), &b"one"[..]);
+        assert_eq!(chunker.buffered_len(), 12);
+        assert_eq!(chunker.scan_offset(), 2);
+
+        /* A delimiter that never shows up, from a source that then
+        stops answering. */
+        struct ThenBlock(Cursor<Vec<u8>>);
+        impl Read for ThenBlock {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                match self.0.read(buf)? {
+                    0 => Err(std::io::ErrorKind::WouldBlock.into()),
+                    n => Ok(n),
+                }
+            }
+        }
+        let mut chunker = ByteChunker::new(ThenBlock(Cursor::new(vec![b'x'; 48])), ";;")
+            .unwrap()
+            .with_buffer_size(16)
+            .with_read_timeout(std::time::Duration::from_millis(10))
+            .on_error(ErrorResponse::Continue);
+        assert!(matches!(chunker.next(), Some(Err(RcErr::Timeout))));
+        assert_eq!(chunker.buffered_len(), 48);
+        /* Searching resumes a match's length from the end. */
+        assert_eq!(chunker.scan_offset(), 46);
+    }
+
+    #[test]
+    fn size_hint() {
+        let len = std::fs::metadata(PASSWD_PATH).unwrap().len();
+        let mut chunker = ByteChunker::new(File::open(PASSWD_PATH).unwrap(), PASSWD_PATT)
+            .unwrap()
+            .with_buffer_size(64);
+        assert_eq!(chunker.size_hint(), (0, None));
+
+        chunker = chunker.with_size_hint(len);
+        let mut prev_upper = len as usize + 1;
+        while let Some(res) = chunker.next() {
+            res.unwrap();
+            let (_, upper) = chunker.size_hint();
+            let upper = upper.unwrap();
+            assert!(upper < prev_upper);
+            prev_upper = upper;
+        }
+        assert_eq!(chunker.size_hint(), (0, Some(1)));
+
+        let mut chunker = ByteChunker::new(Cursor::new("a,b"), ",")
+            .unwrap()
+            .with_size_hint(3);
+        chunker.peek();
+        assert_eq!(chunker.size_hint(), (1, Some(3)));
+        chunker.by_ref().for_each(drop);
+        chunker.peek();
+        assert_eq!(chunker.size_hint(), (0, Some(0)));
+    }
+
+    struct Lengths;
+
+    impl SimpleAdapter for Lengths {
+        type Item = usize;
+
+        fn adapt(&mut self, v: Vec<u8>) -> usize {
+            v.len()
+        }
+    }
+
+    #[test]
+    fn simple_adapter() {
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+        let re = Regex::new(PASSWD_PATT).unwrap();
+        let slice_lengths: Vec<usize> = chunk_vec(&re, &byte_vec, MatchDisposition::Drop)
+            .iter()
+            .map(|s| s.len())
+            .collect();
+
+        let mut chunker = ByteChunker::new(File::open(PASSWD_PATH).unwrap(), PASSWD_PATT)
+            .unwrap()
+            .with_simple_adapter(Lengths);
+        let lengths: Vec<usize> = (&mut chunker).map(|res| res.unwrap()).collect();
+        assert_eq!(&lengths[..], &slice_lengths[..lengths.len()]);
+
+        let (chunker, _) = chunker.into_innards();
+        assert!(chunker.into_innards().1.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json() {
+        let text = b"[1, 2]\n[3]\nnot json\n[]\n";
+
+        let chunker = ByteChunker::new(Cursor::new(text), r"\n").unwrap();
+        let results: Vec<Result<Vec<u32>, RcErr>> =
+            chunker.with_adapter(JsonAdapter::default()).collect();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1].as_ref().unwrap(), &[3]);
+        assert!(matches!(results[2], Err(RcErr::Deserialize(_))));
+
+        let chunker = ByteChunker::new(Cursor::new(text), r"\n").unwrap();
+        let results: Vec<Result<Vec<u32>, RcErr>> = chunker
+            .with_adapter(JsonAdapter::new(DecodeFailureMode::Continue))
+            .collect();
+        assert_eq!(results.len(), 4);
+        assert!(results[2].is_err());
+        assert!(results[3].as_ref().unwrap().is_empty());
+    }
+
+    #[test]
+    fn multiple_patterns() {
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+        let re = Regex::new(PASSWD_PATT).unwrap();
+        let patterns = [":", r"\r?\n", "::+"];
+        let alt_re = Regex::new(r"(?::)|(?:\r?\n)|(?:::+)").unwrap();
+        for dispo in [
+            MatchDisposition::Drop,
+            MatchDisposition::Append,
+            MatchDisposition::Prepend,
+        ] {
+            let slice_vec = chunk_vec(&alt_re, &byte_vec, dispo);
+            let chunks: Vec<Vec<u8>> =
+                ByteChunker::with_patterns(File::open(PASSWD_PATH).unwrap(), &patterns)
+                    .unwrap()
+                    .with_match(dispo)
+                    .map(|res| res.unwrap())
+                    .collect();
+            ref_slice_cmp(&chunks, &slice_vec);
+            // The earlier pattern wins, so "::" is two delimiters, and
+            // there are more chunks than with the single pattern.
+            assert!(chunks.len() > chunk_vec(&re, &byte_vec, dispo).len());
+        }
+
+        let chunker = ByteChunker::with_patterns(Cursor::new(b"abc"), &[]).unwrap();
+        let chunks: Vec<Vec<u8>> = chunker.map(|res| res.unwrap()).collect();
+        assert_eq!(chunks, vec![b"abc".to_vec()]);
+    }
+
+    #[test]
+    fn delimiter_kinds() {
+        let text = b"a;b\nc;;d\n;e";
+        let patterns = [";", "\n"];
+        for (dispo, expected) in [
+            (
+                MatchDisposition::Drop,
+                vec![Some(0), Some(1), Some(0), Some(0), Some(1), Some(0), None],
+            ),
+            (
+                MatchDisposition::Prepend,
+                vec![None, Some(0), Some(1), Some(0), Some(0), Some(1), Some(0)],
+            ),
+        ] {
+            let kinds: Vec<Option<usize>> =
+                ByteChunker::with_patterns(Cursor::new(text), &patterns)
+                    .unwrap()
+                    .with_match(dispo)
+                    .with_delimiter_kinds()
+                    .map(|res| res.unwrap().1.index())
+                    .collect();
+            assert_eq!(kinds, expected);
+        }
+
+        let kinds: Vec<DelimiterKind> = ByteChunker::new(Cursor::new(text), "[;\n]")
+            .unwrap()
+            .with_max_yield_size(1)
+            .with_delimiter_kinds()
+            .map(|res| res.unwrap().1)
+            .collect();
+        assert_eq!(kinds[0], DelimiterKind::Pattern(0));
+        assert_eq!(kinds.last(), Some(&DelimiterKind::None));
+    }
+
+    #[test]
+    fn write_all_to() {
+        let text = b"a;b;;c;";
+        for (sep, expected) in [(None, &b"abc"[..]), (Some(&b"--"[..]), b"a--b----c")] {
+            let mut out = Vec::new();
+            let n = ByteChunker::new(Cursor::new(text), ";")
+                .unwrap()
+                .write_all_to(&mut out, sep)
+                .unwrap();
+            assert_eq!(&out, expected);
+            assert_eq!(n, expected.len());
+        }
+
+        let mut out = Vec::new();
+        let n = ByteChunker::new(Cursor::new(b""), ";")
+            .unwrap()
+            .write_all_to(&mut out, Some(b"\n"))
+            .unwrap();
+        assert_eq!(n, 0);
+        assert!(out.is_empty());
+
+        let mut sink = [0u8; 4];
+        let res = ByteChunker::new(Cursor::new(text), ";")
+            .unwrap()
+            .write_all_to(&mut &mut sink[..], Some(b"--"));
+        assert!(matches!(res, Err(RcErr::Write(_))));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_chunks() {
+        use rayon::prelude::*;
+
+        let passwd = std::fs::read(PASSWD_PATH).unwrap();
+        let text = passwd.repeat(3);
+        for patt in [PASSWD_PATT, "[a-z]+", "(?m)^", "x*"] {
+            let re = Regex::new(patt).unwrap();
+            let seq: Vec<&[u8]> = SliceChunker::with_regex(&text, re.clone()).collect();
+            for segment_size in [3, 7, 100, 4096, text.len() + 1] {
+                let par: Vec<&[u8]> =
+                    par::par_chunks_with_segment_size(&text, &re, segment_size).collect();
+                assert_eq!(
+                    par, seq,
+                    "pattern {:?}, segment size {}",
+                    patt, segment_size
+                );
+            }
+        }
+
+        let re = Regex::new(";").unwrap();
+        assert_eq!(crate::par_chunks(b"", &re).count(), 0);
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn mmap() {
+        let re = Regex::new(PASSWD_PATT).unwrap();
+        let passwd = std::fs::read(PASSWD_PATH).unwrap();
+        for dispo in [
+            MatchDisposition::Drop,
+            MatchDisposition::Append,
+            MatchDisposition::Prepend,
+        ] {
+            let slice_chunks: Vec<&[u8]> = SliceChunker::with_regex(&passwd, re.clone())
+                .with_match(dispo)
+                .collect();
+
+            let mut chunker = ByteChunker::from_path_mmap(PASSWD_PATH, PASSWD_PATT)
+                .unwrap()
+                .with_match(dispo);
+            assert_eq!(chunker.as_bytes(), &passwd);
+            let first = chunker.next().unwrap();
+            assert_eq!(&first, slice_chunks[0]);
+            let rest: Vec<&[u8]> = chunker.chunks().collect();
+            assert_eq!(&rest, &slice_chunks[1..]);
+            let rest: Vec<Vec<u8>> = chunker.collect();
+            ref_slice_cmp(&rest, &slice_chunks[1..]);
+            assert_eq!(rest.len(), slice_chunks.len() - 1);
+        }
+
+        let empty = std::env::temp_dir().join("regex_chunker_mmap_empty.txt");
+        File::create(&empty).unwrap();
+        let mut chunker = MmapChunker::new(&empty, PASSWD_PATT).unwrap();
+        assert!(chunker.as_bytes().is_empty());
+        assert_eq!(chunker.chunks().count(), 0);
+        assert_eq!(chunker.next(), None);
+        std::fs::remove_file(&empty).unwrap();
+    }
+
+    #[test]
+    fn line_numbers() {
+        let text = b"one\ntwo\n\nfour\r\nfive";
+        let numbers = |dispo, delimiter_lines| -> Vec<usize> {
+            ByteChunker::new(Cursor::new(text), r"\r?\n")
+                .unwrap()
+                .with_match(dispo)
+                .with_simple_adapter(LineNumberAdapter::new().with_delimiter_lines(delimiter_lines))
+                .map(|res| res.unwrap().0)
+                .collect()
+        };
+        assert_eq!(numbers(MatchDisposition::Drop, 1), vec![1, 2, 3, 4, 5]);
+        assert_eq!(numbers(MatchDisposition::Drop, 0), vec![1, 1, 1, 1, 1]);
+        assert_eq!(numbers(MatchDisposition::Append, 0), vec![1, 2, 3, 4, 5]);
+        assert_eq!(numbers(MatchDisposition::Prepend, 0), vec![1, 1, 2, 3, 4]);
+
+        // Chunks that span several lines.
+        let numbers: Vec<usize> = ByteChunker::new(Cursor::new(text), "o")
+            .unwrap()
+            .with_simple_adapter(LineNumberAdapter::default())
+            .map(|res| res.unwrap().0)
+            .collect();
+        assert_eq!(numbers, vec![1, 1, 2, 4]);
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn gzip() {
+        use flate2::{write::GzEncoder, Compression};
+
+        let passwd = std::fs::read(PASSWD_PATH).unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&passwd).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        let chunks: Vec<Vec<u8>> = ByteChunker::new_gzip(Cursor::new(&gzipped), PASSWD_PATT)
+            .unwrap()
+            .map(|res| res.unwrap())
+            .collect();
+        let slice_chunks: Vec<&[u8]> = SliceChunker::new(&passwd, PASSWD_PATT).unwrap().collect();
+        assert_eq!(chunks, slice_chunks);
+
+        let truncated = &gzipped[..gzipped.len() / 2];
+        let last = ByteChunker::new_gzip(Cursor::new(truncated), PASSWD_PATT)
+            .unwrap()
+            .last();
+        assert!(matches!(last, Some(Err(RcErr::Read(_)))));
+    }
+
+    #[test]
+    fn trim() {
+        let mut trimmer = TrimAdapter::default();
+        for (chunk, trimmed) in [
+            (&b""[..], &b""[..]),
+            (b" \t\r\n", b""),
+            (b"x", b"x"),
+            (b"  x y\r\n", b"x y"),
+            (b"\tx", b"x"),
+            (b"x\n", b"x"),
+        ] {
+            assert_eq!(&trimmer.adapt(chunk.to_vec()), trimmed);
+        }
+
+        let mut trimmer = TrimAdapter::new(b"-");
+        assert_eq!(&trimmer.adapt(b"-- x --".to_vec()), b" x ");
+        let mut trimmer = TrimAdapter::new(b"");
+        assert_eq!(&trimmer.adapt(b" x ".to_vec()), b" x ");
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn base64() {
+        let text = b"b25l\ndHdv\nnot base64\ndGhyZWU=";
+        for (mode, n_chunks) in [
+            (DecodeFailureMode::Fatal, 3),
+            (DecodeFailureMode::Continue, 4),
+        ] {
+            let chunks: Vec<_> = ByteChunker::new(Cursor::new(text), "\n")
+                .unwrap()
+                .with_adapter(Base64Adapter::new(
+                    base64::engine::general_purpose::STANDARD,
+                    mode,
+                ))
+                .collect();
+            assert_eq!(chunks.len(), n_chunks);
+            assert_eq!(chunks[1].as_ref().unwrap(), b"two");
+            assert!(matches!(chunks[2], Err(RcErr::Base64(_))));
+            if n_chunks == 4 {
+                assert_eq!(chunks[3].as_ref().unwrap(), b"three");
+            }
+        }
+    }
+
+    #[test]
+    fn literal_delimiters() {
+        let text = b"one\r\ntwo\r\r\n\nthree\r\n\r\nfour\r";
+        for pattern in ["\r\n", "\n", r"\r", "(?:two)", "\u{e9}"] {
+            for dispo in [
+                MatchDisposition::Drop,
+                MatchDisposition::Append,
+                MatchDisposition::Prepend,
+            ] {
+                for size in [1, 2, 5, 1024] {
+                    let chunks = |chunker: ByteChunker<Cursor<&[u8]>>| -> Vec<Vec<u8>> {
+                        chunker
+                            .with_match(dispo)
+                            .with_buffer_size(size)
+                            .collect::<Result<_, _>>()
+                            .unwrap()
+                    };
+                    let literal =
+                        chunks(ByteChunker::new(Cursor::new(&text[..]), pattern).unwrap());
+                    let regex = chunks(ByteChunker::with_regex(
+                        Cursor::new(&text[..]),
+                        Regex::new(pattern).unwrap(),
+                    ));
+                    assert_eq!(literal, regex, "{pattern:?} {dispo:?} {size}");
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "aho-corasick")]
+    #[test]
+    fn literal_sets() {
+        let text = b"a<br>b<p><br>c<hr>d<brr>";
+        let literals: &[&[u8]] = &[b"<br>", b"<hr>", b"<p>", b"<br", b""];
+        for size in [1, 3, 1024] {
+            for dispo in [
+                MatchDisposition::Drop,
+                MatchDisposition::Append,
+                MatchDisposition::Prepend,
+            ] {
+                let chunker = ByteChunker::with_literals(Cursor::new(&text[..]), literals)
+                    .unwrap()
+                    .with_buffer_size(size)
+                    .with_match(dispo);
+                let regex = Regex::new(chunker.fence().as_str()).unwrap();
+                let kinds: Vec<(Vec<u8>, DelimiterKind)> = chunker
+                    .with_delimiter_kinds()
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+                let chunks: Vec<Vec<u8>> = kinds.iter().map(|(c, _)| c.clone()).collect();
+                let expected: Vec<Vec<u8>> = ByteChunker::with_regex(Cursor::new(&text[..]), regex)
+                    .with_buffer_size(size)
+                    .with_match(dispo)
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+                assert_eq!(chunks, expected, "{dispo:?} {size}");
+
+                if let MatchDisposition::Drop = dispo {
+                    let kinds: Vec<DelimiterKind> = kinds.into_iter().map(|(_, k)| k).collect();
+                    assert_eq!(
+                        kinds,
+                        vec![
+                            DelimiterKind::Pattern(0),
+                            DelimiterKind::Pattern(2),
+                            DelimiterKind::Pattern(0),
+                            DelimiterKind::Pattern(1),
+                            DelimiterKind::Pattern(3),
+                            DelimiterKind::None,
+                        ]
+                    );
+                }
+            }
+        }
+
+        let chunks: Vec<Vec<u8>> = ByteChunker::with_literals(Cursor::new(&text[..]), &[])
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(chunks, vec![text.to_vec()]);
+    }
+
+    #[test]
+    fn regex_flags() {
+        let chunks = |chunker: ByteChunker<Cursor<&[u8]>>| -> Vec<Vec<u8>> {
+            chunker.collect::<Result<_, _>>().unwrap()
+        };
+        let text: &[u8] = b"aXbxc\nEND\nd";
+
+        let chunker = ByteChunker::new(Cursor::new(text), "x").unwrap();
+        assert_eq!(
+            chunks(chunker.case_insensitive(true).unwrap()),
+            vec![&b"a"[..], b"b", b"c\nEND\nd"]
+        );
+
+        /* Flags accumulate, and can be turned off again. */
+        let chunker = ByteChunker::new(Cursor::new(text), "C.")
+            .unwrap()
+            .case_insensitive(true)
+            .unwrap()
+            .dot_matches_newline(true)
+            .unwrap();
+        assert_eq!(chunks(chunker), vec![&b"aXbx"[..], b"END\nd"]);
+        let chunker = ByteChunker::new(Cursor::new(text), "C.")
+            .unwrap()
+            .case_insensitive(true)
+            .unwrap()
+            .dot_matches_newline(true)
+            .unwrap()
+            .case_insensitive(false)
+            .unwrap();
+        assert_eq!(chunks(chunker), vec![text]);
+
+        let chunker = ByteChunker::new(Cursor::new(text), "^END$")
+            .unwrap()
+            .multi_line(true)
+            .unwrap();
+        assert_eq!(chunks(chunker), vec![&b"aXbxc\n"[..], b"\nd"]);
+
+        /* They apply to every pattern, and to patterns set later. */
+        let chunker = ByteChunker::with_patterns(Cursor::new(text), &["x", "end"])
+            .unwrap()
+            .case_insensitive(true)
+            .unwrap();
+        assert_eq!(chunks(chunker), vec![&b"a"[..], b"b", b"c\n", b"\nd"]);
+        let mut chunker = ByteChunker::new(Cursor::new(text), "q")
+            .unwrap()
+            .case_insensitive(true)
+            .unwrap();
+        chunker.set_delimiter("b").unwrap();
+        assert_eq!(chunks(chunker), vec![&b"aX"[..], b"xc\nEND\nd"]);
+
+        /* Matching case-insensitively can change the longest match. */
+        let text = "x\u{212a}y".as_bytes();
+        let chunker = ByteChunker::new(Cursor::new(text), "k")
+            .unwrap()
+            .with_buffer_size(1)
+            .case_insensitive(true)
+            .unwrap();
+        assert_eq!(chunks(chunker), vec![&b"x"[..], b"y"]);
+    }
+
+    #[cfg(feature = "aho-corasick")]
+    #[test]
+    fn literal_set_flags() {
+        let text: &[u8] = b"one<BR>two<br>three";
+        let chunks: Vec<Vec<u8>> = ByteChunker::with_literals(Cursor::new(text), &[b"<br>"])
+            .unwrap()
+            .case_insensitive(true)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(chunks, vec![&b"one"[..], b"two", b"three"]);
+    }
+
+    #[test]
+    fn clone() {
+        let text = b"one two three four";
+        let mut chunker = ByteChunker::new(Cursor::new(text), " ")
+            .unwrap()
+            .with_buffer_size(6);
+        assert_eq!(chunker.next().unwrap().unwrap(), b"one");
+        let twin = chunker.clone();
+
+        let rest: Vec<Vec<u8>> = chunker.collect::<Result<_, _>>().unwrap();
+        let twin_rest: Vec<Vec<u8>> = twin.collect::<Result<_, _>>().unwrap();
+        assert_eq!(rest, vec![&b"two"[..], b"three", b"four"]);
+        assert_eq!(twin_rest, rest);
+    }
+
+    #[test]
+    fn seek() {
+        let text = b"one two three";
+        let mut chunker = ByteChunker::new(Cursor::new(text), " ")
+            .unwrap()
+            .with_buffer_size(5)
+            .with_match(MatchDisposition::Prepend);
+        assert_eq!(chunker.next().unwrap().unwrap(), b"one");
+        assert_eq!(chunker.next().unwrap().unwrap(), b" two");
+
+        chunker.rewind().unwrap();
+        assert_eq!(chunker.chunk_count(), 0);
+        assert_eq!(chunker.next().unwrap().unwrap(), b"one");
+
+        /* Into the middle of a chunk. */
+        chunker.seek_to(5).unwrap();
+        let chunks: Vec<ChunkMeta> = chunker.with_offsets().collect::<Result<_, _>>().unwrap();
+        let spans: Vec<(&[u8], u64, u64)> = chunks
+            .iter()
+            .map(|meta| (meta.bytes.as_slice(), meta.start, meta.end))
+            .collect();
+        assert_eq!(spans, vec![(&b"wo"[..], 5, 7), (b" three", 7, 13)]);
+    }
+
+    #[test]
+    fn channel() {
+        let text = "a,b,,c".repeat(100);
+        let expected: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text.clone()), ",")
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        let rx = ByteChunker::new(Cursor::new(text.clone()), ",")
+            .unwrap()
+            .into_channel();
+        let chunks: Vec<Vec<u8>> = rx.iter().collect::<Result<_, _>>().unwrap();
+        assert_eq!(chunks, expected);
+
+        for bound in [0, 1, 16] {
+            let rx = ByteChunker::new(Cursor::new(text.clone()), ",")
+                .unwrap()
+                .into_bounded_channel(bound);
+            let chunks: Vec<Vec<u8>> = rx.iter().collect::<Result<_, _>>().unwrap();
+            assert_eq!(chunks, expected);
+        }
+
+        /* Dropping the receiver early doesn't hang anything. */
+        let rx = ByteChunker::new(Cursor::new(text), ",")
+            .unwrap()
+            .into_bounded_channel(1);
+        assert_eq!(rx.recv().unwrap().unwrap(), b"a");
+        drop(rx);
+
+        /* Errors come through the channel too. */
+        let rx = ByteChunker::new(Cursor::new("abcdef"), ",")
+            .unwrap()
+            .with_max_chunk_size(2)
+            .into_channel();
+        assert!(rx.recv().unwrap().is_err());
+        assert!(rx.recv().is_err());
+    }
+
+    #[test]
+    fn leading_empty() {
+        let chunks = |text: &[u8], dispo: MatchDisposition, skip: bool| -> Vec<Vec<u8>> {
+            let chunker = ByteChunker::new(Cursor::new(text.to_vec()), ",")
+                .unwrap()
+                .with_match(dispo)
+                .with_leading_empty(false);
+            let chunker = if skip { chunker.skip_empty() } else { chunker };
+            chunker.collect::<Result<_, _>>().unwrap()
+        };
+
+        assert_eq!(
+            chunks(b",,a,", MatchDisposition::Drop, false),
+            vec![&b""[..], b"a"]
+        );
+        assert_eq!(
+            chunks(b",,a,", MatchDisposition::Prepend, false),
+            vec![&b","[..], b",a", b","]
+        );
+        assert_eq!(
+            chunks(b",,a,", MatchDisposition::Append, false),
+            vec![&b","[..], b",", b"a,"]
+        );
+        assert_eq!(chunks(b",,a,", MatchDisposition::Drop, true), vec![b"a"]);
+        assert_eq!(
+            chunks(b"a,,b", MatchDisposition::Drop, false),
+            vec![&b"a"[..], b"", b"b"]
+        );
+        assert!(chunks(b",", MatchDisposition::Drop, false).is_empty());
+
+        /* The borrowing path too, and it starts over after a reset. */
+        let mut chunker = ByteChunker::new(Cursor::new(b",a"), ",")
+            .unwrap()
+            .with_leading_empty(false);
+        assert_eq!(&*chunker.next_borrowed().unwrap().unwrap(), b"a");
+        chunker.reset(Cursor::new(b",b"));
+        assert_eq!(chunker.next().unwrap().unwrap(), b"b");
+    }
+
+    #[test]
+    fn shrink_threshold() {
+        let mut text = b"a;".to_vec();
+        text.extend(std::iter::repeat_n(b'b', 100_000));
+        text.extend_from_slice(b";c;d");
+        for threshold in [0, 1, 4096] {
+            let mut out = Vec::new();
+            ByteChunker::new(Cursor::new(&text), ";")
+                .unwrap()
+                .with_buffer_size(7)
+                .with_shrink_threshold(threshold)
+                .write_all_to(&mut out, Some(b";"))
+                .unwrap();
+            assert_eq!(out, text);
+
+            let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(&text), ";")
+                .unwrap()
+                .with_shrink_threshold(threshold)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(rejoin(&chunks, b";"), text);
+        }
+    }
+
+    #[test]
+    fn search_capacity() {
+        let text = b"alpha,beta,,gamma,delta-epsilon-zeta,eta".repeat(20);
+        let expected: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(&text), ",")
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        for capacity in [0, 1, 16, 4096] {
+            for growth in [
+                GrowthPolicy::Double,
+                GrowthPolicy::Fixed(0),
+                GrowthPolicy::Fixed(3),
+                GrowthPolicy::Fixed(1024),
+            ] {
+                for size in [1, 5, 1024] {
+                    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(&text), ",")
+                        .unwrap()
+                        .with_buffer_size(size)
+                        .with_search_capacity(capacity)
+                        .with_growth_policy(growth)
+                        .with_shrink_threshold(2048)
+                        .collect::<Result<_, _>>()
+                        .unwrap();
+                    assert_eq!(chunks, expected, "{capacity} {growth:?} {size}");
+                }
+            }
+        }
+
+        let mut buff = vec![0u8; 10];
+        buff.shrink_to_fit();
+        GrowthPolicy::Fixed(8).reserve(&mut buff, 11);
+        assert_eq!(buff.capacity(), 26);
+        GrowthPolicy::Fixed(8).reserve(&mut buff, 16);
+        assert_eq!(buff.capacity(), 26);
+    }
+
+    #[test]
+    fn get_ref() {
+        let mut chunker = ByteChunker::new(Cursor::new(b"one two"), " ")
+            .unwrap()
+            .with_buffer_size(4);
+        assert_eq!(chunker.get_ref().position(), 0);
+        assert_eq!(chunker.next().unwrap().unwrap(), b"one");
+        assert_eq!(chunker.get_ref().position(), 4);
+
+        /* Rewinding the source under the chunker makes it reread data. */
+        chunker.get_mut().set_position(0);
+        assert_eq!(chunker.next().unwrap().unwrap(), b"one");
+    }
+
+    #[cfg(feature = "crc32fast")]
+    #[test]
+    fn checksum() {
+        let text = b"alpha;beta;;gamma";
+        for dispo in [MatchDisposition::Drop, MatchDisposition::Append] {
+            let mut chunker = ByteChunker::new(Cursor::new(text), ";")
+                .unwrap()
+                .with_match(dispo)
+                .with_simple_adapter(ChecksumAdapter::new());
+            let mut all = Vec::new();
+            for res in chunker.by_ref() {
+                let (sum, chunk) = res.unwrap();
+                assert_eq!(sum, crc32fast::hash(&chunk));
+                all.extend_from_slice(&chunk);
+            }
+            assert_eq!(chunker.get_adapter().checksum(), crc32fast::hash(&all));
+            assert_eq!(chunker.get_adapter().chunks(), 4);
+        }
+        assert_eq!(ChecksumAdapter::new().checksum(), crc32fast::hash(b""));
+    }
+
+    #[test]
+    fn inspect() {
+        let mut seen = Vec::new();
+        let mut errors = 0;
+        let chunks: Vec<Result<Vec<u8>, RcErr>> = ByteChunker::new(Cursor::new(b"a,bbbbb,c"), ",")
+            .unwrap()
+            .with_max_chunk_size(3)
+            .on_error(ErrorResponse::Continue)
+            .with_adapter(
+                InspectAdapter::new(|chunk: &[u8]| seen.push(chunk.to_vec()))
+                    .with_error_inspector(|_: &RcErr| errors += 1),
+            )
+            .collect();
+
+        assert_eq!(errors, chunks.iter().filter(|res| res.is_err()).count());
+        assert!(errors > 0);
+        let ok: Vec<Vec<u8>> = chunks.into_iter().filter_map(|res| res.ok()).collect();
+        assert_eq!(seen, ok);
+    }
+
+    #[test]
+    fn adapter_action() {
+        assert_eq!(AdapterAction::from(Some(3)), AdapterAction::Emit(3));
+        assert_eq!(AdapterAction::<i32>::from(None), AdapterAction::Done);
+
+        /* Driven by hand, a skipping adapter tells a chunk it skips apart
+        from the end, for every adapter that skips. */
+        let mut adapter = FilterAdapter::new(|chunk: &[u8]| !chunk.is_empty());
+        assert_eq!(adapter.act(Some(Ok(Vec::new()))), AdapterAction::Skip);
+        assert_eq!(
+            adapter.act(Some(Ok(b"x".to_vec()))),
+            AdapterAction::Emit(Ok(b"x".to_vec()))
+        );
+        assert_eq!(adapter.act(None), AdapterAction::Done);
+
+        let mut adapter = GroupAdapter::new(|chunk: &[u8]| chunk.is_empty()).with_separator(b" ");
+        assert_eq!(adapter.act(Some(Ok(b"a".to_vec()))), AdapterAction::Skip);
+        assert_eq!(adapter.act(Some(Ok(b"b".to_vec()))), AdapterAction::Skip);
+        assert_eq!(
+            adapter.act(Some(Ok(Vec::new()))),
+            AdapterAction::Emit(Ok(b"a b".to_vec()))
+        );
+
+        let mut adapter = RunLengthAdapter::new();
+        assert_eq!(adapter.act(Some(Ok(b"a".to_vec()))), AdapterAction::Skip);
+        assert_eq!(adapter.act(Some(Ok(b"a".to_vec()))), AdapterAction::Skip);
+        assert_eq!(
+            adapter.act(Some(Ok(b"b".to_vec()))),
+            AdapterAction::Emit(Ok((2, b"a".to_vec())))
+        );
+
+        let mut adapter = FilterAdapter::new(|chunk: &[u8]| chunk != b"x")
+            .and_then(FieldAdapter::new(",").unwrap());
+        assert_eq!(adapter.act(Some(Ok(b"x".to_vec()))), AdapterAction::Skip);
+        assert_eq!(
+            adapter.act(Some(Ok(b"x,y".to_vec()))),
+            AdapterAction::Emit(Ok(vec![b"x".to_vec(), b"y".to_vec()]))
+        );
+        assert_eq!(adapter.act(None), AdapterAction::Done);
+
+        /* Through adapt(), a skipped chunk is `None`. */
+        let mut adapter = FilterAdapter::new(|chunk: &[u8]| !chunk.is_empty());
+        assert_eq!(adapter.adapt(Some(Ok(Vec::new()))), None);
+        assert_eq!(adapter.adapt(Some(Ok(b"x".to_vec()))), Some(Ok(b"x".to_vec())));
+        assert_eq!(Option::<i32>::from(AdapterAction::Skip), None);
+    }
+
+    #[test]
+    fn filter() {
+        let text = b"1,22,333,4444,55555";
+        let mut chunker = ByteChunker::new(Cursor::new(text), ",")
+            .unwrap()
+            .with_adapter(FilterAdapter::new(|chunk: &[u8]| chunk.len() % 2 == 1));
+        assert_eq!(chunker.next().unwrap().unwrap(), b"1");
+        assert_eq!(chunker.next().unwrap().unwrap(), b"333");
+        assert_eq!(chunker.next().unwrap().unwrap(), b"55555");
+        assert!(chunker.next().is_none());
+
+        /* Nothing passes. */
+        let mut chunker = ByteChunker::new(Cursor::new(text), ",")
+            .unwrap()
+            .with_adapter(FilterAdapter::new(|_: &[u8]| false));
+        assert!(chunker.next().is_none());
+    }
+
+    #[test]
+    fn grep() {
+        let text = b"apple\nBanana\ncherry\nbanana split\n";
+        let grep = |adapter: GrepAdapter| {
+            let mut chunker = ByteChunker::new(Cursor::new(text), r"\n")
+                .unwrap()
+                .with_adapter(adapter);
+            let lines: Vec<Vec<u8>> = (&mut chunker).collect::<Result<_, _>>().unwrap();
+            (lines, chunker.get_adapter().count())
+        };
+
+        let (lines, count) = grep(GrepAdapter::new("(?i)banana").unwrap());
+        ref_slice_cmp(&lines, &["Banana", "banana split"]);
+        assert_eq!(count, 2);
+
+        let (lines, count) = grep(GrepAdapter::new("an").unwrap().with_invert(true));
+        ref_slice_cmp(&lines, &["apple", "cherry"]);
+        assert_eq!(count, 2);
+
+        let (lines, count) = grep(GrepAdapter::new("a").unwrap().with_count_only(true));
+        assert!(lines.is_empty());
+        assert_eq!(count, 3);
+
+        let (lines, count) = grep(GrepAdapter::new("x").unwrap());
+        assert!(lines.is_empty());
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn run_length() {
+        let runs = |text: &'static [u8]| -> Vec<(usize, Vec<u8>)> {
+            ByteChunker::new(Cursor::new(text), ",")
+                .unwrap()
+                .with_adapter(RunLengthAdapter::new())
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(
+            runs(b"x,,,,y,y"),
+            vec![(1, b"x".to_vec()), (3, b"".to_vec()), (2, b"y".to_vec())]
+        );
+        assert_eq!(runs(b"z"), vec![(1, b"z".to_vec())]);
+        assert_eq!(
+            runs(b"a,b,a"),
+            vec![(1, b"a".to_vec()), (1, b"b".to_vec()), (1, b"a".to_vec())]
+        );
+        assert!(ByteChunker::new(std::io::empty(), ",")
+            .unwrap()
+            .with_adapter(RunLengthAdapter::new())
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn terminal_flag() {
+        let flags = |text: &'static [u8]| -> Vec<(Vec<u8>, bool)> {
+            ByteChunker::new(Cursor::new(text), ",")
+                .unwrap()
+                .with_terminal_flag()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(flags(b"only"), vec![(b"only".to_vec(), true)]);
+        assert_eq!(
+            flags(b"a,,b"),
+            vec![
+                (b"a".to_vec(), false),
+                (b"".to_vec(), false),
+                (b"b".to_vec(), true)
+            ]
+        );
+        assert!(flags(b"").is_empty());
+
+        /* A chunk followed by an error isn't the last. */
+        let mut chunker = ByteChunker::new(Cursor::new(b"a,bbbbbb"), ",")
+            .unwrap()
+            .with_max_chunk_size(4)
+            .with_terminal_flag();
+        assert_eq!(chunker.next().unwrap().unwrap(), (b"a".to_vec(), false));
+        assert!(chunker.next().unwrap().is_err());
+        assert!(chunker.next().is_none());
+
+        /* The chunk read ahead is still there after unwrapping. */
+        let mut chunker = ByteChunker::new(Cursor::new(b"a,b"), ",")
+            .unwrap()
+            .with_terminal_flag();
+        chunker.next();
+        assert_eq!(chunker.into_inner().next().unwrap().unwrap(), b"b");
+    }
+
+    /* Pairs each chunk with the delimiter the chunker says ended it. */
+    struct Delims;
+
+    impl Adapter for Delims {
+        type Item = Result<(Vec<u8>, Option<Vec<u8>>), RcErr>;
+
+        fn adapt(&mut self, v: Option<Result<Vec<u8>, RcErr>>) -> Option<Self::Item> {
+            v.map(|res| res.map(|v| (v, None)))
+        }
+
+        fn wants_delimiters(&self) -> bool {
+            true
+        }
+
+        fn adapt_with_delim(
+            &mut self,
+            v: Option<Result<Vec<u8>, RcErr>>,
+            delimiter: Option<&[u8]>,
+        ) -> AdapterAction<Self::Item> {
+            v.map(|res| res.map(|v| (v, delimiter.map(<[u8]>::to_vec))))
+                .into()
+        }
+    }
+
+    #[test]
+    fn adapt_with_delim() {
+        let chunks: Vec<(Vec<u8>, Option<Vec<u8>>)> =
+            ByteChunker::with_patterns(Cursor::new(b"a;b\nc"), &[";", "\n"])
+                .unwrap()
+                .with_adapter(Delims)
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                (b"a".to_vec(), Some(b";".to_vec())),
+                (b"b".to_vec(), Some(b"\n".to_vec())),
+                (b"c".to_vec(), None)
+            ]
+        );
+
+        /* Adapters that don't care still work the same, without the
+        chunker keeping track of delimiters for them. */
+        let mut chunker = ByteChunker::new(Cursor::new(b"a;b"), ";")
+            .unwrap()
+            .with_adapter(StringAdapter::default());
+        let strings: Vec<String> = (&mut chunker).collect::<Result<_, _>>().unwrap();
+        assert_eq!(strings, vec!["a", "b"]);
+        assert!(!chunker.byte_chunker().tracks_delimiters());
+
+        /* Tracking starts with the first chunk, and is turned off again
+        when the chunker is unwrapped. */
+        let mut chunker = ByteChunker::new(Cursor::new(b"a;b"), ";")
+            .unwrap()
+            .with_adapter(Delims);
+        assert!(!chunker.byte_chunker().tracks_delimiters());
+        assert_eq!(chunker.next().unwrap().unwrap(), (b"a".to_vec(), Some(b";".to_vec())));
+        assert!(chunker.byte_chunker().tracks_delimiters());
+        let (chunker, _) = chunker.into_innards();
+        assert!(!chunker.tracks_delimiters());
+        assert!(Simple(TrimAdapter::default())
+            .and_then(Delims)
+            .wants_delimiters());
+
+        /* Anything can be paired up with a chunker, adapter or not. */
+        let chunker = ByteChunker::new(Cursor::new(b"a;b"), ";").unwrap();
+        let (_, unit) = CustomChunker::from((chunker, ())).into_innards();
+        assert_eq!(unit, ());
+    }
+
+    #[test]
+    fn boxed_adapter() {
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+        let re = Regex::new(PASSWD_PATT).unwrap();
+        let slice_vec = chunk_vec(&re, &byte_vec, MatchDisposition::Drop);
+
+        let chunks: Vec<Box<[u8]>> =
+            ByteChunker::new(File::open(PASSWD_PATH).unwrap(), PASSWD_PATT)
+                .unwrap()
+                .with_simple_adapter(BoxedAdapter)
+                .collect::<Result<_, _>>()
+                .unwrap();
+        ref_slice_cmp(&chunks, &slice_vec);
+    }
+
+    #[test]
+    fn and_then_adapter() {
+        /* The first adapter holds a group until the end of the source,
+        and the second holds a run, so both have to be flushed. */
+        let text = b"a\nb\n\na\nb\n\nc";
+        let mut chunker = ByteChunker::new(Cursor::new(text), r"\n")
+            .unwrap()
+            .with_adapter(
+                GroupAdapter::new(|line: &[u8]| line.is_empty())
+                    .with_separator(b" ")
+                    .and_then(RunLengthAdapter::new()),
+            );
+        assert_eq!(chunker.next().unwrap().unwrap(), (2, b"a b".to_vec()));
+        assert_eq!(chunker.next().unwrap().unwrap(), (1, b"c".to_vec()));
+        assert!(chunker.next().is_none());
+        assert!(chunker.next().is_none());
+
+        /* Both adapters see each chunk's delimiter. */
+        let chunks: Vec<(Vec<u8>, Option<Vec<u8>>)> =
+            ByteChunker::with_patterns(Cursor::new(b" a ;b\nc "), &[";", "\n"])
+                .unwrap()
+                .with_adapter(Simple(TrimAdapter::default()).and_then(Delims))
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                (b"a".to_vec(), Some(b";".to_vec())),
+                (b"b".to_vec(), Some(b"\n".to_vec())),
+                (b"c".to_vec(), None)
+            ]
+        );
+
+        /* Errors go through the first adapter to the second. */
+        let mut chunker = ByteChunker::new(ErrorReader, ";")
+            .unwrap()
+            .with_adapter(FilterAdapter::new(|_: &[u8]| true).and_then(StringAdapter::default()));
+        assert!(chunker.next().unwrap().is_err());
+        assert!(chunker.next().is_none());
+
+        let adapter = chunker.get_adapter();
+        assert_eq!(adapter.second().replacement_count(), 0);
+    }
+
+    #[test]
+    fn rejoin_round_trip() {
+        let texts: [&[u8]; 4] = [b"a,b,,c", b",a,b,", b"", b"abc"];
+        for text in texts {
+            for dispo in [MatchDisposition::Append, MatchDisposition::Prepend] {
+                let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ",")
+                    .unwrap()
+                    .with_match(dispo)
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+                assert_eq!(rejoin(&chunks, b""), text, "{dispo:?}");
+            }
+
+            let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), ",")
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            let expected = text.strip_suffix(b",").unwrap_or(text);
+            assert_eq!(rejoin(&chunks, b","), expected);
+        }
+
+        assert_eq!(rejoin(Vec::<Vec<u8>>::new(), b","), b"");
+        assert_eq!(rejoin([b"x"], b","), b"x");
+    }
+
+    #[test]
+    fn chunk_reader() {
+        let text = b"one,,two,three,";
+        for size in [1, 2, 5, 100] {
+            let chunker = ByteChunker::new(Cursor::new(text), ",")
+                .unwrap()
+                .with_match(MatchDisposition::Append);
+            let mut reader = ChunkReader::new(chunker);
+            let mut out = Vec::new();
+            let mut buf = vec![0u8; size];
+            loop {
+                let n = reader.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                out.extend_from_slice(&buf[..n]);
+            }
+            assert_eq!(out, text);
+            assert_eq!(reader.read(&mut buf).unwrap(), 0);
+        }
+
+        let chunks = vec![
+            Ok(b"ab".to_vec()),
+            Ok(Vec::new()),
+            Err(RcErr::ChunkTooLarge { size: 9 }),
+            Ok(b"c".to_vec()),
+        ];
+        let mut reader = ChunkReader::new(chunks.into_iter());
+        let mut buf = [0u8; 8];
+        assert_eq!(reader.read(&mut buf).unwrap(), 2);
+        let err = reader.read(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(reader.read(&mut buf).unwrap(), 1);
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn bom() {
+        let strip = |text: &[u8]| -> Vec<Vec<u8>> {
+            ByteChunker::new(Cursor::new(text.to_vec()), ",")
+                .unwrap()
+                .with_simple_adapter(BomAdapter::new())
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(
+            strip(b"\xef\xbb\xbfa,b"),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+        assert_eq!(strip(b"\xff\xfea\x00,"), vec![b"a\x00".to_vec()]);
+        assert_eq!(strip(b"\xfe\xff\x00a,"), vec![b"\x00a".to_vec()]);
+        assert_eq!(strip(b"\xff\xfe\x00\x00a"), vec![b"a".to_vec()]);
+        assert_eq!(strip(b"\x00\x00\xfe\xffa"), vec![b"a".to_vec()]);
+        assert_eq!(
+            strip(b"a,\xef\xbb\xbfb"),
+            vec![b"a".to_vec(), b"\xef\xbb\xbfb".to_vec()]
+        );
+        assert_eq!(strip(b"\xef\xbb"), vec![b"\xef\xbb".to_vec()]);
+    }
+
+    #[test]
+    fn fields() {
+        let text = b"a=1; b=2\n\nc\n";
+        let rows: Vec<Vec<Vec<u8>>> = ByteChunker::new(Cursor::new(text), "\n")
+            .unwrap()
+            .with_adapter(FieldAdapter::new(r";\s*|=").unwrap())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                vec![b"a".to_vec(), b"1".to_vec(), b"b".to_vec(), b"2".to_vec()],
+                vec![vec![]],
+                vec![b"c".to_vec()],
+            ]
+        );
+        assert!(FieldAdapter::new("(").is_err());
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test]
+    fn encoding() {
+        /* "日本" in Shift_JIS, split partway through the second character. */
+        let text = b"\x93\xfa\x96\x7b\n\x93\xfa\x96";
+        let decode = |text: &[u8]| -> Vec<String> {
+            ByteChunker::new(Cursor::new(text.to_vec()), "\n")
+                .unwrap()
+                .with_max_yield_size(3)
+                .with_adapter(EncodingAdapter::new(encoding_rs::SHIFT_JIS))
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(decode(&text[..5]), vec!["日", "本"]);
+        assert_eq!(decode(text), vec!["日", "本", "日", "\u{fffd}"]);
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn hex() {
+        let text = b"00ff\n0 1\n\n123\nxy\nA b\tC d";
+        let decode = |adapter: HexAdapter| -> Vec<Option<Vec<u8>>> {
+            ByteChunker::new(Cursor::new(text), "\n")
+                .unwrap()
+                .with_adapter(adapter)
+                .map(|res| res.ok())
+                .collect()
+        };
+
+        assert_eq!(
+            decode(HexAdapter::default()),
+            vec![Some(vec![0x00, 0xff]), None]
+        );
+        assert_eq!(
+            decode(HexAdapter::new(DecodeFailureMode::Continue)),
+            vec![Some(vec![0x00, 0xff]), None, Some(vec![]), None, None, None]
+        );
+        assert_eq!(
+            decode(HexAdapter::new(DecodeFailureMode::Continue).skip_whitespace()),
+            vec![
+                Some(vec![0x00, 0xff]),
+                Some(vec![0x01]),
+                Some(vec![]),
+                None,
+                None,
+                Some(vec![0xab, 0xcd])
+            ]
+        );
+    }
+
+    #[test]
+    fn counts() {
+        let passwd = std::fs::read(PASSWD_PATH).unwrap();
+        let slice_chunks: Vec<&[u8]> = SliceChunker::new(&passwd, PASSWD_PATT).unwrap().collect();
+        let n_bytes: usize = slice_chunks.iter().map(|c| c.len()).sum();
+
+        let mut chunker = ByteChunker::new(File::open(PASSWD_PATH).unwrap(), PASSWD_PATT).unwrap();
+        assert_eq!((chunker.chunk_count(), chunker.byte_count()), (0, 0));
+        chunker.peek();
+        assert_eq!(chunker.chunk_count(), 1);
+        chunker.next();
+        assert_eq!(chunker.chunk_count(), 1);
+        while let Some(chunk) = chunker.next_borrowed() {
+            chunk.unwrap();
+        }
+        assert_eq!(chunker.chunk_count(), slice_chunks.len() as u64);
+        assert_eq!(chunker.byte_count(), n_bytes as u64);
+
+        chunker.reset(File::open(PASSWD_PATH).unwrap());
+        assert_eq!((chunker.chunk_count(), chunker.byte_count()), (0, 0));
+        let mut chunker = chunker.with_max_yield_size(3);
+        for chunk in &mut chunker {
+            chunk.unwrap();
+        }
+        assert!(chunker.chunk_count() > slice_chunks.len() as u64);
+        assert_eq!(chunker.byte_count(), n_bytes as u64);
+    }
+
+    #[test]
+    fn error_comparison() {
+        let text = b"ok\n\xff\n\xfe\n";
+        let errors: Vec<RcErr> = ByteChunker::new(Cursor::new(text), "\n")
+            .unwrap()
+            .with_adapter(StringAdapter::new(Utf8FailureMode::Continue))
+            .filter_map(Result::err)
+            .collect();
+        assert_eq!(errors.len(), 2);
+        assert_ne!(errors[0], errors[1]);
+        assert_eq!(errors[0], errors[0].clone());
+        assert!(errors.iter().all(|e| e.kind() == RcErrKind::Utf8));
+
+        let read_err = |kind, msg| RcErr::from(std::io::Error::new(kind, msg));
+        let err = read_err(std::io::ErrorKind::Other, "oops");
+        assert_eq!(err, err.clone());
+        assert_eq!(err, read_err(std::io::ErrorKind::Other, "oops"));
+        assert_ne!(err, read_err(std::io::ErrorKind::Other, "uh oh"));
+        assert_ne!(err, read_err(std::io::ErrorKind::InvalidData, "oops"));
+        assert_eq!(err.kind(), RcErrKind::Read);
+
+        let too_large = RcErr::ChunkTooLarge { size: 3 };
+        assert_ne!(err, too_large);
+        assert_eq!(too_large.kind(), RcErrKind::ChunkTooLarge);
+    }
+
+    #[test]
+    fn into_io_error() {
+        use std::io::ErrorKind;
+
+        let err = RcErr::from(std::io::Error::new(ErrorKind::TimedOut, "slow"));
+        let cloned = err.clone();
+        let io_err = std::io::Error::from(err);
+        assert_eq!(io_err.kind(), ErrorKind::TimedOut);
+        assert_eq!(io_err.to_string(), "slow");
+        let io_err = std::io::Error::from(cloned);
+        assert_eq!(io_err.kind(), ErrorKind::TimedOut);
+
+        let err = ByteChunker::new(std::io::empty(), "[").unwrap_err();
+        let io_err = std::io::Error::from(err);
+        assert_eq!(io_err.kind(), ErrorKind::InvalidData);
+        let inner = io_err.into_inner().unwrap().downcast::<RcErr>().unwrap();
+        assert_eq!(inner.kind(), RcErrKind::Regex);
+
+        let res: Result<Vec<String>, RcErr> = ByteChunker::new(Cursor::new(b"\xff"), ",")
+            .unwrap()
+            .with_adapter(StringAdapter::default())
+            .collect();
+        let io_err = std::io::Error::from(res.unwrap_err());
+        assert_eq!(io_err.kind(), ErrorKind::InvalidData);
+    }
+
+    // Returns `WouldBlock` before every successful read.
+    struct NotReady<R> {
+        inner: R,
+        ready: bool,
+    }
+
+    impl<R: Read> Read for NotReady<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.ready = !self.ready;
+            if self.ready {
+                self.inner.read(buf)
+            } else {
+                Err(std::io::ErrorKind::WouldBlock.into())
+            }
+        }
+    }
+
+    #[test]
+    fn block_policy() {
+        let passwd = std::fs::read(PASSWD_PATH).unwrap();
+        let slice_chunks: Vec<&[u8]> = SliceChunker::new(&passwd, PASSWD_PATT).unwrap().collect();
+        for policy in [
+            BlockPolicy::Spin,
+            BlockPolicy::Yield,
+            BlockPolicy::Sleep(std::time::Duration::from_micros(1)),
+        ] {
+            let source = NotReady {
+                inner: Cursor::new(&passwd),
+                ready: false,
+            };
+            let chunks: Vec<Vec<u8>> = ByteChunker::new(source, PASSWD_PATT)
+                .unwrap()
+                .with_buffer_size(64)
+                .with_block_policy(policy)
+                .map(|res| res.unwrap())
+                .collect();
+            assert_eq!(chunks, slice_chunks);
+        }
+    }
+
+    #[test]
+    fn chunk_limit() {
+        let text = b"a,b,c,d,e";
+        let mut chunker = ByteChunker::new(Cursor::new(text), ",")
+            .unwrap()
+            .with_chunk_limit(2);
+        assert_eq!(chunker.size_hint(), (0, Some(2)));
+        chunker.peek();
+        assert_eq!(chunker.size_hint(), (1, Some(2)));
+        assert_eq!(chunker.next_borrowed().unwrap().unwrap().as_ref(), b"a");
+        assert_eq!(chunker.next_borrowed().unwrap().unwrap().as_ref(), b"b");
+        assert!(chunker.next_borrowed().is_none());
+        assert!(chunker.next().is_none());
+        assert_eq!(chunker.size_hint(), (0, Some(0)));
+        let (mut source, unread) = chunker.into_innards();
+        let mut rest = unread;
+        source.read_to_end(&mut rest).unwrap();
+        assert_eq!(&rest, b"c,d,e");
+
+        let mut chunker = ByteChunker::new(Cursor::new(text), ",")
+            .unwrap()
+            .with_chunk_limit(0);
+        assert!(chunker.next().is_none());
+        chunker.reset(Cursor::new(text));
+        assert!(chunker.next().is_none());
+
+        let chunks = ByteChunker::new(Cursor::new(text), ",")
+            .unwrap()
+            .with_chunk_limit(10)
+            .count();
+        assert_eq!(chunks, 5);
+    }
+
+    #[test]
+    fn skip() {
+        let text = b"h1\nh2\n\na\nb\nc";
+        let mut chunker = ByteChunker::new(Cursor::new(text), "\n")
+            .unwrap()
+            .with_skip(3)
+            .with_chunk_limit(2);
+        assert_eq!(chunker.next_borrowed().unwrap().unwrap().as_ref(), b"a");
+        assert_eq!(chunker.next().unwrap().unwrap(), b"b");
+        assert!(chunker.next().is_none());
+        assert_eq!(chunker.chunk_count(), 2);
+
+        chunker.reset(Cursor::new(text));
+        let chunks: Vec<Vec<u8>> = (&mut chunker).map(|res| res.unwrap()).collect();
+        assert_eq!(chunks, vec![b"a".to_vec(), b"b".to_vec()]);
+
+        let offsets: Vec<(u64, u64)> = ByteChunker::new(Cursor::new(text), "\n")
+            .unwrap()
+            .with_skip(4)
+            .with_offsets()
+            .map(|res| {
+                let meta = res.unwrap();
+                (meta.start, meta.end)
+            })
+            .collect();
+        assert_eq!(offsets, vec![(9, 10), (11, 12)]);
+
+        let mut chunker = ByteChunker::new(Cursor::new(text), "\n")
+            .unwrap()
+            .with_skip(10);
+        assert!(chunker.next().is_none());
+
+        let mut chunker = ByteChunker::new(ErrorReader, "\n").unwrap().with_skip(1);
+        assert!(chunker.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn set_delimiter() {
+        let text = b"one\ntwo\nthree;four\nfive;six";
+        for buffer_size in [1, 3, 1024] {
+            let mut chunker = ByteChunker::new(Cursor::new(text), "\n")
+                .unwrap()
+                .with_buffer_size(buffer_size);
+            assert_eq!(chunker.next().unwrap().unwrap(), b"one");
+            chunker.set_delimiter(";").unwrap();
+            let rest: Vec<Vec<u8>> = chunker.map(|res| res.unwrap()).collect();
+            ref_slice_cmp(&rest, &["two\nthree", "four\nfive", "six"]);
+            assert_eq!(rest.len(), 3);
+        }
+
+        let mut chunker = ByteChunker::with_patterns(Cursor::new(text), &["\n", ";"])
+            .unwrap()
+            .with_match(MatchDisposition::Prepend);
+        assert_eq!(chunker.next().unwrap().unwrap(), b"one");
+        let mut borrowed = chunker.next_borrowed().unwrap().unwrap().to_vec();
+        assert_eq!(&borrowed, b"\ntwo");
+        chunker.set_delimiter("[ef]").unwrap();
+        borrowed = chunker.next().unwrap().unwrap();
+        assert_eq!(&borrowed, b"\nthr");
+        let rest: Vec<Vec<u8>> = chunker.map(|res| res.unwrap()).collect();
+        ref_slice_cmp(&rest, &["e", "e;", "four\n", "fiv", "e;six"]);
+        assert_eq!(rest.len(), 5);
+
+        let mut chunker = ByteChunker::new(Cursor::new(text), "\n").unwrap();
+        assert!(matches!(chunker.set_delimiter("("), Err(RcErr::Regex(_))));
+    }
+
+    #[test]
+    fn group_adapter() {
+        let text = b"\n\na\nb\n\nc\n\n\nd\ne\n";
+        let groups: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), "\n")
+            .unwrap()
+            .with_adapter(GroupAdapter::new(|line: &[u8]| line.is_empty()).with_separator(b"+"))
+            .map(|res| res.unwrap())
+            .collect();
+        ref_slice_cmp(&groups, &["a+b", "c", "d+e"]);
+        assert_eq!(groups.len(), 3);
+
+        // Boundaries that aren't empty, and a group in progress at the end.
+        let mut n = 0;
+        let groups: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(b"1,2,3,4,5"), ",")
+            .unwrap()
+            .with_adapter(GroupAdapter::new(|_: &[u8]| {
+                n += 1;
+                n % 3 == 0
+            }))
+            .map(|res| res.unwrap())
+            .collect();
+        ref_slice_cmp(&groups, &["12", "45"]);
+        assert_eq!(groups.len(), 2);
+
+        let mut chunker = ByteChunker::new(ErrorReader, ",")
+            .unwrap()
+            .with_adapter(GroupAdapter::new(|_: &[u8]| false));
+        assert!(chunker.next().unwrap().is_err());
+        assert!(chunker.next().is_none());
+    }
+
+    #[test]
+    fn csv_records() {
+        let text: &[u8] =
+            b"a,b\n\"multi\nline\",\"\"\"quoted\"\"\"\r\n\n\"\"\"\"\n,\"\r\n\",x\nlast";
+        let expected: Vec<&[u8]> = vec![
+            b"a,b",
+            b"\"multi\nline\",\"\"\"quoted\"\"\"",
+            b"",
+            b"\"\"\"\"",
+            b",\"\r\n\",x",
+            b"last",
+        ];
+        let records: Vec<Vec<u8>> = CsvRecordChunker::new(Cursor::new(text))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        ref_slice_cmp(&records, &expected);
+
+        /* However the data comes in, the quote state is tracked. */
+        for size in [1, 2, 3, 7] {
+            let records: Vec<Vec<u8>> = CsvRecordChunker::new(Cursor::new(text))
+                .with_buffer_size(size)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            ref_slice_cmp(&records, &expected);
+        }
+
+        /* The chunker underneath doesn't depend on what it's been asked
+        before, so a clone left behind while the other runs into an
+        unterminated quoted field still finds the same records. */
+        let text: &[u8] = b"a,b\n\"\nc";
+        let chunker = CsvRecordChunker::new(Cursor::new(text)).into_inner();
+        let twin = chunker.clone();
+        for chunker in [chunker, twin] {
+            let records: Vec<Vec<u8>> = chunker.collect::<Result<_, _>>().unwrap();
+            ref_slice_cmp(&records, &[&b"a,b"[..], b"\"\nc"]);
+        }
+
+        /* A quoted field with lots of line endings in it. */
+        let mut text = b"a,\"".to_vec();
+        text.extend_from_slice(&b"x\n".repeat(10_000));
+        text.extend_from_slice(b"\"\nb,c\n");
+        let records: Vec<Vec<u8>> = CsvRecordChunker::new(Cursor::new(&text))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].len(), text.len() - 5);
+        assert_eq!(records[1], b"b,c");
+    }
+
+    #[test]
+    fn lines() {
+        let cases: [(&[u8], &[&str]); 6] = [
+            (b"", &[]),
+            (b"\n", &[""]),
+            (b"a\r\nb\n", &["a", "b"]),
+            (b"a\n\r\nb", &["a", "", "b"]),
+            (b"a\rb\r", &["a\rb"]),
+            (b"a\r\r\nb\r\n", &["a\r", "b"]),
+        ];
+        for (text, expected) in cases {
+            let lines: Vec<String> = LineChunker::new(Cursor::new(text))
+                .strip_trailing_cr()
+                .strings()
+                .map(|res| res.unwrap())
+                .collect();
+            assert_eq!(&lines, expected);
+        }
+
+        let lines: Vec<Vec<u8>> = LineChunker::new(Cursor::new(b"a\r"))
+            .map(|res| res.unwrap())
+            .collect();
+        assert_eq!(lines, vec![b"a\r".to_vec()]);
+
+        let lines: Vec<String> = LineChunker::new(Cursor::new(b"ok\n\xff\nok"))
+            .strings_with(Utf8FailureMode::Continue)
+            .map(|res| res.unwrap_or_default())
+            .collect();
+        assert_eq!(lines, vec!["ok", "", "ok"]);
+    }
+
+    /* Hands out its pieces one read at a time, with an end-of-file
+    (a read of zero bytes) after each one. */
+    struct Stalling {
+        pieces: std::collections::VecDeque<&'static [u8]>,
+        stalled: bool,
+        handle: Option<FollowHandle>,
+    }
+
+    impl Read for Stalling {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.stalled = !self.stalled;
+            if self.stalled {
+                return Ok(0);
+            }
+            match self.pieces.pop_front() {
+                Some(piece) => {
+                    buf[..piece.len()].copy_from_slice(piece);
+                    Ok(piece.len())
+                }
+                None => {
+                    if let Some(handle) = &self.handle {
+                        handle.stop_following();
+                    }
+                    Ok(0)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn follow() {
+        let stalling = || Stalling {
+            pieces: vec![&b"a,b"[..], b"c,", b"d"].into(),
+            stalled: false,
+            handle: None,
+        };
+
+        /* Without following, the first end-of-file is the end. */
+        let chunker = ByteChunker::new(stalling(), ",").unwrap();
+        assert_eq!(chunker.count(), 0);
+
+        let mut chunker = ByteChunker::new(stalling(), ",")
+            .unwrap()
+            .with_follow(std::time::Duration::from_millis(1));
+        chunker.get_mut().handle = Some(chunker.follow_handle());
+        let chunks: Vec<Vec<u8>> = chunker.collect::<Result<_, _>>().unwrap();
+        assert_eq!(chunks, vec![&b"a"[..], b"bc", b"d"]);
+
+        /* Stopping between chunks flushes at the next end-of-file. */
+        let mut chunker = ByteChunker::new(stalling(), ",")
+            .unwrap()
+            .with_follow(std::time::Duration::from_millis(1));
+        assert_eq!(chunker.next().unwrap().unwrap(), b"a");
+        chunker.stop_following();
+        assert_eq!(chunker.next().unwrap().unwrap(), b"b");
+    }
+
+    /* Fails its first `fails` reads with an error of kind `kind`, then
+    reads from `inner`. */
+    struct Unready {
+        kind: std::io::ErrorKind,
+        fails: usize,
+        inner: Cursor<&'static [u8]>,
+    }
+
+    impl Read for Unready {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.fails > 0 {
+                self.fails -= 1;
+                return Err(self.kind.into());
+            }
+            self.inner.read(buf)
+        }
+    }
+
+    #[test]
+    fn read_timeout() {
+        use std::{io::ErrorKind, time::Duration};
+
+        let unready = |kind, fails| Unready {
+            kind,
+            fails,
+            inner: Cursor::new(b"a,b"),
+        };
+
+        /* Sources that come around in time are fine. */
+        for kind in [ErrorKind::WouldBlock, ErrorKind::TimedOut] {
+            let chunks: Vec<Vec<u8>> = ByteChunker::new(unready(kind, 3), ",")
+                .unwrap()
+                .with_read_timeout(Duration::from_secs(60))
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(chunks, vec![&b"a"[..], b"b"]);
+        }
+
+        /* Ones that don't time out, and can be waited on again. */
+        let mut chunker = ByteChunker::new(unready(ErrorKind::WouldBlock, usize::MAX), ",")
+            .unwrap()
+            .with_read_timeout(Duration::from_millis(1))
+            .on_error(ErrorResponse::Continue);
+        assert_eq!(chunker.next(), Some(Err(RcErr::Timeout)));
+        assert_eq!(chunker.next(), Some(Err(RcErr::Timeout)));
+        chunker.get_mut().fails = 0;
+        assert_eq!(chunker.next(), Some(Ok(b"a".to_vec())));
+
+        /* Without a read timeout, a timed-out read is just a read error. */
+        let mut chunker = ByteChunker::new(unready(ErrorKind::TimedOut, 1), ",").unwrap();
+        assert_eq!(chunker.next().unwrap().unwrap_err().kind(), RcErrKind::Read);
+
+        let e: std::io::Error = RcErr::Timeout.into();
+        assert_eq!(e.kind(), ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn overlap() {
+        let chunks = |text: &'static [u8], dispo, overlap| -> Vec<Vec<u8>> {
+            ByteChunker::new(Cursor::new(text), ",")
+                .unwrap()
+                .with_match(dispo)
+                .with_overlap(overlap)
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(
+            chunks(b"ab,cd,e", MatchDisposition::Drop, 0),
+            vec![&b"ab"[..], b"cd", b"e"]
+        );
+        assert_eq!(
+            chunks(b"ab,cd,e", MatchDisposition::Drop, 1),
+            vec![&b"ab"[..], b"bcd", b"de"]
+        );
+        assert_eq!(
+            chunks(b"ab,cd,e", MatchDisposition::Append, 1),
+            vec![&b"ab,"[..], b",cd,", b",e"]
+        );
+        /* Short chunks carry the overlap along with them. */
+        assert_eq!(
+            chunks(b"abc,,d", MatchDisposition::Drop, 4),
+            vec![&b"abc"[..], b"abc", b"abcd"]
+        );
+
+        /* Offsets don't count the overlap. */
+        let metas: Vec<ChunkMeta> = ByteChunker::new(Cursor::new(b"ab,cd"), ",")
+            .unwrap()
+            .with_overlap(1)
+            .with_offsets()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!((metas[1].start, metas[1].end), (3, 5));
+        assert_eq!(metas[1].bytes, b"bcd");
+
+        /* Borrowing gets the overlap too, and a reset forgets it. */
+        let mut chunker = ByteChunker::new(Cursor::new(b"ab,cd"), ",")
+            .unwrap()
+            .with_overlap(1);
+        assert_eq!(&*chunker.next_borrowed().unwrap().unwrap(), b"ab");
+        assert_eq!(&*chunker.next_borrowed().unwrap().unwrap(), b"bcd");
+        chunker.reset(Cursor::new(b"ef,gh"));
+        assert_eq!(chunker.next().unwrap().unwrap(), b"ef");
+    }
+
+    #[test]
+    fn match_filter() {
+        /* Split on runs of spaces, but only where a run is followed by a
+        capital letter. */
+        let text = b"One two  Three four Five  ";
+        let accept = |buff: &[u8], m: std::ops::Range<usize>| match buff.get(m.end) {
+            Some(b) => b.is_ascii_uppercase(),
+            None => true,
+        };
+        for size in [1, 2, 3, 1024] {
+            let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), " +")
+                .unwrap()
+                .with_buffer_size(size)
+                .with_match_filter(accept)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(
+                chunks,
+                vec![&b"One two"[..], b"Three four", b"Five"],
+                "{size}"
+            );
+        }
+
+        /* Rejected matches belong to the chunk in any disposition. */
+        let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(b"a1b2c"), "[0-9]")
+            .unwrap()
+            .with_match(MatchDisposition::Append)
+            .with_match_filter(|buff: &[u8], m: std::ops::Range<usize>| buff[m] == *b"2")
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(chunks, vec![&b"a1b2"[..], b"c"]);
+
+        /* Clones share the predicate. */
+        let calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = calls.clone();
+        let chunker = ByteChunker::new(Cursor::new(b"a,b,c"), ",")
+            .unwrap()
+            .with_match_filter(move |_: &[u8], _| {
+                counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                true
+            });
+        assert_eq!(chunker.clone().count(), 3);
+        assert_eq!(chunker.count(), 3);
+        assert!(calls.load(std::sync::atomic::Ordering::Relaxed) >= 4);
+    }
+
+    #[test]
+    fn progress() {
+        use std::sync::{Arc, Mutex};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let text = b"aaaa,bbbb,cccc,dddd".to_vec();
+        let mut chunker = ByteChunker::new(Cursor::new(text.clone()), ",")
+            .unwrap()
+            .with_buffer_size(3)
+            .with_progress(5, move |n| log.lock().unwrap().push(n));
+
+        /* It's called while reading, before any chunk is found. */
+        assert_eq!(chunker.next().unwrap().unwrap(), b"aaaa");
+        assert_eq!(*seen.lock().unwrap(), vec![6]);
+        assert_eq!(chunker.by_ref().count(), 3);
+        assert_eq!(*seen.lock().unwrap(), vec![6, 12, 15]);
+
+        /* A reset starts the count over. */
+        seen.lock().unwrap().clear();
+        chunker.reset(Cursor::new(text.clone()));
+        assert_eq!(chunker.by_ref().count(), 4);
+        assert_eq!(*seen.lock().unwrap(), vec![6, 12, 15]);
+
+        /* Buffered sources report progress too. */
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let chunker = ByteChunker::from_buf_read(
+            std::io::BufReader::with_capacity(4, Cursor::new(text)),
+            ",",
+        )
+        .unwrap()
+        .with_progress(8, move |n| log.lock().unwrap().push(n));
+        assert_eq!(chunker.count(), 4);
+        assert_eq!(*seen.lock().unwrap(), vec![8, 16]);
+    }
+
+    #[test]
+    fn zero_buffer_size() {
+        let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(b"a,b,c"), ",")
+            .unwrap()
+            .with_buffer_size(0)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(chunks, vec![&b"a"[..], b"b", b"c"]);
+    }
+
+    #[test]
+    fn apply_disposition() {
+        use crate::ctrl::{apply_disposition, Split};
+
+        /* A match at 3..5 in "abc, def". */
+        let split = |dispo| apply_disposition(dispo, 3, 5);
+        assert_eq!(
+            split(MatchDisposition::Drop),
+            Split {
+                len: 3,
+                consumed: 5,
+                scan_offset: 0
+            }
+        );
+        assert_eq!(
+            split(MatchDisposition::Append),
+            Split {
+                len: 5,
+                consumed: 5,
+                scan_offset: 0
+            }
+        );
+        assert_eq!(
+            split(MatchDisposition::Prepend),
+            Split {
+                len: 3,
+                consumed: 3,
+                scan_offset: 2
+            }
+        );
+
+        /* A match at the very front of the buffer. */
+        assert_eq!(
+            apply_disposition(MatchDisposition::Prepend, 0, 2),
+            Split {
+                len: 0,
+                consumed: 0,
+                scan_offset: 2
+            }
+        );
+
+        /* The chunkers that use it all agree with the
+        reference implementation. */
+        let text = std::fs::read(TEST_PATH).unwrap();
+        let re = Regex::new(TEST_PATT).unwrap();
+        for dispo in [
+            MatchDisposition::Drop,
+            MatchDisposition::Append,
+            MatchDisposition::Prepend,
+        ] {
+            let expected = chunk_vec(&re, &text, dispo);
+            let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(&text), TEST_PATT)
+                .unwrap()
+                .with_match(dispo)
+                .map(Result::unwrap)
+                .collect();
+            ref_slice_cmp(&chunks, &expected);
+            let slices: Vec<&[u8]> = SliceChunker::new(&text, TEST_PATT)
+                .unwrap()
+                .with_match(dispo)
+                .collect();
+            ref_slice_cmp(&slices, &expected);
+        }
+    }
+
+    #[test]
+    fn switch_disposition() {
+        type Switched = (Vec<Vec<u8>>, Vec<Vec<u8>>);
+        let text: &[u8] = b"a,bb,,ccc,dddd,e,,ff";
+        let re = Regex::new(",").unwrap();
+        let byte_switch = |size| {
+            move |from, to| -> Switched {
+                let mut chunker = ByteChunker::new(Cursor::new(text), ",")
+                    .unwrap()
+                    .with_buffer_size(size)
+                    .with_match(from);
+                let before: Vec<Vec<u8>> = chunker.by_ref().take(3).map(Result::unwrap).collect();
+                let after: Vec<Vec<u8>> = chunker.with_match(to).map(Result::unwrap).collect();
+                (before, after)
+            }
+        };
+        let slice_switch = |from, to| -> Switched {
+            let mut chunker = SliceChunker::new(text, ",").unwrap().with_match(from);
+            let before: Vec<Vec<u8>> = chunker.by_ref().take(3).map(<[u8]>::to_vec).collect();
+            let after: Vec<Vec<u8>> = chunker.with_match(to).map(<[u8]>::to_vec).collect();
+            (before, after)
+        };
+        #[cfg_attr(not(feature = "mmap"), allow(unused_mut))]
+        let mut switches: Vec<Box<dyn Fn(MatchDisposition, MatchDisposition) -> Switched>> = vec![
+            Box::new(byte_switch(1)),
+            Box::new(byte_switch(4)),
+            Box::new(byte_switch(1024)),
+            Box::new(slice_switch),
+        ];
+        #[cfg(feature = "mmap")]
+        let path = std::env::temp_dir().join("regex_chunker_switch_disposition.txt");
+        #[cfg(feature = "mmap")]
+        {
+            std::fs::write(&path, text).unwrap();
+            let path = path.clone();
+            switches.push(Box::new(move |from, to| -> Switched {
+                let mut chunker = MmapChunker::new(&path, ",").unwrap().with_match(from);
+                let before: Vec<Vec<u8>> = chunker.by_ref().take(3).collect();
+                let after: Vec<Vec<u8>> = chunker.with_match(to).collect();
+                (before, after)
+            }));
+        }
+
+        for switch in switches {
+            /* The delimiter after "a,bb," was dropped before the switch. */
+            let (before, after) = switch(MatchDisposition::Drop, MatchDisposition::Prepend);
+            ref_slice_cmp(&before, &["a", "bb", ""]);
+            let rest = &text[6..];
+            ref_slice_cmp(&after, &chunk_vec(&re, rest, MatchDisposition::Prepend));
+
+            /* Then the delimiter after "a,bb," was left for the next chunk. */
+            let (before, after) = switch(MatchDisposition::Prepend, MatchDisposition::Drop);
+            ref_slice_cmp(&before, &["a", ",bb", ","]);
+            ref_slice_cmp(&after, &chunk_vec(&re, rest, MatchDisposition::Drop));
+
+            let (_, after) = switch(MatchDisposition::Prepend, MatchDisposition::Append);
+            let mut expected: Vec<&[u8]> = vec![b",ccc,"];
+            expected.extend(chunk_vec(&re, &text[10..], MatchDisposition::Append));
+            ref_slice_cmp(&after, &expected);
+
+            let (before, after) = switch(MatchDisposition::Append, MatchDisposition::Prepend);
+            ref_slice_cmp(&before, &["a,", "bb,", ","]);
+            ref_slice_cmp(&after, &chunk_vec(&re, rest, MatchDisposition::Prepend));
+        }
+        #[cfg(feature = "mmap")]
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn trailing() {
+        let text = b"a;b;tail";
+        let collect = |text: &'static [u8], policy, response| -> Vec<Result<Vec<u8>, RcErr>> {
+            ByteChunker::new(Cursor::new(text), ";")
+                .unwrap()
+                .with_trailing(policy)
+                .on_error(response)
+                .collect()
+        };
+
+        let chunks = collect(text, TrailingPolicy::Emit, ErrorResponse::Halt);
+        assert_eq!(chunks.last(), Some(&Ok(b"tail".to_vec())));
+        let chunks = collect(text, TrailingPolicy::Discard, ErrorResponse::Halt);
+        assert_eq!(chunks, vec![Ok(b"a".to_vec()), Ok(b"b".to_vec())]);
+        for response in [ErrorResponse::Halt, ErrorResponse::Continue] {
+            let chunks = collect(text, TrailingPolicy::Error, response);
+            assert_eq!(chunks.len(), 3);
+            assert_eq!(chunks[2], Err(RcErr::UnterminatedTail(b"tail".to_vec())));
+            let err: std::io::Error = chunks[2].clone().unwrap_err().into();
+            assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        }
+        let chunks = collect(text, TrailingPolicy::Error, ErrorResponse::Ignore);
+        assert_eq!(chunks.len(), 2);
+
+        // Terminated input is fine.
+        let chunks = collect(b"a;b;", TrailingPolicy::Error, ErrorResponse::Halt);
+        assert_eq!(chunks, vec![Ok(b"a".to_vec()), Ok(b"b".to_vec())]);
+        let chunks = collect(b"", TrailingPolicy::Error, ErrorResponse::Halt);
+        assert!(chunks.is_empty());
+    }
+
+    #[test]
+    fn size_limit() {
+        /* Unicode \w repeated this many times blows the default limit. */
+        let pattern = r"#\w{400}#";
+        assert!(matches!(
+            ByteChunker::new(Cursor::new(""), pattern),
+            Err(RcErr::Regex(_))
+        ));
+
+        let text = format!("a#{}#b", "x".repeat(400));
+        let chunk = |chunker: ByteChunker<_>| -> Vec<Vec<u8>> {
+            chunker.collect::<Result<_, _>>().unwrap()
+        };
+        let chunker = ByteChunker::with_size_limit(Cursor::new(text.clone()), pattern, 1 << 30);
+        ref_slice_cmp(&chunk(chunker.unwrap()), &["a", "b"]);
+
+        /* The limit survives recompiling with different flags. */
+        let chunker = ByteChunker::with_size_limit(Cursor::new(text), pattern, 1 << 30)
+            .unwrap()
+            .multi_line(true);
+        ref_slice_cmp(&chunk(chunker.unwrap()), &["a", "b"]);
+
+        assert!(matches!(
+            ByteChunker::with_size_limit(Cursor::new(""), r"\w{20}", 100),
+            Err(RcErr::Regex(_))
+        ));
+    }
+}
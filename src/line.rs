@@ -0,0 +1,137 @@
+/*!
+Chunkers for splitting text into lines.
+*/
+use std::io::Read;
+
+use crate::{Adapter, AdapterAction, ByteChunker, RcErr, StringAdapter, Utf8FailureMode};
+
+/**
+A [`ByteChunker`] preconfigured to yield the lines of its source, split
+on `\n` or `\r\n`, without their line endings. A final line with no line
+ending is returned like any other, and a source that ends with a line
+ending doesn't produce an empty final line.
+
+Some files end with a carriage return that isn't followed by a newline;
+to drop it from the final line, use [`LineChunker::strip_trailing_cr`].
+To get `String`s instead of `Vec<u8>`s, use [`LineChunker::strings`].
+
+```
+use regex_chunker::LineChunker;
+use std::io::Cursor;
+
+# fn main() -> Result<(), regex_chunker::RcErr> {
+let text = b"unix\nwindows\r\n\nno newline\r";
+
+let lines: Vec<Vec<u8>> = LineChunker::new(Cursor::new(text))
+    .strip_trailing_cr()
+    .collect::<Result<_, _>>()?;
+assert_eq!(&lines, &[&b"unix"[..], b"windows", b"", b"no newline"]);
+
+let lines: Vec<String> = LineChunker::new(Cursor::new(text))
+    .strings()
+    .collect::<Result<_, _>>()?;
+assert_eq!(lines.last().unwrap(), "no newline\r");
+# Ok(())
+# }
+```
+*/
+#[derive(Debug)]
+pub struct LineChunker<R> {
+    chunker: ByteChunker<R>,
+    strip_trailing_cr: bool,
+}
+
+impl<R> LineChunker<R> {
+    /// Return a new [`LineChunker`] that yields the lines of `source`.
+    pub fn new(source: R) -> Self {
+        // This pattern is known to be valid.
+        let chunker = ByteChunker::new(source, r"\r?\n").unwrap();
+        Self {
+            chunker,
+            strip_trailing_cr: false,
+        }
+    }
+
+    /// Builder-pattern method for removing a carriage return from the
+    /// end of the final line, if it has one (and no newline).
+    pub fn strip_trailing_cr(mut self) -> Self {
+        self.strip_trailing_cr = true;
+        self
+    }
+
+    /// Return a [`StringLineChunker`] that yields each line as a
+    /// `String`, treating invalid UTF-8 according to the
+    /// [`Utf8FailureMode`] (like a [`StringAdapter`] does).
+    pub fn strings_with(self, mode: Utf8FailureMode) -> StringLineChunker<R> {
+        StringLineChunker {
+            lines: self,
+            adapter: StringAdapter::new(mode),
+        }
+    }
+
+    /// Return a [`StringLineChunker`] that yields each line as a
+    /// `String`, stopping with an error at the first invalid UTF-8.
+    pub fn strings(self) -> StringLineChunker<R> {
+        self.strings_with(Utf8FailureMode::default())
+    }
+
+    /// Consume the `LineChunker` and return the underlying
+    /// [`ByteChunker`].
+    pub fn into_inner(self) -> ByteChunker<R> {
+        self.chunker
+    }
+}
+
+impl<R: Read> Iterator for LineChunker<R> {
+    type Item = Result<Vec<u8>, RcErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = match self.chunker.next()? {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e)),
+        };
+        // Only the last line can end with a carriage return that isn't
+        // part of its line ending.
+        if self.strip_trailing_cr && line.last() == Some(&b'\r') && self.chunker.peek().is_none() {
+            line.pop();
+        }
+        Some(Ok(line))
+    }
+
+    fn count(self) -> usize {
+        self.chunker.count()
+    }
+}
+
+/**
+A [`LineChunker`] that yields `String`s. It is built with
+[`LineChunker::strings`] or [`LineChunker::strings_with`].
+*/
+#[derive(Debug)]
+pub struct StringLineChunker<R> {
+    lines: LineChunker<R>,
+    adapter: StringAdapter,
+}
+
+impl<R> StringLineChunker<R> {
+    /// Consume the `StringLineChunker` and return the underlying
+    /// [`LineChunker`].
+    pub fn into_inner(self) -> LineChunker<R> {
+        self.lines
+    }
+}
+
+impl<R: Read> Iterator for StringLineChunker<R> {
+    type Item = Result<String, RcErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = self.lines.next();
+            match self.adapter.act(line) {
+                AdapterAction::Emit(s) => return Some(s),
+                AdapterAction::Skip => continue,
+                AdapterAction::Done => return None,
+            }
+        }
+    }
+}
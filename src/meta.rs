@@ -0,0 +1,374 @@
+/*!
+Chunker types that yield information about each chunk along with
+its contents.
+*/
+use std::io::Read;
+
+use crate::{ByteChunker, MatchDisposition, RcErr};
+
+/**
+A chunk of bytes along with its position in the chunker's source.
+
+`start` and `end` are absolute byte positions counted from the beginning
+of the wrapped reader, such that `end - start == bytes.len()`. In
+[`MatchDisposition::Drop`](crate::MatchDisposition::Drop) mode, the
+dropped delimiter bytes account for the gaps between the `end` of one
+chunk and the `start` of the next.
+(The exception is a chunker with an overlap; see
+[`ByteChunker::with_overlap`].)
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkMeta {
+    /// Position in the source of the first byte of the chunk.
+    pub start: u64,
+    /// Position in the source just past the last byte of the chunk.
+    pub end: u64,
+    /// The contents of the chunk.
+    pub bytes: Vec<u8>,
+}
+
+/**
+A chunker that yields [`ChunkMeta`]s, reporting where in the source each
+chunk was found. It is built from a [`ByteChunker`] with
+[`ByteChunker::with_offsets`].
+
+```
+use regex_chunker::{ByteChunker, ChunkMeta};
+use std::io::Cursor;
+
+# fn main() -> Result<(), regex_chunker::RcErr> {
+let text = b"one, two, three";
+
+let chunks: Vec<ChunkMeta> = ByteChunker::new(Cursor::new(text), ", ")?
+    .with_offsets()
+    .collect::<Result<_, _>>()?;
+
+assert_eq!((chunks[1].start, chunks[1].end), (5, 8));
+assert_eq!(&text[5..8], chunks[1].bytes.as_slice());
+# Ok(())
+# }
+```
+*/
+pub struct OffsetChunker<R> {
+    chunker: ByteChunker<R>,
+}
+
+impl<R> OffsetChunker<R> {
+    /// Consume this `OffsetChunker` and return the underlying
+    /// [`ByteChunker`].
+    pub fn into_inner(self) -> ByteChunker<R> {
+        self.chunker
+    }
+}
+
+impl<R> From<ByteChunker<R>> for OffsetChunker<R> {
+    fn from(chunker: ByteChunker<R>) -> Self {
+        Self { chunker }
+    }
+}
+
+impl<R: Read> Iterator for OffsetChunker<R> {
+    type Item = Result<ChunkMeta, RcErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.chunker.next()? {
+            Ok(bytes) => {
+                let (start, end) = self.chunker.last_span();
+                Some(Ok(ChunkMeta { start, end, bytes }))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/**
+A chunker that yields each chunk along with the capture groups of the
+delimiter match associated with it. It is built from a [`ByteChunker`]
+with [`ByteChunker::with_captures`].
+
+Each item is a tuple of the chunk's bytes and a vector with one element
+for each capture group in the delimiter pattern (not counting the implicit
+group 0, the whole match); groups that didn't participate in the match are
+`None`. In [`MatchDisposition::Drop`](crate::MatchDisposition::Drop) and
+[`MatchDisposition::Append`](crate::MatchDisposition::Append) mode, the
+groups are those of the delimiter that _ended_ the chunk; in
+[`MatchDisposition::Prepend`](crate::MatchDisposition::Prepend) mode they
+are those of the delimiter the chunk _begins_ with.
+
+If the delimiter pattern has no capture groups, the vector is always
+empty. It is also empty for chunks that aren't associated with a match,
+like the final chunk at the end of the source.
+
+```
+use regex_chunker::ByteChunker;
+use std::io::Cursor;
+
+# fn main() -> Result<(), regex_chunker::RcErr> {
+let text = b"alpha[1]beta[22]gamma";
+
+let chunks: Vec<_> = ByteChunker::new(Cursor::new(text), r"\[(\d+)\]")?
+    .with_captures()
+    .collect::<Result<_, _>>()?;
+
+assert_eq!(chunks[0], (b"alpha".to_vec(), vec![Some(b"1".to_vec())]));
+assert_eq!(chunks[1], (b"beta".to_vec(), vec![Some(b"22".to_vec())]));
+assert_eq!(chunks[2], (b"gamma".to_vec(), vec![]));
+# Ok(())
+# }
+```
+*/
+pub struct CaptureChunker<R> {
+    chunker: ByteChunker<R>,
+}
+
+impl<R> CaptureChunker<R> {
+    /// Consume this `CaptureChunker` and return the underlying
+    /// [`ByteChunker`].
+    pub fn into_inner(mut self) -> ByteChunker<R> {
+        self.chunker.track_captures(false);
+        self.chunker
+    }
+}
+
+impl<R> From<ByteChunker<R>> for CaptureChunker<R> {
+    fn from(mut chunker: ByteChunker<R>) -> Self {
+        chunker.track_captures(true);
+        Self { chunker }
+    }
+}
+
+impl<R: Read> Iterator for CaptureChunker<R> {
+    type Item = Result<(Vec<u8>, Vec<Option<Vec<u8>>>), RcErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.chunker.next()? {
+            Ok(bytes) => Some(Ok((bytes, self.chunker.take_captures()))),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/**
+A chunker that yields each chunk along with the delimiter that followed
+it, so that the source can be reconstructed exactly. It is built from a
+[`ByteChunker`] with [`ByteChunker::with_delimiters`].
+
+Each item is a tuple of the chunk's bytes and the text of the match that
+ended it; this is `None` for the final chunk at the end of the source
+(or for a chunk cut short by
+[`ByteChunker::with_max_chunk_size`](crate::ByteChunker::with_max_chunk_size)).
+
+```
+use regex_chunker::ByteChunker;
+use std::io::Cursor;
+
+# fn main() -> Result<(), regex_chunker::RcErr> {
+let text = b"one;two;;three";
+
+let chunks: Vec<_> = ByteChunker::new(Cursor::new(text), ";+")?
+    .with_delimiters()
+    .collect::<Result<_, _>>()?;
+
+assert_eq!(chunks[1], (b"two".to_vec(), Some(b";;".to_vec())));
+assert_eq!(chunks[2], (b"three".to_vec(), None));
+# Ok(())
+# }
+```
+*/
+pub struct DelimitedChunker<R> {
+    chunker: ByteChunker<R>,
+}
+
+impl<R> DelimitedChunker<R> {
+    /// Consume this `DelimitedChunker` and return the underlying
+    /// [`ByteChunker`].
+    pub fn into_inner(mut self) -> ByteChunker<R> {
+        self.chunker.track_delimiters(false);
+        self.chunker
+    }
+}
+
+impl<R> From<ByteChunker<R>> for DelimitedChunker<R> {
+    fn from(mut chunker: ByteChunker<R>) -> Self {
+        chunker.track_delimiters(true);
+        Self {
+            chunker: chunker.with_match(MatchDisposition::Drop),
+        }
+    }
+}
+
+impl<R: Read> Iterator for DelimitedChunker<R> {
+    type Item = Result<(Vec<u8>, Option<Vec<u8>>), RcErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.chunker.next()? {
+            Ok(bytes) => {
+                let delim = self.chunker.last_delim().map(<[u8]>::to_vec);
+                Some(Ok((bytes, delim)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/**
+Identifies the delimiter pattern associated with a chunk yielded by a
+[`KindChunker`].
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DelimiterKind {
+    /// The delimiter matched the pattern with this index in the list
+    /// passed to [`ByteChunker::with_patterns`] (or `0` if the chunker
+    /// only has one pattern).
+    Pattern(usize),
+    /// The chunk isn't associated with a delimiter, like the final chunk
+    /// at the end of the source.
+    None,
+}
+
+impl DelimiterKind {
+    /// The index of the matching pattern, if there was a match.
+    pub fn index(&self) -> Option<usize> {
+        match self {
+            DelimiterKind::Pattern(n) => Some(*n),
+            DelimiterKind::None => None,
+        }
+    }
+}
+
+/**
+A chunker that yields each chunk along with a [`DelimiterKind`]
+identifying which of its patterns matched the delimiter associated with
+the chunk. It is built from a [`ByteChunker`] with
+[`ByteChunker::with_delimiter_kinds`].
+
+Like with the [`CaptureChunker`], in
+[`MatchDisposition::Drop`](crate::MatchDisposition::Drop) and
+[`MatchDisposition::Append`](crate::MatchDisposition::Append) mode, the
+delimiter is the one that _ended_ the chunk; in
+[`MatchDisposition::Prepend`](crate::MatchDisposition::Prepend) mode it's
+the one the chunk _begins_ with.
+
+To attach labels to the patterns, index a list of labels with
+[`DelimiterKind::index`].
+
+```
+use regex_chunker::{ByteChunker, DelimiterKind};
+use std::io::Cursor;
+
+# fn main() -> Result<(), regex_chunker::RcErr> {
+let text = b"a = 1; b = 2\nc = 3";
+let chunks: Vec<_> = ByteChunker::with_patterns(Cursor::new(text), &[r";\s*", r"\n"])?
+    .with_delimiter_kinds()
+    .collect::<Result<_, _>>()?;
+
+assert_eq!(chunks[0], (b"a = 1".to_vec(), DelimiterKind::Pattern(0)));
+assert_eq!(chunks[1], (b"b = 2".to_vec(), DelimiterKind::Pattern(1)));
+assert_eq!(chunks[2], (b"c = 3".to_vec(), DelimiterKind::None));
+
+let labels = ["statement", "line"];
+assert_eq!(chunks[1].1.index().map(|n| labels[n]), Some("line"));
+# Ok(())
+# }
+```
+*/
+pub struct KindChunker<R> {
+    chunker: ByteChunker<R>,
+}
+
+impl<R> KindChunker<R> {
+    /// Consume this `KindChunker` and return the underlying
+    /// [`ByteChunker`].
+    pub fn into_inner(self) -> ByteChunker<R> {
+        self.chunker
+    }
+}
+
+impl<R> From<ByteChunker<R>> for KindChunker<R> {
+    fn from(chunker: ByteChunker<R>) -> Self {
+        Self { chunker }
+    }
+}
+
+impl<R: Read> Iterator for KindChunker<R> {
+    type Item = Result<(Vec<u8>, DelimiterKind), RcErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.chunker.next()? {
+            Ok(bytes) => {
+                let kind = match self.chunker.take_kind() {
+                    Some(n) => DelimiterKind::Pattern(n),
+                    None => DelimiterKind::None,
+                };
+                Some(Ok((bytes, kind)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/**
+A chunker that yields each chunk along with a flag that's `true` if it's
+the last one. It is built from a [`ByteChunker`] with
+[`ByteChunker::with_terminal_flag`].
+
+There's no telling whether a chunk is the last one until the chunker has
+tried to read the one after it, so this reads one chunk ahead (using
+[`ByteChunker::peek`]): each chunk is held back until the next one has
+been read in full or the source has ended. With a slow source, that
+means a chunk isn't available as soon as it's complete, but only once
+the chunk after it is, too.
+
+A chunk that's followed by an error isn't the last one, so it isn't
+flagged, even if the error turns out to be the last thing the chunker
+returns.
+
+```
+use regex_chunker::ByteChunker;
+use std::io::Cursor;
+
+# fn main() -> Result<(), regex_chunker::RcErr> {
+let chunks: Vec<(Vec<u8>, bool)> = ByteChunker::new(Cursor::new("a,b,c"), ",")?
+    .with_terminal_flag()
+    .collect::<Result<_, _>>()?;
+
+assert_eq!(
+    &chunks,
+    &[(b"a".to_vec(), false), (b"b".to_vec(), false), (b"c".to_vec(), true)]
+);
+# Ok(())
+# }
+```
+*/
+pub struct TerminalChunker<R> {
+    chunker: ByteChunker<R>,
+}
+
+impl<R> TerminalChunker<R> {
+    /// Consume this `TerminalChunker` and return the underlying
+    /// [`ByteChunker`]. If the `TerminalChunker` has read ahead, the
+    /// chunk it read is still there to be returned by the `ByteChunker`.
+    pub fn into_inner(self) -> ByteChunker<R> {
+        self.chunker
+    }
+}
+
+impl<R> From<ByteChunker<R>> for TerminalChunker<R> {
+    fn from(chunker: ByteChunker<R>) -> Self {
+        Self { chunker }
+    }
+}
+
+impl<R: Read> Iterator for TerminalChunker<R> {
+    type Item = Result<(Vec<u8>, bool), RcErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.chunker.next()? {
+            Ok(bytes) => {
+                let last = self.chunker.peek().is_none();
+                Some(Ok((bytes, last)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
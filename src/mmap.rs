@@ -0,0 +1,134 @@
+/*!
+Chunking memory-mapped files.
+*/
+use std::{fs::File, path::Path};
+
+use memmap2::Mmap;
+use regex::bytes::Regex;
+
+use crate::{slice::SliceScanner, ByteChunker, MatchDisposition, RcErr, SliceChunker};
+
+impl ByteChunker<File> {
+    /**
+    Memory-map the file at `path` and return an [`MmapChunker`] that
+    chunks its contents by delimiting them with the supplied regex
+    pattern.
+
+    Rather than reading the file through a buffer, this searches the
+    mapped file directly, so none of the data is copied except the
+    chunks themselves. See the [`MmapChunker`] docs for caveats.
+    */
+    pub fn from_path_mmap<P: AsRef<Path>>(path: P, delimiter: &str) -> Result<MmapChunker, RcErr> {
+        MmapChunker::new(path, delimiter)
+    }
+}
+
+/**
+Chunks the contents of a memory-mapped file. Build one with
+[`ByteChunker::from_path_mmap`] or [`MmapChunker::new`].
+
+It yields each chunk as a `Vec<u8>`, treating matches and empty chunks
+the same way as a [`ByteChunker`]. To avoid copying the chunks, too,
+[`MmapChunker::chunks`] returns a [`SliceChunker`] that borrows them
+from the mapping.
+
+The file stays mapped (and open) until the `MmapChunker` is dropped.
+Like with any memory-mapped file, if some other process modifies or
+truncates the file while it's mapped, the chunker may return garbage or
+the program may crash.
+
+```no_run
+use regex_chunker::ByteChunker;
+
+# fn main() -> Result<(), regex_chunker::RcErr> {
+let chunker = ByteChunker::from_path_mmap("/var/log/syslog", r"\n")?;
+let n_errors = chunker
+    .chunks()
+    .filter(|line| line.windows(5).any(|w| w == b"error"))
+    .count();
+println!("{} lines mention errors", n_errors);
+# Ok(())
+# }
+```
+*/
+#[derive(Debug)]
+pub struct MmapChunker {
+    /* `None` for an empty file, which can't be mapped on every platform. */
+    map: Option<Mmap>,
+    scanner: SliceScanner,
+}
+
+impl MmapChunker {
+    /**
+    Memory-map the file at `path` and return a new [`MmapChunker`] that
+    will chunk its contents by delimiting them with the supplied regex
+    pattern.
+    */
+    pub fn new<P: AsRef<Path>>(path: P, delimiter: &str) -> Result<Self, RcErr> {
+        let fence = Regex::new(delimiter)?;
+        Self::with_regex(path, fence)
+    }
+
+    /**
+    Memory-map the file at `path` and return a new [`MmapChunker`] that
+    will chunk its contents with the supplied, already-compiled
+    [`bytes::Regex`](https://docs.rs/regex/latest/regex/bytes/struct.Regex.html).
+    */
+    pub fn with_regex<P: AsRef<Path>>(path: P, fence: Regex) -> Result<Self, RcErr> {
+        let file = File::open(path)?;
+        let map = if file.metadata()?.len() == 0 {
+            None
+        } else {
+            // SAFETY: The caller has been warned (in the type's docs)
+            // about modifying the file while it's mapped.
+            Some(unsafe { Mmap::map(&file)? })
+        };
+        Ok(Self {
+            map,
+            scanner: SliceScanner::new(fence),
+        })
+    }
+
+    /// Builder-pattern method for controlling what the chunker does with
+    /// the matched text. Default value is [`MatchDisposition::Drop`].
+    pub fn with_match(mut self, behavior: MatchDisposition) -> Self {
+        self.scanner.set_match(behavior);
+        self
+    }
+
+    /// Get a reference to the compiled regular expression used to
+    /// delimit chunks.
+    pub fn fence(&self) -> &Regex {
+        self.scanner.fence()
+    }
+
+    /// The entire contents of the mapped file.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.map.as_deref().unwrap_or_default()
+    }
+
+    /// Return the part of the file that hasn't been chunked yet.
+    pub fn remainder(&self) -> &[u8] {
+        &self.as_bytes()[self.scanner.offset()..]
+    }
+
+    /**
+    Return a [`SliceChunker`] over the part of the file that hasn't been
+    chunked yet, yielding chunks borrowed directly from the mapping.
+    It picks up where this chunker left off, with the same pattern and
+    [`MatchDisposition`].
+    */
+    pub fn chunks(&self) -> SliceChunker<'_> {
+        SliceChunker::from_scanner(self.as_bytes(), self.scanner.clone())
+    }
+}
+
+impl Iterator for MmapChunker {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let haystack = self.map.as_deref().unwrap_or_default();
+        let (start, end) = self.scanner.next_span(haystack)?;
+        Some(haystack[start..end].to_vec())
+    }
+}
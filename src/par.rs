@@ -0,0 +1,131 @@
+/*!
+Chunking an in-memory slice in parallel with `rayon`.
+*/
+use rayon::prelude::*;
+use regex::bytes::Regex;
+
+use crate::base::find_nonempty;
+
+// Below this many bytes per segment, splitting up the search isn't worth
+// the overhead.
+const MIN_SEGMENT_SIZE: usize = 64 * 1024;
+
+/* The matches found by searching one segment of the haystack. */
+struct Segment {
+    /* Where the search of this segment started. */
+    start: usize,
+    /* Spans of the matches that begin in this segment. */
+    matches: Vec<(usize, usize)>,
+    /* Where the search of the next segment should start: the end of the
+    last match, or `start` if there weren't any. */
+    end: usize,
+}
+
+impl Segment {
+    /*
+    Search `haystack` from `start`, collecting every match that begins
+    before `limit`. Matches may extend past `limit`.
+    */
+    fn scan(fence: &Regex, haystack: &[u8], start: usize, limit: usize) -> Segment {
+        let mut matches = Vec::new();
+        let mut end = start;
+        while let Some(m) = find_nonempty(fence, haystack, end) {
+            if m.start() >= limit {
+                break;
+            }
+            matches.push((m.start(), m.end()));
+            end = m.end();
+        }
+        Segment {
+            start,
+            matches,
+            end,
+        }
+    }
+}
+
+/**
+Chunk `data` on matches of `pattern`, searching different parts of it in
+parallel on `rayon`'s thread pool.
+
+The chunks are the same ones, in the same order, that a
+[`SliceChunker`](crate::SliceChunker) using the same pattern (and the
+default [`MatchDisposition::Drop`](crate::MatchDisposition::Drop))
+would yield.
+
+The data is divided into ranges that are searched independently. A match
+that begins in one range and extends into the next throws off the search
+of that next range; when this happens, that range is searched again from
+the end of the straddling match, so no chunk is duplicated or dropped.
+This means patterns whose matches can be very long relative to the size
+of the data being chunked can cause some work to be done twice. Also,
+the search of each range continues until it finds a match beyond the end
+of that range, so if delimiters are very sparse compared to the amount
+of data, there's less to be gained from searching in parallel.
+
+All the searching is done before this function returns; the returned
+iterator just hands out the chunks.
+
+```
+use rayon::prelude::*;
+use regex::bytes::Regex;
+use regex_chunker::{par_chunks, SliceChunker};
+
+let data = b"alpha, beta, gamma, delta".repeat(10_000);
+let fence = Regex::new(r",\s*").unwrap();
+
+let par: Vec<&[u8]> = par_chunks(&data, &fence).collect();
+let seq: Vec<&[u8]> = SliceChunker::with_regex(&data, fence).collect();
+assert_eq!(par, seq);
+```
+*/
+pub fn par_chunks<'a>(
+    data: &'a [u8],
+    pattern: &Regex,
+) -> impl IndexedParallelIterator<Item = &'a [u8]> + 'a {
+    let segment_size = (data.len() / (4 * rayon::current_num_threads())).max(MIN_SEGMENT_SIZE);
+    par_chunks_with_segment_size(data, pattern, segment_size)
+}
+
+pub(crate) fn par_chunks_with_segment_size<'a>(
+    data: &'a [u8],
+    pattern: &Regex,
+    segment_size: usize,
+) -> impl IndexedParallelIterator<Item = &'a [u8]> + 'a {
+    let n_segments = data.len().div_ceil(segment_size.max(1));
+    let bound = |n: usize| (n * segment_size).min(data.len());
+
+    let mut segments: Vec<Segment> = (0..n_segments)
+        .into_par_iter()
+        .map(|n| Segment::scan(pattern, data, bound(n), bound(n + 1)))
+        .collect();
+
+    /* Each segment's search is only right if it started where the
+    previous segment's search left off. If the previous segment's last
+    match ran past the start of this segment, search it again from the
+    end of that match. (If the previous segment's search ended _before_
+    this segment starts, its next match began at or after this
+    segment's start, so starting here finds the same matches.) */
+    for n in 1..segments.len() {
+        let prev_end = segments[n - 1].end;
+        if prev_end > segments[n].start {
+            segments[n] = Segment::scan(pattern, data, prev_end, bound(n + 1).max(prev_end));
+        }
+    }
+
+    let mut spans = Vec::new();
+    let mut chunk_start = 0;
+    for (start, end) in segments.into_iter().flat_map(|s| s.matches) {
+        spans.push((chunk_start, start));
+        chunk_start = end;
+    }
+    // Like the other chunkers, there's no empty chunk after a match at
+    // the very end.
+    if chunk_start < data.len() {
+        spans.push((chunk_start, data.len()));
+    }
+
+    spans
+        .into_par_iter()
+        .map(move |(start, end)| &data[start..end])
+}
@@ -0,0 +1,143 @@
+/*!
+Readers for feeding chunkers and for reading from a stream of chunks.
+*/
+use std::io::{BufRead, Read};
+
+use crate::RcErr;
+
+/**
+Wraps an iterator of chunks (like a [`ByteChunker`](crate::ByteChunker),
+or a [`CustomChunker`](crate::CustomChunker) whose adapter yields
+`Result<Vec<u8>, RcErr>`) and implements [`Read`] (and [`BufRead`]) over
+their concatenation, so a transformed stream can be handed to anything
+that reads.
+
+An error from the iterator is returned (converted to an `io::Error`)
+from the `read` call that reaches it; any bytes from earlier chunks will
+have been returned by earlier calls.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{ByteChunker, ChunkReader, TrimAdapter};
+use std::io::{Cursor, Read};
+
+let text = b"  one \n two\n   three  \n";
+let chunker = ByteChunker::new(Cursor::new(text), r"\n")?
+    .with_simple_adapter(TrimAdapter::default());
+
+let mut trimmed = String::new();
+ChunkReader::new(chunker).read_to_string(&mut trimmed).unwrap();
+assert_eq!(&trimmed, "onetwothree");
+# Ok::<(), RcErr>(())
+```
+*/
+#[derive(Debug)]
+pub struct ChunkReader<I> {
+    chunks: I,
+    /* The chunk being read from, and how much of it has been read. */
+    current: Vec<u8>,
+    pos: usize,
+}
+
+impl<I> ChunkReader<I>
+where
+    I: Iterator<Item = Result<Vec<u8>, RcErr>>,
+{
+    pub fn new(chunks: I) -> Self {
+        Self {
+            chunks,
+            current: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Consumes the `ChunkReader` and returns the wrapped iterator. Any
+    /// unread part of the current chunk is lost.
+    pub fn into_inner(self) -> I {
+        self.chunks
+    }
+}
+
+impl<I> Read for ChunkReader<I>
+where
+    I: Iterator<Item = Result<Vec<u8>, RcErr>>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<I> BufRead for ChunkReader<I>
+where
+    I: Iterator<Item = Result<Vec<u8>, RcErr>>,
+{
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        /* Skip over empty chunks, so an empty result means the end. */
+        while self.pos == self.current.len() {
+            match self.chunks.next() {
+                Some(Ok(chunk)) => {
+                    self.current = chunk;
+                    self.pos = 0;
+                }
+                Some(Err(e)) => return Err(e.into()),
+                None => break,
+            }
+        }
+        Ok(&self.current[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.current.len());
+    }
+}
+
+/**
+A reader over several sources, one after another, as if they were one
+continuous stream. It's what [`ByteChunker::from_sources`] reads from;
+see there for more.
+
+[`ByteChunker::from_sources`]: crate::ByteChunker::from_sources
+*/
+#[derive(Debug)]
+pub struct SourceChain<R> {
+    current: Option<R>,
+    rest: std::vec::IntoIter<R>,
+}
+
+impl<R> SourceChain<R> {
+    /// Return a new [`SourceChain`] that reads from each of `sources` in
+    /// turn.
+    pub fn new(sources: Vec<R>) -> Self {
+        let mut rest = sources.into_iter();
+        Self {
+            current: rest.next(),
+            rest,
+        }
+    }
+
+    /// Consumes the `SourceChain` and returns the sources that haven't
+    /// been read to the end yet, starting with the one being read from.
+    pub fn into_remaining(self) -> Vec<R> {
+        self.current.into_iter().chain(self.rest).collect()
+    }
+}
+
+impl<R: Read> Read for SourceChain<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        /* Only the last source running out is the end. */
+        while let Some(source) = self.current.as_mut() {
+            match source.read(buf)? {
+                0 => self.current = self.rest.next(),
+                n => return Ok(n),
+            }
+        }
+        Ok(0)
+    }
+}
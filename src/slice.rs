@@ -0,0 +1,167 @@
+/*!
+A chunker for data that's already in memory.
+*/
+use regex::bytes::Regex;
+
+use crate::{base::find_nonempty, ctrl::apply_disposition, MatchDisposition, RcErr};
+
+/**
+The `SliceChunker` works like a [`ByteChunker`](crate::ByteChunker), but
+on a byte slice that's already in memory instead of a reader. Rather than
+copying each chunk into a new `Vec<u8>`, it yields subslices of the
+original slice, so it never allocates.
+
+It treats matches and empty chunks the same way as a `ByteChunker`
+(including ignoring empty matches), and its chunks borrow from the
+wrapped slice rather than from the chunker, so they can outlive it.
+
+```
+use regex_chunker::{MatchDisposition, SliceChunker};
+
+# fn main() -> Result<(), regex_chunker::RcErr> {
+let text = b"One, two, three, four. Can I have a little more?";
+
+let chunks: Vec<&[u8]> = SliceChunker::new(text, "[ .,?]+")?
+    .with_match(MatchDisposition::Prepend)
+    .collect();
+
+assert_eq!(
+    &chunks,
+    &[&b"One"[..], b", two", b", three", b", four", b". Can",
+    b" I", b" have", b" a", b" little", b" more", b"?"]
+);
+# Ok(())
+# }
+```
+*/
+#[derive(Debug)]
+pub struct SliceChunker<'a> {
+    haystack: &'a [u8],
+    scanner: SliceScanner,
+}
+
+/*
+The state of chunking in-memory data, shared by the `SliceChunker` and
+the chunkers that own their data.
+*/
+#[derive(Clone, Debug)]
+pub(crate) struct SliceScanner {
+    fence: Regex,
+    match_dispo: MatchDisposition,
+    /* Position of the beginning of the next chunk. */
+    offset: usize,
+    /* Position from which to search for the next match. In Prepend mode,
+    the next chunk begins with a match, so this is past `offset`. */
+    scan_offset: usize,
+}
+
+impl SliceScanner {
+    pub(crate) fn new(fence: Regex) -> Self {
+        Self {
+            fence,
+            match_dispo: MatchDisposition::default(),
+            offset: 0,
+            scan_offset: 0,
+        }
+    }
+
+    pub(crate) fn set_match(&mut self, behavior: MatchDisposition) {
+        self.match_dispo = behavior;
+        // In Prepend mode, the match that ended the last chunk begins
+        // the next one, and scan_offset keeps us from finding it again.
+        // In Append mode it stays, to become part of the next chunk; in
+        // Drop mode it's dropped. Otherwise the two are the same.
+        if let MatchDisposition::Drop = behavior {
+            self.offset = self.scan_offset;
+        }
+    }
+
+    pub(crate) fn fence(&self) -> &Regex {
+        &self.fence
+    }
+
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /*
+    Return the span in `haystack` of the next chunk. `haystack` must be
+    the same every call.
+    */
+    pub(crate) fn next_span(&mut self, haystack: &[u8]) -> Option<(usize, usize)> {
+        if self.offset >= haystack.len() {
+            return None;
+        }
+
+        let m = match find_nonempty(&self.fence, haystack, self.scan_offset) {
+            Some(m) => m,
+            None => {
+                let start = self.offset;
+                self.offset = haystack.len();
+                return Some((start, haystack.len()));
+            }
+        };
+
+        let start = self.offset;
+        let split = apply_disposition(self.match_dispo, m.start() - start, m.end() - start);
+        self.offset = start + split.consumed;
+        self.scan_offset = self.offset + split.scan_offset;
+
+        Some((start, start + split.len))
+    }
+}
+
+impl<'a> SliceChunker<'a> {
+    /**
+    Return a new [`SliceChunker`] over the given slice that will chunk it
+    by delimiting it with the supplied regex pattern.
+    */
+    pub fn new(haystack: &'a [u8], delimiter: &str) -> Result<Self, RcErr> {
+        let fence = Regex::new(delimiter)?;
+        Ok(Self::with_regex(haystack, fence))
+    }
+
+    /**
+    Return a new [`SliceChunker`] over the given slice that will chunk it
+    with the supplied, already-compiled
+    [`bytes::Regex`](https://docs.rs/regex/latest/regex/bytes/struct.Regex.html).
+    */
+    pub fn with_regex(haystack: &'a [u8], fence: Regex) -> Self {
+        Self {
+            haystack,
+            scanner: SliceScanner::new(fence),
+        }
+    }
+
+    #[cfg(any(feature = "mmap", docsrs))]
+    pub(crate) fn from_scanner(haystack: &'a [u8], scanner: SliceScanner) -> Self {
+        Self { haystack, scanner }
+    }
+
+    /// Builder-pattern method for controlling what the chunker does with
+    /// the matched text. Default value is [`MatchDisposition::Drop`].
+    pub fn with_match(mut self, behavior: MatchDisposition) -> Self {
+        self.scanner.set_match(behavior);
+        self
+    }
+
+    /// Get a reference to the compiled regular expression used to
+    /// delimit chunks.
+    pub fn fence(&self) -> &Regex {
+        self.scanner.fence()
+    }
+
+    /// Return the part of the slice that hasn't been chunked yet.
+    pub fn remainder(&self) -> &'a [u8] {
+        &self.haystack[self.scanner.offset()..]
+    }
+}
+
+impl<'a> Iterator for SliceChunker<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, end) = self.scanner.next_span(self.haystack)?;
+        Some(&self.haystack[start..end])
+    }
+}
@@ -7,58 +7,154 @@ types and implement
 */
 
 use std::{
+    fmt::{Debug, Formatter},
+    io::Read,
     pin::Pin,
     task::{Context, Poll},
 };
 
-use bytes::{Buf, BytesMut};
+use bytes::{Buf, Bytes, BytesMut};
 use regex::bytes::Regex;
-use tokio::io::AsyncRead;
-use tokio_stream::Stream;
+use tokio::{io::AsyncRead, sync::mpsc};
+use tokio_stream::{wrappers::ReceiverStream, Stream};
 use tokio_util::codec::{Decoder, FramedRead};
 
-use crate::{Adapter, MatchDisposition, RcErr};
+use crate::{
+    base::{find_nonempty, has_look_around, max_match_len},
+    ctrl::{apply_disposition, ErrorStatus},
+    Adapter, AdapterAction, ChunkMeta, ErrorResponse, MatchDisposition, RcErr, SimpleAdapter,
+    TrailingPolicy,
+};
 
 struct ByteDecoder {
     fence: Regex,
     match_dispo: MatchDisposition,
     scan_offset: usize,
+    skip_empty: bool,
+    max_match_len: Option<usize>,
+    /* Whether the fence has assertions that look at the bytes next to a
+    match, which can change their minds when more bytes arrive. */
+    look_around: bool,
+    max_chunk_size: Option<usize>,
+    trailing: TrailingPolicy,
+    /* The read buffer only ever holds what hasn't been returned yet, so
+    to know where chunks were in the source, we keep track of the
+    position of its front, and of the span of the latest chunk. */
+    buff_offset: u64,
+    last_span: (u64, u64),
+    /* The delimiter after the latest chunk, for a `CustomChunker` whose
+    adapter wants it. Only kept if `track_delims` is set. */
+    track_delims: bool,
+    last_delim: Option<Vec<u8>>,
 }
 
-impl Decoder for ByteDecoder {
-    type Item = Vec<u8>;
-    type Error = RcErr;
+impl ByteDecoder {
+    /*
+    Remove `consumed` bytes from the front of `src`, returning the first
+    `len` of them as a chunk and noting where it was in the source.
+    */
+    fn take_front(&mut self, src: &mut BytesMut, len: usize, consumed: usize) -> BytesMut {
+        let start = self.buff_offset;
+        self.last_span = (start, start + len as u64);
+        self.buff_offset += consumed as u64;
+        let mut chunk = src.split_to(consumed);
+        chunk.truncate(len);
+        chunk
+    }
 
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let (start, end) = match self.fence.find_at(src.as_ref(), self.scan_offset) {
-            Some(m) => (m.start(), m.end()),
-            None => return Ok(None),
-        };
-        let length = end - start;
+    /* Throw away `n` bytes from the front of `src`. */
+    fn discard(&mut self, src: &mut BytesMut, n: usize) {
+        src.advance(n);
+        self.buff_offset += n as u64;
+    }
+
+    /*
+    Split the next chunk off the front of `src`, if there's a match to
+    delimit it. Unless we're at the end of the source, a match running up
+    to the end of `src` might get longer (or, if it has assertions, not
+    match at all) when more bytes arrive, so it's not trusted unless it's
+    already as long as a match can be and has no assertions.
+    */
+    fn next_chunk(&mut self, src: &mut BytesMut, at_eof: bool) -> Result<Option<BytesMut>, RcErr> {
+        loop {
+            let found = find_nonempty(&self.fence, src.as_ref(), self.scan_offset)
+                .map(|m| (m.start(), m.end()));
 
-        let new_buff = match self.match_dispo {
-            MatchDisposition::Drop => {
-                let new_buff: Vec<u8> = src.split_to(start).into();
-                src.advance(length);
-                new_buff
+            if let Some(max) = self.max_chunk_size {
+                let unmatched = match found {
+                    Some((start, _)) => start,
+                    None => src.len(),
+                };
+                if unmatched > max {
+                    // Throw away the oversize chunk so that, if the
+                    // chunker keeps going, it has a chance of recovering.
+                    let size = src.len();
+                    self.discard(src, max);
+                    self.scan_offset = self.scan_offset.saturating_sub(max);
+                    return Err(RcErr::ChunkTooLarge { size });
+                }
             }
-            MatchDisposition::Append => src.split_to(end).into(),
-            MatchDisposition::Prepend => {
-                self.scan_offset = length;
-                src.split_to(start).into()
+
+            let (start, end) = match found {
+                Some(span) => span,
+                None => return Ok(None),
+            };
+            let length = end - start;
+            if !at_eof
+                && end == src.len()
+                && (self.look_around || self.max_match_len != Some(length))
+            {
+                return Ok(None);
             }
-        };
 
-        Ok(Some(new_buff))
+            if self.track_delims {
+                let delim = self.last_delim.get_or_insert_with(Vec::new);
+                delim.clear();
+                delim.extend_from_slice(&src[start..end]);
+            }
+
+            let split = apply_disposition(self.match_dispo, start, end);
+            self.scan_offset = split.scan_offset;
+            let new_buff = self.take_front(src, split.len, split.consumed);
+
+            if self.skip_empty && new_buff.is_empty() {
+                continue;
+            }
+            return Ok(Some(new_buff));
+        }
+    }
+}
+
+impl Decoder for ByteDecoder {
+    type Item = BytesMut;
+    type Error = RcErr;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.next_chunk(src, false)
     }
 
     fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        if let Some(v) = self.decode(src)? {
+        if let Some(v) = self.next_chunk(src, true)? {
             Ok(Some(v))
         } else if src.is_empty() {
             Ok(None)
         } else {
-            Ok(Some(src.split().into()))
+            let size = src.len();
+            match self.trailing {
+                TrailingPolicy::Emit => {
+                    self.last_delim = None;
+                    Ok(Some(self.take_front(src, size, size)))
+                }
+                TrailingPolicy::Discard => {
+                    self.discard(src, size);
+                    Ok(None)
+                }
+                TrailingPolicy::Error => {
+                    let tail = src.to_vec();
+                    self.discard(src, size);
+                    Err(RcErr::UnterminatedTail(tail))
+                }
+            }
         }
     }
 }
@@ -71,12 +167,20 @@ and implements the
 [`Stream`](https://docs.rs/futures-core/0.3.28/futures_core/stream/trait.Stream.html)
 trait.
 
-This async version of the base `ByteChunker` is less flexible in how it
-handles errors; you'll get errors when Tokio's underlying black magic
-returns them.
+Errors are handled according to [`ByteChunker::on_error`], as closely
+to the way the base `ByteChunker` handles them as Tokio's underlying
+black magic allows.
 */
 pub struct ByteChunker<R: AsyncRead> {
     freader: FramedRead<R, ByteDecoder>,
+    error_status: ErrorStatus,
+    /* After it returns an error, the `FramedRead` returns `None` once
+    before it will try to read again. If we're going to keep going after
+    an error, we need to ignore that `None`. */
+    recovering: bool,
+    /* Number of chunks (and bytes in them) successfully yielded. */
+    chunks_emitted: u64,
+    bytes_emitted: u64,
 }
 
 impl<R: AsyncRead> ByteChunker<R> {
@@ -85,41 +189,423 @@ impl<R: AsyncRead> ByteChunker<R> {
     /// expression pattern.
     pub fn new(source: R, pattern: &str) -> Result<Self, RcErr> {
         let fence = Regex::new(pattern)?;
+        let mut chunker = Self::with_regex(source, fence);
+        let decoder = chunker.freader.decoder_mut();
+        decoder.max_match_len = max_match_len(pattern);
+        decoder.look_around = has_look_around(pattern);
+        Ok(chunker)
+    }
+
+    /// Return a new [`ByteChunker`] wrapping the given async reader that
+    /// will chunk its output with the supplied, already-compiled regular
+    /// expression.
+    pub fn with_regex(source: R, fence: Regex) -> Self {
         let decoder = ByteDecoder {
             fence,
-            //error_status: ErrorStatus::Ok,
             match_dispo: MatchDisposition::default(),
             scan_offset: 0,
+            skip_empty: false,
+            max_match_len: None,
+            look_around: false,
+            max_chunk_size: None,
+            trailing: TrailingPolicy::default(),
+            buff_offset: 0,
+            last_span: (0, 0),
+            track_delims: false,
+            last_delim: None,
         };
 
         let freader = FramedRead::new(source, decoder);
-        Ok(Self { freader })
+        Self {
+            freader,
+            error_status: ErrorStatus::Ok,
+            recovering: false,
+            chunks_emitted: 0,
+            bytes_emitted: 0,
+        }
+    }
+
+    /// Get a reference to the compiled regular expression used to
+    /// delimit chunks.
+    pub fn fence(&self) -> &Regex {
+        &self.freader.decoder().fence
+    }
+
+    /// Get a reference to the wrapped `AsyncRead`er.
+    pub fn get_ref(&self) -> &R {
+        self.freader.get_ref()
+    }
+
+    /// Get a mutable reference to the wrapped `AsyncRead`er, for
+    /// configuring it. Reading from it directly will throw off the
+    /// chunker; see [`crate::ByteChunker::get_mut`].
+    pub fn get_mut(&mut self) -> &mut R {
+        self.freader.get_mut()
+    }
+
+    /// Consumes the [`ByteChunker`] and returns its wrapped `AsyncRead`er.
+    /// Any data that has been read from it but not yet returned is lost;
+    /// to retrieve that too, see [`ByteChunker::into_innards`].
+    pub fn into_inner(self) -> R {
+        self.freader.into_inner()
+    }
+
+    /// Consumes the [`ByteChunker`] and returns its wrapped `AsyncRead`er,
+    /// as well as any data that has been read from it but not yet
+    /// returned. This is useful for handing the source off to something
+    /// else partway through. See [`crate::ByteChunker::into_innards`].
+    pub fn into_innards(self) -> (R, BytesMut) {
+        let parts = self.freader.into_parts();
+        (parts.io, parts.read_buf)
+    }
+
+    /// The number of chunks this chunker has successfully yielded. See
+    /// [`crate::ByteChunker::chunk_count`].
+    pub fn chunk_count(&self) -> u64 {
+        self.chunks_emitted
+    }
+
+    /// The total number of bytes in the chunks counted by
+    /// [`ByteChunker::chunk_count`]. See
+    /// [`crate::ByteChunker::byte_count`].
+    pub fn byte_count(&self) -> u64 {
+        self.bytes_emitted
+    }
+
+    /// The number of bytes read from the source but not yet returned in
+    /// a chunk (or dropped as a delimiter). See
+    /// [`crate::ByteChunker::buffered_len`].
+    pub fn buffered_len(&self) -> usize {
+        self.freader.read_buffer().len()
+    }
+
+    /// Where, in the bytes counted by [`ByteChunker::buffered_len`], the
+    /// chunker will start looking for the next delimiter. See
+    /// [`crate::ByteChunker::scan_offset`].
+    pub fn scan_offset(&self) -> usize {
+        self.freader.decoder().scan_offset
     }
 
+    /// Combine this `ByteChunker` with an [`Adapter`] to make a
+    /// [`CustomChunker`] that yields whatever the `Adapter` produces.
     pub fn with_adapter<A>(self, adapter: A) -> CustomChunker<R, A> {
-        CustomChunker {
-            chunker: self,
-            adapter,
-        }
+        (self, adapter).into()
+    }
+
+    /// Combine this `ByteChunker` with a [`SimpleAdapter`] to make a
+    /// [`SimpleCustomChunker`].
+    pub fn with_simple_adapter<A>(self, adapter: A) -> SimpleCustomChunker<R, A> {
+        (self, adapter).into()
+    }
+
+    /// Builder-pattern method for controlling how the chunker behaves when
+    /// encountering an error in the course of its operation. Default value
+    /// is [`ErrorResponse::Halt`]; see
+    /// [`crate::ByteChunker::on_error`].
+    pub fn on_error(mut self, response: ErrorResponse) -> Self {
+        self.error_status = match response {
+            ErrorResponse::Halt => {
+                if self.error_status != ErrorStatus::Errored {
+                    ErrorStatus::Ok
+                } else {
+                    ErrorStatus::Errored
+                }
+            }
+            ErrorResponse::Continue => ErrorStatus::Continue,
+            ErrorResponse::Ignore => ErrorStatus::Ignore,
+        };
+        self
+    }
+
+    /// Builder-pattern method for setting the initial capacity of the
+    /// buffer the source is read into. (The buffer grows as necessary to
+    /// hold chunks bigger than this.) The default is 8 KiB; see
+    /// [`FramedRead::with_capacity`](https://docs.rs/tokio-util/latest/tokio_util/codec/struct.FramedRead.html#method.with_capacity).
+    pub fn with_buffer_size(self, size: usize) -> Self {
+        let parts = self.freader.into_parts();
+        let mut freader = FramedRead::with_capacity(parts.io, parts.codec, size);
+        freader.read_buffer_mut().extend_from_slice(&parts.read_buf);
+        Self { freader, ..self }
+    }
+
+    /// Builder-pattern method for capping the number of bytes the
+    /// chunker will buffer while looking for a delimiter. If more than
+    /// `size` bytes come in without a match, the stream yields an
+    /// [`RcErr::ChunkTooLarge`] error (and, if it's been told to continue
+    /// after errors with [`ByteChunker::on_error`], discards the first
+    /// `size` bytes). By default there's no limit. See
+    /// [`crate::ByteChunker::with_max_chunk_size`].
+    pub fn with_max_chunk_size(mut self, size: usize) -> Self {
+        self.freader.decoder_mut().max_chunk_size = Some(size);
+        self
+    }
+
+    /// Another name for [`ByteChunker::with_max_chunk_size`], for those
+    /// used to the `max_frame_length` of `tokio-util`'s
+    /// [`LengthDelimitedCodec`](https://docs.rs/tokio-util/latest/tokio_util/codec/length_delimited/struct.Builder.html#method.max_frame_length).
+    /// A source that never matches the delimiter gets an error once
+    /// more than `length` bytes have been read from it, instead of
+    /// growing the read buffer without limit.
+    pub fn with_max_frame_length(self, length: usize) -> Self {
+        self.with_max_chunk_size(length)
+    }
+
+    /// Builder-pattern method for controlling what the chunker does with
+    /// any data left at the end of its source after the last delimiter.
+    /// Default value is [`TrailingPolicy::Emit`]; see
+    /// [`crate::ByteChunker::with_trailing`].
+    pub fn with_trailing(mut self, policy: TrailingPolicy) -> Self {
+        self.freader.decoder_mut().trailing = policy;
+        self
+    }
+
+    /// Builder-pattern method for suppressing empty chunks. See
+    /// [`crate::ByteChunker::skip_empty`].
+    pub fn skip_empty(mut self) -> Self {
+        self.freader.decoder_mut().skip_empty = true;
+        self
     }
 
     /// Builder-pattern for controlling what the chunker does with the
-    /// matched text; default value is [`MatchDisposition::Drop`].
+    /// matched text; default value is [`MatchDisposition::Drop`]. This
+    /// can be changed partway through, as with
+    /// [`crate::ByteChunker::with_match`].
     pub fn with_match(mut self, behavior: MatchDisposition) -> Self {
         let d = self.freader.decoder_mut();
         d.match_dispo = behavior;
-        if matches!(behavior, MatchDisposition::Drop | MatchDisposition::Append) {
-            d.scan_offset = 0;
+        // Coming from Prepend mode, the match that ended the last chunk
+        // is still at the front of the buffer; Drop mode drops it, and
+        // Append mode skips over it once, to begin the next chunk.
+        if let MatchDisposition::Drop = behavior {
+            let n = std::mem::take(&mut d.scan_offset);
+            d.buff_offset += n as u64;
+            self.freader.read_buffer_mut().advance(n);
         }
         self
     }
 }
 
+/* How much of the read buffer `Debug` shows. */
+const DEBUG_PREVIEW_LEN: usize = 64;
+
+impl<R: AsyncRead> Debug for ByteChunker<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let d = self.freader.decoder();
+        let buff = self.freader.read_buffer();
+        let preview = &buff[..buff.len().min(DEBUG_PREVIEW_LEN)];
+        f.debug_struct("ByteChunker")
+            .field("source", &std::any::type_name::<R>())
+            .field("fence", &d.fence)
+            .field("match_dispo", &d.match_dispo)
+            .field("scan_offset", &d.scan_offset)
+            .field("skip_empty", &d.skip_empty)
+            .field("max_match_len", &d.max_match_len)
+            .field("look_around", &d.look_around)
+            .field("max_chunk_size", &d.max_chunk_size)
+            .field("trailing", &d.trailing)
+            .field("buff_offset", &d.buff_offset)
+            .field("last_span", &d.last_span)
+            .field("track_delims", &d.track_delims)
+            .field("buffered", &buff.len())
+            .field("buffer_preview", &String::from_utf8_lossy(preview))
+            .field("error_status", &self.error_status)
+            .field("recovering", &self.recovering)
+            .field("chunks_emitted", &self.chunks_emitted)
+            .field("bytes_emitted", &self.bytes_emitted)
+            .finish()
+    }
+}
+
+impl<A: AsyncRead + Unpin> ByteChunker<A> {
+    /* Get the next frame from the decoder, handling errors according to
+    the chunker's `ErrorResponse` and keeping count of what's yielded. This
+    is shared by the `Stream` impls here and on `BytesChunker`, which only
+    differ in what they turn the frame into. */
+    fn poll_frame(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<BytesMut, RcErr>>> {
+        loop {
+            if self.error_status == ErrorStatus::Errored {
+                return Poll::Ready(None);
+            }
+
+            match Pin::new(&mut self.freader).poll_next(cx) {
+                Poll::Ready(None) if self.recovering => {
+                    self.recovering = false;
+                }
+                Poll::Ready(Some(Err(e))) => match self.error_status {
+                    ErrorStatus::Ok | ErrorStatus::Errored => {
+                        self.error_status = ErrorStatus::Errored;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    ErrorStatus::Continue => {
+                        self.recovering = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    ErrorStatus::Ignore => {
+                        self.recovering = true;
+                    }
+                },
+                Poll::Ready(Some(Ok(v))) => {
+                    self.chunks_emitted += 1;
+                    self.bytes_emitted += v.len() as u64;
+                    return Poll::Ready(Some(Ok(v)));
+                }
+                p => return p,
+            }
+        }
+    }
+}
+
 impl<A: AsyncRead + Unpin> Stream for ByteChunker<A> {
     type Item = Result<Vec<u8>, RcErr>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        Pin::new(&mut self.freader).poll_next(cx)
+        self.poll_frame(cx)
+            .map(|opt| opt.map(|res| res.map(Vec::from)))
+    }
+}
+
+/**
+A [`ByteChunker`] that yields
+[`Bytes`](https://docs.rs/bytes/latest/bytes/struct.Bytes.html)
+instead of `Vec<u8>`s. Each chunk is split off the front of the read
+buffer and frozen, so it shares that buffer's allocation rather than
+being copied out of it. Made with [`ByteChunker::into_bytes`].
+
+Holding on to a `Bytes` keeps (at least part of) the buffer it came
+from alive, so this is a good fit for chunks that get passed along and
+dropped fairly promptly, and maybe not for chunks that get stored away.
+
+```rust
+# use std::error::Error;
+# #[tokio::main]
+# async fn main() -> Result<(), Box<dyn Error>> {
+    use regex_chunker::stream::ByteChunker;
+    use tokio_stream::StreamExt;
+    use std::io::Cursor;
+
+    let text = b"One, two, three.";
+    let chunks: Vec<_> = ByteChunker::new(Cursor::new(text), "[ ,.]+")?
+        .into_bytes()
+        .map(|res| res.unwrap())
+        .collect().await;
+
+    assert_eq!(&chunks, &["One", "two", "three"]);
+#   Ok(()) }
+```
+*/
+pub struct BytesChunker<R: AsyncRead> {
+    chunker: ByteChunker<R>,
+}
+
+impl<R: AsyncRead> ByteChunker<R> {
+    /// Convert this chunker into a [`BytesChunker`], which yields
+    /// [`Bytes`] without copying them out of its read buffer.
+    pub fn into_bytes(self) -> BytesChunker<R> {
+        BytesChunker { chunker: self }
+    }
+}
+
+impl<R: AsyncRead> BytesChunker<R> {
+    /// Consumes the [`BytesChunker`] and returns the underlying
+    /// [`ByteChunker`].
+    pub fn into_inner(self) -> ByteChunker<R> {
+        self.chunker
+    }
+
+    /// Get a reference to the underlying [`ByteChunker`].
+    pub fn get_ref(&self) -> &ByteChunker<R> {
+        &self.chunker
+    }
+}
+
+impl<R: AsyncRead> From<ByteChunker<R>> for BytesChunker<R> {
+    fn from(chunker: ByteChunker<R>) -> Self {
+        chunker.into_bytes()
+    }
+}
+
+impl<A: AsyncRead + Unpin> Stream for BytesChunker<A> {
+    type Item = Result<Bytes, RcErr>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.chunker
+            .poll_frame(cx)
+            .map(|opt| opt.map(|res| res.map(BytesMut::freeze)))
+    }
+}
+
+/**
+The async analog to the base crate's
+[`OffsetChunker`](crate::OffsetChunker): a [`ByteChunker`] that yields
+[`ChunkMeta`]s, reporting where in the source each chunk was found.
+Made with [`ByteChunker::with_offsets`].
+
+```rust
+# use std::error::Error;
+# #[tokio::main]
+# async fn main() -> Result<(), Box<dyn Error>> {
+    use regex_chunker::{stream::ByteChunker, ChunkMeta};
+    use tokio_stream::StreamExt;
+    use std::io::Cursor;
+
+    let text = b"one, two, three";
+    let chunks: Vec<ChunkMeta> = ByteChunker::new(Cursor::new(text), ", ")?
+        .with_offsets()
+        .map(|res| res.unwrap())
+        .collect().await;
+
+    assert_eq!((chunks[1].start, chunks[1].end), (5, 8));
+    assert_eq!(&text[5..8], chunks[1].bytes.as_slice());
+#   Ok(()) }
+```
+*/
+pub struct OffsetChunker<R: AsyncRead> {
+    chunker: ByteChunker<R>,
+}
+
+impl<R: AsyncRead> ByteChunker<R> {
+    /// Convert this chunker into an [`OffsetChunker`], which yields each
+    /// chunk along with its position in the source stream.
+    pub fn with_offsets(self) -> OffsetChunker<R> {
+        OffsetChunker { chunker: self }
+    }
+}
+
+impl<R: AsyncRead> OffsetChunker<R> {
+    /// Consumes the [`OffsetChunker`] and returns the underlying
+    /// [`ByteChunker`].
+    pub fn into_inner(self) -> ByteChunker<R> {
+        self.chunker
+    }
+
+    /// Get a reference to the underlying [`ByteChunker`].
+    pub fn get_ref(&self) -> &ByteChunker<R> {
+        &self.chunker
+    }
+}
+
+impl<R: AsyncRead> From<ByteChunker<R>> for OffsetChunker<R> {
+    fn from(chunker: ByteChunker<R>) -> Self {
+        chunker.with_offsets()
+    }
+}
+
+impl<A: AsyncRead + Unpin> Stream for OffsetChunker<A> {
+    type Item = Result<ChunkMeta, RcErr>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.chunker.poll_frame(cx).map(|opt| {
+            opt.map(|res| {
+                res.map(|v| {
+                    let (start, end) = self.chunker.freader.decoder().last_span;
+                    ChunkMeta {
+                        start,
+                        end,
+                        bytes: v.into(),
+                    }
+                })
+            })
+        })
     }
 }
 
@@ -165,15 +651,28 @@ pub struct CustomChunker<R: AsyncRead, A> {
 impl<R: AsyncRead, A> CustomChunker<R, A> {
     /// Consumes the [`CustomChunker`] and returns the underlying
     /// [`ByteChunker`] and [`Adapter`].
-    pub fn into_innards(self) -> (ByteChunker<R>, A) {
+    pub fn into_innards(mut self) -> (ByteChunker<R>, A) {
+        let decoder = self.chunker.freader.decoder_mut();
+        decoder.track_delims = false;
+        decoder.last_delim = None;
         (self.chunker, self.adapter)
     }
 
     /// Get a reference to the underlying [`Adapter`].
-    pub fn get_adapter(&self) -> &A { &self.adapter }
+    pub fn get_adapter(&self) -> &A {
+        &self.adapter
+    }
 
     /// Get a mutable reference to the underlying [`Adapter`].
-    pub fn get_adapter_mut(&mut self) -> &mut A { &mut self.adapter }
+    pub fn get_adapter_mut(&mut self) -> &mut A {
+        &mut self.adapter
+    }
+}
+
+impl<R: AsyncRead, A> From<(ByteChunker<R>, A)> for CustomChunker<R, A> {
+    fn from((chunker, adapter): (ByteChunker<R>, A)) -> Self {
+        Self { chunker, adapter }
+    }
 }
 
 impl<R: AsyncRead, A> Unpin for CustomChunker<R, A> {}
@@ -186,14 +685,162 @@ where
     type Item = A::Item;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let p = Pin::new(&mut self.chunker).poll_next(cx);
-        match p {
+        let this = &mut *self;
+        // Copying out each delimiter costs something, so it's only done
+        // for adapters that will look at them.
+        if this.adapter.wants_delimiters() {
+            this.chunker.freader.decoder_mut().track_delims = true;
+        }
+        loop {
+            let x = match Pin::new(&mut this.chunker).poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(x) => x,
+            };
+            let delim = match x {
+                Some(Ok(_)) => this.chunker.freader.decoder().last_delim.as_deref(),
+                _ => None,
+            };
+            match this.adapter.adapt_with_delim(x, delim) {
+                AdapterAction::Emit(item) => return Poll::Ready(Some(item)),
+                AdapterAction::Skip => continue,
+                AdapterAction::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/**
+The async analog to the base crate's
+[`SimpleCustomChunker`](crate::SimpleCustomChunker). It takes a
+[`SimpleAdapter`], passing errors and the end of the stream through
+untouched and only adapting successfully-read chunks.
+
+```rust
+# use std::error::Error;
+# #[tokio::main]
+# async fn main() -> Result<(), Box<dyn Error>> {
+    use regex_chunker::{stream::ByteChunker, SimpleAdapter};
+    use tokio_stream::StreamExt;
+    use std::io::Cursor;
+
+    struct LossyStringAdapter {}
+
+    impl SimpleAdapter for LossyStringAdapter {
+        type Item = String;
+
+        fn adapt(&mut self, v: Vec<u8>) -> Self::Item {
+            String::from_utf8_lossy(&v).into()
+        }
+    }
+
+    let text = b"One, two, thr\xffee four.";
+    let c = Cursor::new(text);
+
+    let chunks: Vec<_> = ByteChunker::new(c, "[ .,?]+")?
+        .with_simple_adapter(LossyStringAdapter {})
+        .map(|res| res.unwrap())
+        .collect().await;
+
+    assert_eq!(&chunks, &["One", "two", "thr\u{FFFD}ee", "four"]);
+#   Ok(()) }
+```
+*/
+pub struct SimpleCustomChunker<R: AsyncRead, A> {
+    chunker: ByteChunker<R>,
+    adapter: A,
+}
+
+impl<R: AsyncRead, A> SimpleCustomChunker<R, A> {
+    /// Consumes the [`SimpleCustomChunker`] and returns the underlying
+    /// [`ByteChunker`] and [`SimpleAdapter`].
+    pub fn into_innards(self) -> (ByteChunker<R>, A) {
+        (self.chunker, self.adapter)
+    }
+
+    /// Get a reference to the underlying [`SimpleAdapter`].
+    pub fn get_adapter(&self) -> &A {
+        &self.adapter
+    }
+
+    /// Get a mutable reference to the underlying [`SimpleAdapter`].
+    pub fn get_adapter_mut(&mut self) -> &mut A {
+        &mut self.adapter
+    }
+}
+
+impl<R: AsyncRead, A> From<(ByteChunker<R>, A)> for SimpleCustomChunker<R, A> {
+    fn from((chunker, adapter): (ByteChunker<R>, A)) -> Self {
+        Self { chunker, adapter }
+    }
+}
+
+impl<R: AsyncRead, A> Unpin for SimpleCustomChunker<R, A> {}
+
+impl<R, A> Stream for SimpleCustomChunker<R, A>
+where
+    R: AsyncRead + Unpin,
+    A: SimpleAdapter,
+{
+    type Item = Result<A::Item, RcErr>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match Pin::new(&mut self.chunker).poll_next(cx) {
             Poll::Pending => Poll::Pending,
-            Poll::Ready(x) => Poll::Ready(self.adapter.adapt(x)),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
+            Poll::Ready(Some(Ok(v))) => Poll::Ready(Some(Ok(self.adapter.adapt(v)))),
         }
     }
 }
 
+/* How many chunks `into_stream()` lets pile up ahead of the consumer. */
+const BRIDGE_CAPACITY: usize = 16;
+
+impl<R: Read + Send + 'static> crate::ByteChunker<R> {
+    /**
+    Consume this (synchronous) chunker and present its output as a
+    [`Stream`], for when the source can only be read by blocking, but
+    the chunks are wanted in async code. The chunker is moved onto
+    Tokio's blocking thread pool with
+    [`spawn_blocking`](https://docs.rs/tokio/latest/tokio/task/fn.spawn_blocking.html),
+    so its reads never hold up the runtime's worker threads, and passes
+    its chunks back over a channel.
+
+    The chunker runs at most 16 chunks ahead of the stream's consumer,
+    and stops (after its current read) if the stream is dropped.
+
+    This must be called from within a Tokio runtime.
+
+    ```
+    use regex_chunker::ByteChunker;
+    use std::io::Cursor;
+    use tokio_stream::StreamExt;
+
+    # #[tokio::main]
+    # async fn main() -> Result<(), regex_chunker::RcErr> {
+    let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new("one two three"), " ")?
+        .into_stream()
+        .collect::<Result<_, _>>()
+        .await?;
+
+    assert_eq!(&chunks, &[&b"one"[..], b"two", b"three"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn into_stream(self) -> ReceiverStream<Result<Vec<u8>, RcErr>> {
+        let (tx, rx) = mpsc::channel(BRIDGE_CAPACITY);
+        tokio::task::spawn_blocking(move || {
+            for res in self {
+                if tx.blocking_send(res).is_err() {
+                    break;
+                }
+            }
+        });
+        ReceiverStream::new(rx)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +871,286 @@ mod tests {
         ref_slice_cmp(&vec_vec, &slice_vec);
     }
 
+    #[tokio::test]
+    async fn bytes_async() {
+        let byte_vec = std::fs::read(TEST_PATH).unwrap();
+        let re = Regex::new(TEST_PATT).unwrap();
+        let slice_vec = chunk_vec(&re, &byte_vec, MatchDisposition::Drop);
+
+        let f = File::open(TEST_PATH).await.unwrap();
+        let chunker = ByteChunker::new(f, TEST_PATT).unwrap().into_bytes();
+        let bytes_vec: Vec<Bytes> = chunker.map(|res| res.unwrap()).collect().await;
+
+        ref_slice_cmp(&bytes_vec, &slice_vec);
+    }
+
+    #[tokio::test]
+    async fn offsets_async() {
+        let byte_vec = std::fs::read(PASSWD_PATH).unwrap();
+        for dispo in [
+            MatchDisposition::Drop,
+            MatchDisposition::Append,
+            MatchDisposition::Prepend,
+        ] {
+            let f = File::open(PASSWD_PATH).await.unwrap();
+            let chunker = ByteChunker::new(f, PASSWD_PATT)
+                .unwrap()
+                .with_buffer_size(64)
+                .with_match(dispo)
+                .with_offsets();
+            let metas: Vec<ChunkMeta> = chunker.map(|res| res.unwrap()).collect().await;
+
+            let expected: Vec<ChunkMeta> = crate::ByteChunker::new(&byte_vec[..], PASSWD_PATT)
+                .unwrap()
+                .with_match(dispo)
+                .with_offsets()
+                .map(|res| res.unwrap())
+                .collect();
+            assert_eq!(metas, expected);
+        }
+
+        /* Dropped delimiters, skipped empty chunks, a switch out of
+        Prepend mode, and the trailing data are all accounted for. */
+        let text = b"a,,b,c,d,tail";
+        let mut chunker = ByteChunker::new(std::io::Cursor::new(text), ",")
+            .unwrap()
+            .skip_empty()
+            .with_match(MatchDisposition::Prepend)
+            .with_offsets();
+        let first = chunker.next().await.unwrap().unwrap();
+        assert_eq!((first.start, first.end), (0, 1));
+        let chunker = chunker
+            .into_inner()
+            .with_match(MatchDisposition::Drop)
+            .with_offsets();
+        let metas: Vec<ChunkMeta> = chunker.map(|res| res.unwrap()).collect().await;
+        for meta in metas.iter() {
+            assert_eq!(
+                &text[meta.start as usize..meta.end as usize],
+                meta.bytes.as_slice()
+            );
+        }
+        let spans: Vec<(u64, u64)> = metas.iter().map(|m| (m.start, m.end)).collect();
+        assert_eq!(spans, vec![(3, 4), (5, 6), (7, 8), (9, 13)]);
+    }
+
+    #[tokio::test]
+    async fn zero_buffer_size_async() {
+        let chunker = ByteChunker::new(std::io::Cursor::new(b"a,b,c"), ",")
+            .unwrap()
+            .with_buffer_size(0);
+        let chunks: Vec<Vec<u8>> = chunker.map(|res| res.unwrap()).collect().await;
+        assert_eq!(chunks, vec![&b"a"[..], b"b", b"c"]);
+    }
+
+    #[tokio::test]
+    async fn switch_disposition_async() {
+        let mut chunker = ByteChunker::new(std::io::Cursor::new(b"a,b,c,d"), ",")
+            .unwrap()
+            .with_match(MatchDisposition::Prepend);
+        assert_eq!(chunker.next().await.unwrap().unwrap(), b"a");
+        assert_eq!(chunker.next().await.unwrap().unwrap(), b",b");
+        let mut chunker = chunker.with_match(MatchDisposition::Drop);
+        assert_eq!(chunker.next().await.unwrap().unwrap(), b"c");
+        let chunker = chunker
+            .with_match(MatchDisposition::Prepend)
+            .with_match(MatchDisposition::Append);
+        let rest: Vec<Vec<u8>> = chunker.map(|res| res.unwrap()).collect().await;
+        assert_eq!(rest, vec![b"d".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn skip_empty_async() {
+        let text = b",,one,,two,,";
+        let chunker = ByteChunker::new(std::io::Cursor::new(text), ",")
+            .unwrap()
+            .skip_empty();
+        let vec_vec: Vec<Vec<u8>> = chunker.map(|res| res.unwrap()).collect().await;
+
+        ref_slice_cmp(&vec_vec, &["one", "two"]);
+        assert_eq!(vec_vec.len(), 2);
+    }
+
+    /// Fails its first read, then reads from the wrapped reader.
+    struct FlakyReader<R> {
+        inner: R,
+        failed: bool,
+    }
+
+    impl<R: AsyncRead + Unpin> AsyncRead for FlakyReader<R> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            if !self.failed {
+                self.failed = true;
+                return Poll::Ready(Err(std::io::Error::other("flaky")));
+            }
+            Pin::new(&mut self.inner).poll_read(cx, buf)
+        }
+    }
+
+    #[tokio::test]
+    async fn on_error_async() {
+        for (response, expected) in [
+            (ErrorResponse::Halt, vec![None]),
+            (
+                ErrorResponse::Continue,
+                vec![None, Some(&b"one"[..]), Some(b"two")],
+            ),
+            (ErrorResponse::Ignore, vec![Some(&b"one"[..]), Some(b"two")]),
+        ] {
+            let reader = FlakyReader {
+                inner: std::io::Cursor::new(b"one,two"),
+                failed: false,
+            };
+            let chunker = ByteChunker::new(reader, ",").unwrap().on_error(response);
+            let results: Vec<Option<Vec<u8>>> = chunker.map(|res| res.ok()).collect().await;
+            let results: Vec<Option<&[u8]>> = results.iter().map(|v| v.as_deref()).collect();
+            assert_eq!(results, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn max_chunk_size_async() {
+        let text = b"short,much much longer,x";
+        let chunker = ByteChunker::new(std::io::Cursor::new(text), ",")
+            .unwrap()
+            .with_buffer_size(4)
+            .with_max_chunk_size(8)
+            .on_error(ErrorResponse::Continue);
+        let results: Vec<Result<Vec<u8>, RcErr>> = chunker.collect().await;
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap(), b"short");
+        assert!(matches!(results[1], Err(RcErr::ChunkTooLarge { .. })));
+        assert_eq!(results[2].as_ref().unwrap(), b"h longer");
+        assert_eq!(results[3].as_ref().unwrap(), b"x");
+    }
+
+    struct Lengths;
+
+    impl SimpleAdapter for Lengths {
+        type Item = usize;
+
+        fn adapt(&mut self, v: Vec<u8>) -> usize {
+            v.len()
+        }
+    }
+
+    #[tokio::test]
+    async fn max_frame_length_async() {
+        /* An endless source with no delimiters in it. */
+        let mut chunker = ByteChunker::new(tokio::io::repeat(b'x'), ",")
+            .unwrap()
+            .with_buffer_size(64)
+            .with_max_frame_length(1024);
+        match chunker.next().await {
+            Some(Err(RcErr::ChunkTooLarge { size })) => assert!(size > 1024 && size <= 4096),
+            x => panic!("expected ChunkTooLarge, got {:?}", x),
+        }
+        assert!(chunker.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn debug_async() {
+        /* A source type that isn't `Debug` itself. */
+        struct Opaque(std::io::Cursor<Vec<u8>>);
+        impl AsyncRead for Opaque {
+            fn poll_read(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &mut tokio::io::ReadBuf<'_>,
+            ) -> Poll<std::io::Result<()>> {
+                Pin::new(&mut self.0).poll_read(cx, buf)
+            }
+        }
+
+        let text = format!("first,{}", "x".repeat(10_000));
+        let mut chunker = ByteChunker::new(Opaque(std::io::Cursor::new(text.into_bytes())), ",")
+            .unwrap()
+            .with_match(MatchDisposition::Prepend);
+        assert_eq!(chunker.next().await.unwrap().unwrap(), b"first");
+
+        let debug = format!("{:?}", chunker);
+        assert!(debug.contains("Prepend"));
+        assert!(debug.contains("scan_offset: 1"));
+        assert!(debug.contains(&format!("buffer_preview: \",{}\"", "x".repeat(63))));
+        assert!(debug.len() < 1024);
+    }
+
+    #[tokio::test]
+    async fn buffered_len_async() {
+        let mut chunker = ByteChunker::new(std::io::Cursor::new(b"one;;two;;three"), ";;")
+            .unwrap()
+            .with_match(MatchDisposition::Prepend);
+        assert_eq!(chunker.next().await.unwrap().unwrap(), b"one");
+        assert_eq!(chunker.buffered_len(), 12);
+        assert_eq!(chunker.scan_offset(), 2);
+    }
+
+    #[tokio::test]
+    async fn simple_adapter_async() {
+        let text = b"a,bb,ccc";
+        let lengths: Vec<usize> = ByteChunker::new(std::io::Cursor::new(text), ",")
+            .unwrap()
+            .with_simple_adapter(Lengths)
+            .map(|res| res.unwrap())
+            .collect()
+            .await;
+        assert_eq!(lengths, vec![1, 2, 3]);
+
+        let reader = FlakyReader {
+            inner: std::io::Cursor::new(text),
+            failed: false,
+        };
+        let mut chunker = ByteChunker::new(reader, ",")
+            .unwrap()
+            .with_simple_adapter(Lengths);
+        assert!(chunker.next().await.unwrap().is_err());
+        assert!(chunker.next().await.is_none());
+    }
+
+    /// Reads only one byte at a time from the wrapped reader.
+    struct Trickle<R>(R);
+
+    impl<R: AsyncRead + Unpin> AsyncRead for Trickle<R> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let mut byte = [0u8; 1];
+            let mut one = tokio::io::ReadBuf::new(&mut byte[..buf.remaining().min(1)]);
+            match Pin::new(&mut self.0).poll_read(cx, &mut one) {
+                Poll::Ready(Ok(())) => {
+                    buf.put_slice(one.filled());
+                    Poll::Ready(Ok(()))
+                }
+                p => p,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn boundary_matches_async() {
+        let text = b"one\r\n\r\ntwo\n\nthree\r\n\n";
+        let reader = tokio::io::BufReader::with_capacity(1, std::io::Cursor::new(text));
+        let chunker = ByteChunker::new(reader, r"\r?\n\r?\n").unwrap();
+        let vec_vec: Vec<Vec<u8>> = chunker.map(|res| res.unwrap()).collect().await;
+
+        ref_slice_cmp(&vec_vec, &["one", "two", "three"]);
+        assert_eq!(vec_vec.len(), 3);
+
+        /* An assertion at the end of what's been read waits for more. */
+        let reader = Trickle(std::io::Cursor::new(b"xa-ab-c"));
+        let chunker = ByteChunker::new(reader, r"a\b").unwrap();
+        let vec_vec: Vec<Vec<u8>> = chunker.map(|res| res.unwrap()).collect().await;
+        ref_slice_cmp(&vec_vec, &["x", "-ab-c"]);
+        assert_eq!(vec_vec.len(), 2);
+    }
+
     #[tokio::test]
     async fn slow_async() {
         let byte_vec = std::fs::read(TEST_PATH).unwrap();
@@ -242,4 +1169,116 @@ mod tests {
 
         ref_slice_cmp(&vec_vec, &slice_vec);
     }
+
+    #[tokio::test]
+    async fn counts_async() {
+        let text = b"a,bb,,ccc";
+        let mut chunker = ByteChunker::new(std::io::Cursor::new(text), ",").unwrap();
+        while let Some(res) = chunker.next().await {
+            res.unwrap();
+        }
+        assert_eq!(chunker.chunk_count(), 4);
+        assert_eq!(chunker.byte_count(), 6);
+    }
+
+    #[tokio::test]
+    async fn into_innards_async() {
+        let text = b"one\ntwo\nthree\nfour";
+        for size in [1, 6, 64] {
+            let mut chunker = ByteChunker::new(std::io::Cursor::new(text), "\n")
+                .unwrap()
+                .with_buffer_size(size);
+            assert_eq!(chunker.next().await.unwrap().unwrap(), b"one");
+            assert!(chunker.get_ref().position() > 3);
+            let (source, buffered) = chunker.into_innards();
+            let mut rest = buffered.to_vec();
+            rest.extend_from_slice(&text[source.position() as usize..]);
+            assert_eq!(rest, b"two\nthree\nfour");
+        }
+    }
+
+    #[tokio::test]
+    async fn into_stream() {
+        let text = "a,b,,c".repeat(50);
+        let expected: Vec<Vec<u8>> = crate::ByteChunker::new(std::io::Cursor::new(&text), ",")
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let chunks: Vec<Vec<u8>> = crate::ByteChunker::new(std::io::Cursor::new(text), ",")
+            .unwrap()
+            .into_stream()
+            .map(|res| res.unwrap())
+            .collect()
+            .await;
+        assert_eq!(chunks, expected);
+
+        /* Dropping the stream early stops the blocking task. */
+        let f = std::fs::File::open(TEST_PATH).unwrap();
+        let mut stream = crate::ByteChunker::new(f, TEST_PATT).unwrap().into_stream();
+        assert!(stream.next().await.unwrap().is_ok());
+        drop(stream);
+    }
+
+    #[tokio::test]
+    async fn group_adapter_async() {
+        let text = b"a\nb\n\nc";
+        let groups: Vec<Vec<u8>> = ByteChunker::new(std::io::Cursor::new(text), "\n")
+            .unwrap()
+            .with_adapter(crate::GroupAdapter::new(|line: &[u8]| line.is_empty()))
+            .map(|res| res.unwrap())
+            .collect()
+            .await;
+        ref_slice_cmp(&groups, &["ab", "c"]);
+        assert_eq!(groups.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn join_split_chars_async() {
+        /* A character cut short by a delimiter isn't joined with the
+        start of the next chunk. */
+        let text = b"caf\xc3;\xa9";
+        let adapter = crate::StringAdapter::default().with_replacement("?");
+        let mut chunker = ByteChunker::new(std::io::Cursor::new(text), ";")
+            .unwrap()
+            .with_adapter(adapter.join_split_chars());
+        let mut strings = Vec::new();
+        while let Some(res) = chunker.next().await {
+            strings.push(res.unwrap());
+        }
+        assert_eq!(strings, vec!["caf?", "?"]);
+
+        let (chunker, _) = chunker.into_innards();
+        assert!(!chunker.freader.decoder().track_delims);
+    }
+
+    #[tokio::test]
+    async fn trailing_async() {
+        let text = b"a;b;tail";
+        for (policy, expected) in [
+            (
+                TrailingPolicy::Emit,
+                vec![Ok(b"a".to_vec()), Ok(b"b".to_vec()), Ok(b"tail".to_vec())],
+            ),
+            (
+                TrailingPolicy::Discard,
+                vec![Ok(b"a".to_vec()), Ok(b"b".to_vec())],
+            ),
+            (
+                TrailingPolicy::Error,
+                vec![
+                    Ok(b"a".to_vec()),
+                    Ok(b"b".to_vec()),
+                    Err(RcErr::UnterminatedTail(b"tail".to_vec())),
+                ],
+            ),
+        ] {
+            let chunks: Vec<Result<Vec<u8>, RcErr>> =
+                ByteChunker::new(std::io::Cursor::new(text), ";")
+                    .unwrap()
+                    .with_trailing(policy)
+                    .collect()
+                    .await;
+            assert_eq!(chunks, expected);
+        }
+    }
 }
@@ -0,0 +1,51 @@
+/*!
+Utility functions for working with chunks.
+*/
+
+/**
+Join `chunks` back together into one `Vec`, with `sep` between each
+chunk and the next (but not before the first or after the last).
+
+This undoes chunking. In
+[`MatchDisposition::Append`](crate::MatchDisposition::Append) and
+[`MatchDisposition::Prepend`](crate::MatchDisposition::Prepend) modes
+the delimiters are kept in the chunks, so rejoining them with an empty
+separator reconstructs the source exactly. In the default
+[`MatchDisposition::Drop`](crate::MatchDisposition::Drop) mode the
+delimiters are gone, so this can only reconstruct the source if every
+delimiter was the same string, and then only up to a delimiter at the
+very end, which doesn't leave a trailing chunk to put one before.
+
+```rust
+# use regex_chunker::RcErr;
+use regex_chunker::{rejoin, ByteChunker, MatchDisposition};
+use std::io::Cursor;
+
+let text = b"one\r\ntwo\nthree\n";
+
+let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), r"\r?\n")?
+    .with_match(MatchDisposition::Append)
+    .collect::<Result<_, _>>()?;
+assert_eq!(&rejoin(&chunks, b""), text);
+
+// The delimiters are dropped, and the line endings normalized.
+let chunks: Vec<Vec<u8>> = ByteChunker::new(Cursor::new(text), r"\r?\n")?
+    .collect::<Result<_, _>>()?;
+assert_eq!(&rejoin(chunks, b"\n"), b"one\ntwo\nthree");
+# Ok::<(), RcErr>(())
+```
+*/
+pub fn rejoin<I>(chunks: I, sep: &[u8]) -> Vec<u8>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let mut joined = Vec::new();
+    for (n, chunk) in chunks.into_iter().enumerate() {
+        if n > 0 {
+            joined.extend_from_slice(sep);
+        }
+        joined.extend_from_slice(chunk.as_ref());
+    }
+    joined
+}